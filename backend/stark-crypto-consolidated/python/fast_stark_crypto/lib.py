@@ -1,29 +1,371 @@
+import json
+from typing import Any, Callable
+
 from fast_stark_crypto.fast_stark_crypto import (
+    rs_set_strict_hex,
+    rs_set_hex_output_mode,
+    rs_configure_asset_encoding,
+    rs_felt_eq,
+    rs_felt_cmp,
+    rs_felt_ct_eq,
+    rs_is_valid_felt,
+    rs_felt_byte_length,
     rs_get_public_key,
+    rs_public_key_x,
+    rs_public_key_parity,
+    rs_get_compressed_public_keys,
+    rs_get_public_keys_map,
+    rs_ec_generator,
+    rs_ec_mul,
+    rs_ec_add,
     rs_compute_pedersen_hash,
+    rs_pedersen_with_zero,
+    rs_hash2,
+    rs_pedersen_verify_proof,
+    rs_hash_config_fingerprint,
     rs_sign_message,
+    rs_sign_message_checked,
+    rs_sign_message_with_hash,
+    rs_sign_message_padded,
+    rs_register_key,
+    rs_sign_with_handle,
+    rs_drop_key,
+    rs_sign_with_key_shares,
+    rs_sign_message_with_external_nonce,
+    rs_sign_pairs,
+    rs_sign_pairs_with_progress,
+    rs_sign_file,
+    rs_sign_for_invoke,
+    rs_signature_to_calldata,
     rs_verify_signature,
+    rs_verify_signature_int,
+    rs_verify_hash_signature,
+    rs_verify_signature_detailed,
+    rs_verify_signature_canonical,
+    rs_verify_signature_timing_safe,
+    rs_signature_r_on_curve,
+    rs_signature_range_check,
+    VerifyResult,
+    Felt,
+    SigningError,
+    rs_verify_signature_strict_encoding,
+    rs_invalid_signature_indices,
+    rs_verify_any,
+    rs_verify_multisig,
     rs_get_order_msg,
+    rs_get_order_msg_sided,
+    rs_get_order_msg_scaled,
+    rs_get_order_msg_int,
+    rs_get_order_msg_preparsed,
+    rs_order_field_sensitivity,
+    rs_get_order_msg_fee_rate,
+    rs_asset_id_from_int,
+    rs_asset_id_to_int,
+    rs_get_order_hashes,
+    rs_hash_order_batch,
+    rs_validate_order_batch,
+    rs_sum_order_notionals,
+    rs_reexpire_order,
+    rs_migrate_orders,
+    rs_verify_order_signature,
+    rs_verify_order_concat_sig,
+    rs_order_fingerprint,
+    rs_verify_order_batch,
+    rs_signed_order_to_bytes,
+    rs_signed_order_from_bytes,
+    rs_build_signed_order_payload,
+    rs_compute_domain_hash,
+    rs_encode_short_string,
+    rs_decode_short_string,
     rs_get_transfer_msg,
+    rs_get_transfer_msg_int,
+    rs_order_field_names,
+    rs_transfer_field_names,
+    rs_hash_message,
+    rs_orders_matchable,
+    rs_orders_mismatch_reason,
+    rs_commit_position,
+    rs_commit_collateral,
+    rs_verify_collateral_commitment,
+    rs_commit_order_batch,
+    rs_open_order_batch_commitment,
+    rs_verify_position_commitment,
+    rs_derive_viewing_key,
+    rs_derive_subaccount_key,
+    rs_eip712_hash,
+    rs_generate_keypair,
+    rs_generate_timestamped_salt,
+    rs_salt_timestamp,
+    rs_test_keypairs,
     rs_generate_keypair_from_eth_signature,
+    rs_generate_keypair_from_eth_signature_checked,
+    rs_compute_multicall_hash,
+    rs_compute_invoke_tx_hash_v3,
+    rs_compute_account_address,
+    rs_get_selector_from_name,
+    rs_set_max_batch_size,
 )
 
 
+def set_strict_hex(strict: bool) -> None:
+    rs_set_strict_hex(strict)
+
+
+def set_hex_output_mode(mode: str) -> None:
+    rs_set_hex_output_mode(mode)
+
+
+def configure_asset_encoding(shift: int = 0, mask: int | None = None) -> None:
+    rs_configure_asset_encoding(shift, None if mask is None else hex(mask))
+
+def felt_eq(a: str | int, b: str | int) -> bool:
+    return rs_felt_eq(str(a), str(b))
+
+
+def felt_cmp(a: str | int, b: str | int) -> int:
+    return rs_felt_cmp(str(a), str(b))
+
+
+def felt_ct_eq(a: int, b: int) -> bool:
+    return rs_felt_ct_eq(hex(a), hex(b))
+
+
+def is_valid_felt(value: str) -> bool:
+    return rs_is_valid_felt(value)
+
+def felt_byte_length(value: int) -> int:
+    return rs_felt_byte_length(hex(value))
+
 def get_public_key(private_key: int) -> int:
     return int(rs_get_public_key(hex(private_key)))
 
 
+def get_public_key_x(private_key: int) -> int:
+    return int(rs_public_key_x(hex(private_key)), 16)
+
+
+def get_public_key_parity(private_key: int) -> int:
+    return rs_public_key_parity(hex(private_key))
+
+
+def get_compressed_public_keys(
+    private_keys: list[int], timeout_ms: int | None = None
+) -> list[tuple[int, int]]:
+    return [
+        (int(x, 16), parity)
+        for (x, parity) in rs_get_compressed_public_keys(
+            [hex(private_key) for private_key in private_keys], timeout_ms
+        )
+    ]
+
+
+def get_public_keys_map(
+    private_keys: dict[str, int], timeout_ms: int | None = None
+) -> dict[str, int]:
+    public_keys = rs_get_public_keys_map(
+        {name: hex(private_key) for (name, private_key) in private_keys.items()}, timeout_ms
+    )
+    return {name: int(public_key, 16) for (name, public_key) in public_keys.items()}
+
+
+def ec_generator() -> tuple[int, int]:
+    x, y = rs_ec_generator()
+    return int(x, 16), int(y, 16)
+
+
+def ec_mul(point: tuple[int, int], scalar: int) -> tuple[int, int]:
+    x, y = rs_ec_mul(hex(point[0]), hex(point[1]), hex(scalar))
+    return int(x, 16), int(y, 16)
+
+
+def ec_add(a: tuple[int, int], b: tuple[int, int]) -> tuple[int, int]:
+    x, y = rs_ec_add(hex(a[0]), hex(a[1]), hex(b[0]), hex(b[1]))
+    return int(x, 16), int(y, 16)
+
 def pedersen_hash(first: int, second: int) -> int:
     return int(rs_compute_pedersen_hash(hex(first), hex(second)))
 
+def pedersen_with_zero(x: int) -> int:
+    return int(rs_pedersen_with_zero(hex(x)))
+
+def hash2(algo: str, first: int, second: int) -> int:
+    return int(rs_hash2(algo, hex(first), hex(second)))
+
+def pedersen_verify_proof(leaf: int, proof: list[tuple[int, bool]], root: int) -> bool:
+    return rs_pedersen_verify_proof(
+        hex(leaf),
+        [(hex(sibling), sibling_is_left) for (sibling, sibling_is_left) in proof],
+        hex(root),
+    )
+
+def hash_config_fingerprint() -> int:
+    return int(rs_hash_config_fingerprint(), 16)
+
 def sign(private_key: int, msg_hash: int) -> tuple[int, int]:
     (r, s) = rs_sign_message(hex(private_key), hex(msg_hash))
     return (int(r), int(s))
 
+def sign_checked(private_key: int, msg_hash: int) -> tuple[int, int]:
+    (r, s) = rs_sign_message_checked(hex(private_key), hex(msg_hash))
+    return (int(r), int(s))
+
+
+def register_key(private_key: int) -> int:
+    return rs_register_key(hex(private_key))
+
+
+def sign_with_handle(handle: int, msg_hash: int) -> tuple[int, int]:
+    (r, s) = rs_sign_with_handle(handle, hex(msg_hash))
+    return (int(r), int(s))
+
+
+def drop_key(handle: int) -> bool:
+    return rs_drop_key(handle)
+
+
+def sign_with_key_shares(share_a: int, share_b: int, msg_hash: int) -> tuple[int, int]:
+    (r, s) = rs_sign_with_key_shares(hex(share_a), hex(share_b), hex(msg_hash))
+    return (int(r), int(s))
+
+
+def sign_with_hash(private_key: int, msg_hash: int) -> tuple[int, int, int]:
+    (hash, r, s) = rs_sign_message_with_hash(hex(private_key), hex(msg_hash))
+    return (int(hash, 16), int(r, 16), int(s, 16))
+
+
+def sign_padded(private_key: int, msg_hash: int) -> tuple[str, str]:
+    return rs_sign_message_padded(hex(private_key), hex(msg_hash))
+
+def sign_with_external_nonce(private_key: int, msg_hash: int, nonce: bytes) -> tuple[int, int, int]:
+    (r, s, r_x) = rs_sign_message_with_external_nonce(hex(private_key), hex(msg_hash), "0x" + nonce.hex())
+    return (int(r, 16), int(s, 16), int(r_x, 16))
+
+def sign_pairs(pairs: list[tuple[int, int]], timeout_ms: int | None = None) -> list[tuple[int, int]]:
+    signatures = rs_sign_pairs(
+        [(hex(private_key), hex(msg_hash)) for (private_key, msg_hash) in pairs],
+        timeout_ms,
+    )
+    return [(int(r), int(s)) for (r, s) in signatures]
+
+def sign_pairs_with_progress(
+    pairs: list[tuple[int, int]],
+    progress: Callable[[int], None],
+    chunk_size: int = 1024,
+) -> list[tuple[int, int]]:
+    signatures = rs_sign_pairs_with_progress(
+        [(hex(private_key), hex(msg_hash)) for (private_key, msg_hash) in pairs],
+        progress,
+        chunk_size,
+    )
+    return [(int(r), int(s)) for (r, s) in signatures]
+
+def sign_file(input_path: str, output_path: str, private_key: int) -> int:
+    return rs_sign_file(input_path, output_path, hex(private_key))
+
+def sign_for_invoke(private_key: int, tx_hash: int, recoverable: bool = False) -> list[int]:
+    return [int(felt, 16) for felt in rs_sign_for_invoke(hex(tx_hash), hex(private_key), recoverable)]
+
+
+def signature_to_calldata(r: int, s: int, style: str = "plain") -> list[int]:
+    return [int(felt, 16) for felt in rs_signature_to_calldata(hex(r), hex(s), style)]
 
 def verify(public_key: int, msg_hash: int, r: int, s: int) -> bool:
     return bool(rs_verify_signature(hex(public_key), hex(msg_hash), hex(r), hex(s)) == True)
 
+def verify_int(public_key: int, msg_hash: int, r: int, s: int) -> bool:
+    return rs_verify_signature_int(public_key, msg_hash, r, s)
+
+def verify_hash_signature(msg_hash: int, r: int, s: int, public_key: int) -> bool:
+    return rs_verify_hash_signature(hex(msg_hash), hex(r), hex(s), hex(public_key))
+
+def verify_detailed(public_key: int, msg_hash: int, r: int, s: int) -> VerifyResult:
+    return rs_verify_signature_detailed(hex(public_key), hex(msg_hash), hex(r), hex(s))
+
+def verify_canonical(public_key: int, msg_hash: int, r: int, s: int) -> VerifyResult:
+    return rs_verify_signature_canonical(hex(public_key), hex(msg_hash), hex(r), hex(s))
+
+def verify_timing_safe(public_key: int, msg_hash: int, r: int, s: int) -> bool:
+    return rs_verify_signature_timing_safe(hex(public_key), hex(msg_hash), hex(r), hex(s))
+
+def signature_r_on_curve(r: int) -> bool:
+    return rs_signature_r_on_curve(hex(r))
+
+def signature_range_check(r: int, s: int) -> str:
+    return rs_signature_range_check(hex(r), hex(s))
+
+def verify_strict_encoding(public_key: int, msg_hash: int, r: bytes, s: bytes) -> bool:
+    return rs_verify_signature_strict_encoding(hex(public_key), hex(msg_hash), r, s)
+
+def invalid_signature_indices(
+    items: list[tuple[int, int, int, int]], timeout_ms: int | None = None
+) -> list[int]:
+    return rs_invalid_signature_indices(
+        [(hex(public_key), hex(msg_hash), hex(r), hex(s)) for (public_key, msg_hash, r, s) in items],
+        timeout_ms,
+    )
+
+
+def verify_any(public_keys: list[int], msg_hash: int, r: int, s: int) -> int | None:
+    return rs_verify_any([hex(public_key) for public_key in public_keys], hex(msg_hash), hex(r), hex(s))
+
+def verify_multisig(msg_hash: int, signers: list[tuple[int, int, int]], threshold: int) -> bool:
+    return rs_verify_multisig(hex(msg_hash), [(hex(public_key), hex(r), hex(s)) for (public_key, r, s) in signers], threshold)
+
+def commit_position(position_id: int, blinding: int) -> int:
+    return int(rs_commit_position(str(position_id), hex(blinding)), 16)
+
+
+def verify_position_commitment(position_id: int, blinding: int, commitment: int) -> bool:
+    return rs_verify_position_commitment(str(position_id), hex(blinding), hex(commitment))
+
+
+def commit_collateral(
+    position_id: int, collateral_asset_id: int, balance: int, blinding: int
+) -> int:
+    return int(
+        rs_commit_collateral(
+            str(position_id), hex(collateral_asset_id), str(balance), hex(blinding)
+        ),
+        16,
+    )
+
+
+def verify_collateral_commitment(
+    position_id: int, collateral_asset_id: int, balance: int, blinding: int, commitment: int
+) -> bool:
+    return rs_verify_collateral_commitment(
+        str(position_id), hex(collateral_asset_id), str(balance), hex(blinding), hex(commitment)
+    )
+
+
+def derive_viewing_key(private_key: int) -> int:
+    return int(rs_derive_viewing_key(hex(private_key)), 16)
+
+def derive_subaccount_key(master_private_key: int, index: int) -> tuple[int, int]:
+    (priv, pub) = rs_derive_subaccount_key(hex(master_private_key), index)
+    return (int(priv, 16), int(pub, 16))
+
+def eip712_hash(domain: dict[str, Any], types: dict[str, Any], message: dict[str, Any]) -> int:
+    return int(rs_eip712_hash(json.dumps(domain), json.dumps(types), json.dumps(message)), 16)
+
+
+def generate_keypair() -> tuple[int, int]:
+    (priv, pub) = rs_generate_keypair()
+    return (int(priv, 16), int(pub, 16))
+
+
+def generate_timestamped_salt(timestamp: int) -> int:
+    return rs_generate_timestamped_salt(timestamp)
+
+
+def salt_timestamp(salt: int) -> int:
+    return rs_salt_timestamp(salt)
+
+
+def get_test_keypairs(count: int) -> list[tuple[int, int]]:
+    return [(int(priv, 16), int(pub, 16)) for (priv, pub) in rs_test_keypairs(count)]
+
+
 def generate_keypair_from_eth_signature(
     eth_signature: str,
 ) -> tuple[int, int]:
@@ -31,6 +373,21 @@ def generate_keypair_from_eth_signature(
     return (int(priv, 16), int(pub, 16))
 
 
+def generate_keypair_from_eth_signature_checked(
+    eth_signature: str,
+    message_hash: str,
+    expected_address: str,
+) -> tuple[int, int]:
+    (priv, pub) = rs_generate_keypair_from_eth_signature_checked(eth_signature, message_hash, expected_address)
+    return (int(priv, 16), int(pub, 16))
+
+def order_field_names() -> list[str]:
+    return rs_order_field_names()
+
+
+def transfer_field_names() -> list[str]:
+    return rs_transfer_field_names()
+
 def get_order_msg_hash(
     position_id: int,
     base_asset_id: int,
@@ -46,6 +403,12 @@ def get_order_msg_hash(
     domain_version: str,
     domain_chain_id: str,
     domain_revision: str,
+    allow_same_assets: bool = False,
+    reduce_only: bool = False,
+    parent_order_hash: int | None = None,
+    post_only: bool = False,
+    leverage: int | None = None,
+    audit_tag: int | None = None,
 ) -> int:
     return int(
         rs_get_order_msg(
@@ -63,16 +426,59 @@ def get_order_msg_hash(
             domain_version,
             domain_chain_id,
             domain_revision,
+            allow_same_assets,
+            reduce_only,
+            None if parent_order_hash is None else hex(parent_order_hash),
+            post_only,
+            None if leverage is None else str(leverage),
+            None if audit_tag is None else hex(audit_tag),
         ),
         16,
     )
 
 
-def get_transfer_msg_hash(
-    recipient_position_id: int,
-    sender_position_id: int,
-    collateral_id: int,
-    amount: int,
+def get_order_msg_hash_int(
+    position_id: int,
+    base_asset_id: int,
+    base_amount: int,
+    quote_asset_id: int,
+    quote_amount: int,
+    fee_asset_id: int,
+    fee_amount: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> int:
+    return rs_get_order_msg_int(
+        position_id,
+        base_asset_id,
+        base_amount,
+        quote_asset_id,
+        quote_amount,
+        fee_asset_id,
+        fee_amount,
+        expiration,
+        salt,
+        user_public_key,
+        domain_name,
+        domain_version,
+        domain_chain_id,
+        domain_revision,
+    )
+
+
+def get_order_msg_hash_preparsed(
+    position_id: int,
+    base_asset_id: Felt,
+    base_amount: int,
+    quote_asset_id: Felt,
+    quote_amount: int,
+    fee_asset_id: Felt,
+    fee_amount: int,
     expiration: int,
     salt: int,
     user_public_key: int,
@@ -82,11 +488,168 @@ def get_transfer_msg_hash(
     domain_revision: str,
 ) -> int:
     return int(
-        rs_get_transfer_msg(
-            str(recipient_position_id),
-            str(sender_position_id),
-            hex(collateral_id),
-            str(amount),
+        rs_get_order_msg_preparsed(
+            str(position_id),
+            base_asset_id,
+            str(base_amount),
+            quote_asset_id,
+            str(quote_amount),
+            fee_asset_id,
+            str(fee_amount),
+            str(expiration),
+            str(salt),
+            hex(user_public_key),
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            domain_revision,
+        ),
+        16,
+    )
+
+
+def order_field_sensitivity(
+    position_id: int,
+    base_asset_id: int,
+    base_amount: int,
+    quote_asset_id: int,
+    quote_amount: int,
+    fee_asset_id: int,
+    fee_amount: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> tuple[int, list[tuple[str, int]]]:
+    (baseline, sensitivity) = rs_order_field_sensitivity(
+        str(position_id),
+        hex(base_asset_id),
+        str(base_amount),
+        hex(quote_asset_id),
+        str(quote_amount),
+        hex(fee_asset_id),
+        str(fee_amount),
+        str(expiration),
+        str(salt),
+        hex(user_public_key),
+        domain_name,
+        domain_version,
+        domain_chain_id,
+        domain_revision,
+    )
+    return (int(baseline, 16), [(field, int(msg_hash, 16)) for (field, msg_hash) in sensitivity])
+
+
+def get_order_msg_hash_sided(
+    position_id: int,
+    base_asset_id: int,
+    base_side: str,
+    base_abs_amount: int,
+    quote_asset_id: int,
+    quote_side: str,
+    quote_abs_amount: int,
+    fee_asset_id: int,
+    fee_amount: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> int:
+    return int(
+        rs_get_order_msg_sided(
+            str(position_id),
+            hex(base_asset_id),
+            base_side,
+            str(base_abs_amount),
+            hex(quote_asset_id),
+            quote_side,
+            str(quote_abs_amount),
+            hex(fee_asset_id),
+            str(fee_amount),
+            str(expiration),
+            str(salt),
+            hex(user_public_key),
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            domain_revision,
+        ),
+        16,
+    )
+
+def get_order_msg_hash_scaled(
+    position_id: int,
+    base_asset_id: int,
+    base_human_amount: str,
+    base_quantum: int,
+    quote_asset_id: int,
+    quote_human_amount: str,
+    quote_quantum: int,
+    fee_asset_id: int,
+    fee_human_amount: str,
+    fee_quantum: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> int:
+    return int(
+        rs_get_order_msg_scaled(
+            str(position_id),
+            hex(base_asset_id),
+            base_human_amount,
+            str(base_quantum),
+            hex(quote_asset_id),
+            quote_human_amount,
+            str(quote_quantum),
+            hex(fee_asset_id),
+            fee_human_amount,
+            str(fee_quantum),
+            str(expiration),
+            str(salt),
+            hex(user_public_key),
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            domain_revision,
+        ),
+        16,
+    )
+
+def get_order_msg_hash_fee_rate(
+    position_id: int,
+    base_asset_id: int,
+    base_amount: int,
+    quote_asset_id: int,
+    quote_amount: int,
+    fee_asset_id: int,
+    fee_rate_bps: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> int:
+    return int(
+        rs_get_order_msg_fee_rate(
+            str(position_id),
+            hex(base_asset_id),
+            str(base_amount),
+            hex(quote_asset_id),
+            str(quote_amount),
+            hex(fee_asset_id),
+            str(fee_rate_bps),
             str(expiration),
             str(salt),
             hex(user_public_key),
@@ -97,3 +660,703 @@ def get_transfer_msg_hash(
         ),
         16,
     )
+
+def get_order_msg_hash_with_fees(
+    position_id: int,
+    base_asset_id: int,
+    base_amount: int,
+    quote_asset_id: int,
+    quote_amount: int,
+    fee_asset_id: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+    maker_fee_amount: int = 0,
+    taker_fee_amount: int = 0,
+) -> int:
+    # Only built with the draft-layouts feature
+    from fast_stark_crypto.fast_stark_crypto import rs_get_order_msg_with_fees
+
+    return int(
+        rs_get_order_msg_with_fees(
+            str(position_id),
+            hex(base_asset_id),
+            str(base_amount),
+            hex(quote_asset_id),
+            str(quote_amount),
+            hex(fee_asset_id),
+            str(expiration),
+            str(salt),
+            hex(user_public_key),
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            domain_revision,
+            str(maker_fee_amount),
+            str(taker_fee_amount),
+        ),
+        16,
+    )
+
+def asset_id_from_int(int_id: int, shift: int, tag: int) -> int:
+    return int(rs_asset_id_from_int(str(int_id), {"shift": shift, "tag": hex(tag)}), 16)
+
+def asset_id_to_int(asset_id: int, shift: int, tag: int) -> int:
+    return int(rs_asset_id_to_int(hex(asset_id), {"shift": shift, "tag": hex(tag)}))
+
+def compute_domain_hash(
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> int:
+    return int(
+        rs_compute_domain_hash(domain_name, domain_version, domain_chain_id, domain_revision),
+        16,
+    )
+
+
+def encode_short_string(value: str) -> int:
+    return int(rs_encode_short_string(value), 16)
+
+
+def decode_short_string(felt: int) -> str:
+    return rs_decode_short_string(hex(felt))
+
+def get_transfer_msg_hash_int(
+    recipient_position_id: int,
+    sender_position_id: int,
+    collateral_id: int,
+    amount: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> int:
+    return rs_get_transfer_msg_int(
+        recipient_position_id,
+        sender_position_id,
+        collateral_id,
+        amount,
+        expiration,
+        salt,
+        user_public_key,
+        domain_name,
+        domain_version,
+        domain_chain_id,
+        domain_revision,
+    )
+
+def get_transfer_msg_hash(
+    recipient_position_id: int,
+    sender_position_id: int,
+    collateral_id: int,
+    amount: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+    owner_address: int | None = None,
+    min_expiration: int | None = None,
+    max_expiration: int | None = None,
+    nonce: int | None = None,
+    memo: int | None = None,
+) -> int:
+    return int(
+        rs_get_transfer_msg(
+            str(recipient_position_id),
+            str(sender_position_id),
+            hex(collateral_id),
+            str(amount),
+            str(expiration),
+            str(salt),
+            hex(user_public_key),
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            domain_revision,
+            None if owner_address is None else hex(owner_address),
+            None if min_expiration is None else str(min_expiration),
+            None if max_expiration is None else str(max_expiration),
+            None if nonce is None else str(nonce),
+            None if memo is None else hex(memo),
+        ),
+        16,
+    )
+
+
+def get_withdrawal_to_address_msg_hash(
+    position_id: int,
+    collateral_id: int,
+    amount: int,
+    eth_address: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> int:
+    # Only built with the draft-layouts feature
+    from fast_stark_crypto.fast_stark_crypto import rs_get_withdrawal_to_address_msg
+
+    return int(
+        rs_get_withdrawal_to_address_msg(
+            str(position_id),
+            hex(collateral_id),
+            str(amount),
+            f"0x{eth_address:040x}",
+            str(expiration),
+            str(salt),
+            hex(user_public_key),
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            domain_revision,
+        ),
+        16,
+    )
+
+
+def get_cancel_all_msg_hash(
+    position_id: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> int:
+    # Only built with the draft-layouts feature
+    from fast_stark_crypto.fast_stark_crypto import rs_get_cancel_all_msg
+
+    return int(
+        rs_get_cancel_all_msg(
+            str(position_id),
+            str(expiration),
+            str(salt),
+            hex(user_public_key),
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            domain_revision,
+        ),
+        16,
+    )
+
+
+def hash_message(
+    message_type: str,
+    fields: dict[str, int],
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> int:
+    return int(
+        rs_hash_message(message_type, fields, domain_name, domain_version, domain_chain_id, domain_revision),
+        16,
+    )
+
+
+def get_settlement_msg_hash(
+    order_a: dict[str, int],
+    order_b: dict[str, int],
+    actual_a_amount: int,
+    actual_b_amount: int,
+    actual_a_fee: int,
+    actual_b_fee: int,
+    signer_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> int:
+    # Only built with the draft-layouts feature
+    from fast_stark_crypto.fast_stark_crypto import rs_get_settlement_msg
+
+    return int(
+        rs_get_settlement_msg(
+            order_a,
+            order_b,
+            str(actual_a_amount),
+            str(actual_b_amount),
+            str(actual_a_fee),
+            str(actual_b_fee),
+            hex(signer_public_key),
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            domain_revision,
+        ),
+        16,
+    )
+
+def orders_matchable(order_a: dict[str, int], order_b: dict[str, int]) -> bool:
+    return rs_orders_matchable(order_a, order_b)
+
+def orders_mismatch_reason(order_a: dict[str, int], order_b: dict[str, int]) -> str | None:
+    return rs_orders_mismatch_reason(order_a, order_b)
+
+def get_order_hashes(
+    position_id: int,
+    base_asset_id: int,
+    base_amount: int,
+    quote_asset_id: int,
+    quote_amount: int,
+    fee_asset_id: int,
+    fee_amount: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> tuple[int, int]:
+    (struct_hash, message_hash) = rs_get_order_hashes(
+        str(position_id),
+        hex(base_asset_id),
+        str(base_amount),
+        hex(quote_asset_id),
+        str(quote_amount),
+        hex(fee_asset_id),
+        str(fee_amount),
+        str(expiration),
+        str(salt),
+        hex(user_public_key),
+        domain_name,
+        domain_version,
+        domain_chain_id,
+        domain_revision,
+    )
+    return (int(struct_hash, 16), int(message_hash, 16))
+
+
+def hash_order_batch(
+    orders: list[dict[str, int]],
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+    timeout_ms: int | None = None,
+) -> tuple[list[int], int]:
+    (hashes, commitment) = rs_hash_order_batch(
+        [
+            {
+                "position_id": str(order["position_id"]),
+                "base_asset_id": hex(order["base_asset_id"]),
+                "base_amount": str(order["base_amount"]),
+                "quote_asset_id": hex(order["quote_asset_id"]),
+                "quote_amount": str(order["quote_amount"]),
+                "fee_asset_id": hex(order["fee_asset_id"]),
+                "fee_amount": str(order["fee_amount"]),
+                "expiration": str(order["expiration"]),
+                "salt": str(order["salt"]),
+                "user_public_key": hex(order["user_public_key"]),
+            }
+            for order in orders
+        ],
+        domain_name,
+        domain_version,
+        domain_chain_id,
+        domain_revision,
+        timeout_ms,
+    )
+    return ([int(h, 16) for h in hashes], int(commitment, 16))
+
+
+def commit_order_batch(
+    orders: list[dict[str, int]], blinding: int, timeout_ms: int | None = None
+) -> int:
+    return int(
+        rs_commit_order_batch(
+            [
+                {
+                    "position_id": str(order["position_id"]),
+                    "base_asset_id": hex(order["base_asset_id"]),
+                    "base_amount": str(order["base_amount"]),
+                    "quote_asset_id": hex(order["quote_asset_id"]),
+                    "quote_amount": str(order["quote_amount"]),
+                    "fee_asset_id": hex(order["fee_asset_id"]),
+                    "fee_amount": str(order["fee_amount"]),
+                    "expiration": str(order["expiration"]),
+                    "salt": str(order["salt"]),
+                    "user_public_key": hex(order["user_public_key"]),
+                }
+                for order in orders
+            ],
+            hex(blinding),
+            timeout_ms,
+        ),
+        16,
+    )
+
+def open_order_batch_commitment(
+    orders: list[dict[str, int]],
+    blinding: int,
+    commitment: int,
+    timeout_ms: int | None = None,
+) -> bool:
+    return rs_open_order_batch_commitment(
+        [
+            {
+                "position_id": str(order["position_id"]),
+                "base_asset_id": hex(order["base_asset_id"]),
+                "base_amount": str(order["base_amount"]),
+                "quote_asset_id": hex(order["quote_asset_id"]),
+                "quote_amount": str(order["quote_amount"]),
+                "fee_asset_id": hex(order["fee_asset_id"]),
+                "fee_amount": str(order["fee_amount"]),
+                "expiration": str(order["expiration"]),
+                "salt": str(order["salt"]),
+                "user_public_key": hex(order["user_public_key"]),
+            }
+            for order in orders
+        ],
+        hex(blinding),
+        hex(commitment),
+        timeout_ms,
+    )
+
+def validate_order_batch(orders: list[dict[str, int]]) -> list[tuple[int, str]]:
+    return rs_validate_order_batch(
+        [
+            {
+                "position_id": str(order["position_id"]),
+                "base_asset_id": hex(order["base_asset_id"]),
+                "base_amount": str(order["base_amount"]),
+                "quote_asset_id": hex(order["quote_asset_id"]),
+                "quote_amount": str(order["quote_amount"]),
+                "fee_asset_id": hex(order["fee_asset_id"]),
+                "fee_amount": str(order["fee_amount"]),
+                "expiration": str(order["expiration"]),
+                "salt": str(order["salt"]),
+                "user_public_key": hex(order["user_public_key"]),
+            }
+            for order in orders
+        ]
+    )
+
+def sum_order_notionals(orders: list[dict[str, int]]) -> int:
+    return int(
+        rs_sum_order_notionals(
+            [
+                {
+                    "position_id": str(order["position_id"]),
+                    "base_asset_id": hex(order["base_asset_id"]),
+                    "base_amount": str(order["base_amount"]),
+                    "quote_asset_id": hex(order["quote_asset_id"]),
+                    "quote_amount": str(order["quote_amount"]),
+                    "fee_asset_id": hex(order["fee_asset_id"]),
+                    "fee_amount": str(order["fee_amount"]),
+                    "expiration": str(order["expiration"]),
+                    "salt": str(order["salt"]),
+                    "user_public_key": hex(order["user_public_key"]),
+                }
+                for order in orders
+            ]
+        )
+    )
+
+def reexpire_order(
+    order: dict[str, int],
+    new_expiration: int,
+    private_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> tuple[int, int, int]:
+    (message_hash, r, s) = rs_reexpire_order(
+        {
+            "position_id": str(order["position_id"]),
+            "base_asset_id": hex(order["base_asset_id"]),
+            "base_amount": str(order["base_amount"]),
+            "quote_asset_id": hex(order["quote_asset_id"]),
+            "quote_amount": str(order["quote_amount"]),
+            "fee_asset_id": hex(order["fee_asset_id"]),
+            "fee_amount": str(order["fee_amount"]),
+            "expiration": str(order["expiration"]),
+            "salt": str(order["salt"]),
+            "user_public_key": hex(order["user_public_key"]),
+        },
+        str(new_expiration),
+        domain_name,
+        domain_version,
+        domain_chain_id,
+        domain_revision,
+        hex(private_key),
+    )
+    return (int(message_hash, 16), int(r, 16), int(s, 16))
+
+
+def migrate_orders(
+    orders: list[dict[str, int]],
+    old_domain: tuple[str, str, str, str],
+    new_domain: tuple[str, str, str, str],
+    private_keys: list[int],
+) -> list[tuple[int, int, int, int]]:
+    migrated = rs_migrate_orders(
+        [
+            {
+                "position_id": str(order["position_id"]),
+                "base_asset_id": hex(order["base_asset_id"]),
+                "base_amount": str(order["base_amount"]),
+                "quote_asset_id": hex(order["quote_asset_id"]),
+                "quote_amount": str(order["quote_amount"]),
+                "fee_asset_id": hex(order["fee_asset_id"]),
+                "fee_amount": str(order["fee_amount"]),
+                "expiration": str(order["expiration"]),
+                "salt": str(order["salt"]),
+                "user_public_key": hex(order["user_public_key"]),
+            }
+            for order in orders
+        ],
+        old_domain,
+        new_domain,
+        [hex(private_key) for private_key in private_keys],
+    )
+    return [
+        (int(old_hash, 16), int(new_hash, 16), int(r, 16), int(s, 16))
+        for (old_hash, new_hash, r, s) in migrated
+    ]
+
+def verify_order_signature(
+    order: dict[str, int],
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+    r: int,
+    s: int,
+) -> tuple[bool, int]:
+    (valid, message_hash) = rs_verify_order_signature(
+        {
+            "position_id": str(order["position_id"]),
+            "base_asset_id": hex(order["base_asset_id"]),
+            "base_amount": str(order["base_amount"]),
+            "quote_asset_id": hex(order["quote_asset_id"]),
+            "quote_amount": str(order["quote_amount"]),
+            "fee_asset_id": hex(order["fee_asset_id"]),
+            "fee_amount": str(order["fee_amount"]),
+            "expiration": str(order["expiration"]),
+            "salt": str(order["salt"]),
+            "user_public_key": hex(order["user_public_key"]),
+        },
+        domain_name,
+        domain_version,
+        domain_chain_id,
+        domain_revision,
+        hex(r),
+        hex(s),
+    )
+    return (valid, int(message_hash, 16))
+
+
+def verify_order_concat_sig(
+    order: dict[str, int],
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+    public_key: int,
+    sig_concat: str,
+) -> bool:
+    return rs_verify_order_concat_sig(
+        {
+            "position_id": str(order["position_id"]),
+            "base_asset_id": hex(order["base_asset_id"]),
+            "base_amount": str(order["base_amount"]),
+            "quote_asset_id": hex(order["quote_asset_id"]),
+            "quote_amount": str(order["quote_amount"]),
+            "fee_asset_id": hex(order["fee_asset_id"]),
+            "fee_amount": str(order["fee_amount"]),
+            "expiration": str(order["expiration"]),
+            "salt": str(order["salt"]),
+            "user_public_key": hex(order["user_public_key"]),
+        },
+        domain_name,
+        domain_version,
+        domain_chain_id,
+        domain_revision,
+        hex(public_key),
+        sig_concat,
+    )
+
+
+def order_fingerprint(order: dict[str, int | str]) -> int:
+    return int(rs_order_fingerprint(order), 16)
+
+
+def verify_order_batch(
+    records: list[tuple[dict[str, int], int, int, int]],
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+) -> list[bool]:
+    return rs_verify_order_batch(
+        [
+            (
+                {
+                    "position_id": str(order["position_id"]),
+                    "base_asset_id": hex(order["base_asset_id"]),
+                    "base_amount": str(order["base_amount"]),
+                    "quote_asset_id": hex(order["quote_asset_id"]),
+                    "quote_amount": str(order["quote_amount"]),
+                    "fee_asset_id": hex(order["fee_asset_id"]),
+                    "fee_amount": str(order["fee_amount"]),
+                    "expiration": str(order["expiration"]),
+                    "salt": str(order["salt"]),
+                    "user_public_key": hex(order["user_public_key"]),
+                },
+                hex(public_key),
+                hex(r),
+                hex(s),
+            )
+            for (order, public_key, r, s) in records
+        ],
+        domain_name,
+        domain_version,
+        domain_chain_id,
+        domain_revision,
+    )
+
+def signed_order_to_bytes(
+    order: dict[str, int],
+    message_hash: int,
+    r: int,
+    s: int,
+) -> bytes:
+    return rs_signed_order_to_bytes(
+        {
+            "position_id": str(order["position_id"]),
+            "base_asset_id": hex(order["base_asset_id"]),
+            "base_amount": str(order["base_amount"]),
+            "quote_asset_id": hex(order["quote_asset_id"]),
+            "quote_amount": str(order["quote_amount"]),
+            "fee_asset_id": hex(order["fee_asset_id"]),
+            "fee_amount": str(order["fee_amount"]),
+            "expiration": str(order["expiration"]),
+            "salt": str(order["salt"]),
+            "user_public_key": hex(order["user_public_key"]),
+        },
+        hex(message_hash),
+        hex(r),
+        hex(s),
+    )
+
+def signed_order_from_bytes(data: bytes) -> tuple[dict[str, int], int, int, int]:
+    (order, message_hash, r, s) = rs_signed_order_from_bytes(data)
+    return (
+        {key: int(value, 0) for (key, value) in order.items()},
+        int(message_hash, 16),
+        int(r, 16),
+        int(s, 16),
+    )
+
+def build_signed_order_payload(
+    position_id: int,
+    base_asset_id: int,
+    base_amount: int,
+    quote_asset_id: int,
+    quote_amount: int,
+    fee_asset_id: int,
+    fee_amount: int,
+    expiration: int,
+    salt: int,
+    user_public_key: int,
+    domain_name: str,
+    domain_version: str,
+    domain_chain_id: str,
+    domain_revision: str,
+    private_key: int,
+) -> str:
+    return rs_build_signed_order_payload(
+        str(position_id),
+        hex(base_asset_id),
+        str(base_amount),
+        hex(quote_asset_id),
+        str(quote_amount),
+        hex(fee_asset_id),
+        str(fee_amount),
+        str(expiration),
+        str(salt),
+        hex(user_public_key),
+        domain_name,
+        domain_version,
+        domain_chain_id,
+        domain_revision,
+        hex(private_key),
+    )
+
+def compute_multicall_hash(calls: list[tuple[int, int, list[int]]]) -> int:
+    return int(
+        rs_compute_multicall_hash(
+            [(hex(to), hex(selector), [hex(felt) for felt in calldata]) for (to, selector, calldata) in calls]
+        ),
+        16,
+    )
+
+
+def compute_invoke_tx_hash_v3(
+    sender_address: int,
+    calldata: list[int],
+    nonce: int,
+    tip: int,
+    l1_gas: tuple[int, int],
+    l2_gas: tuple[int, int],
+    l1_data_gas: tuple[int, int],
+    chain_id: str,
+) -> int:
+    return int(
+        rs_compute_invoke_tx_hash_v3(
+            hex(sender_address),
+            [hex(felt) for felt in calldata],
+            hex(nonce),
+            str(tip),
+            str(l1_gas[0]),
+            str(l1_gas[1]),
+            str(l2_gas[0]),
+            str(l2_gas[1]),
+            str(l1_data_gas[0]),
+            str(l1_data_gas[1]),
+            chain_id,
+        ),
+        16,
+    )
+
+
+def compute_account_address(
+    class_hash: int, salt: int, constructor_calldata: list[int], deployer_address: int = 0
+) -> int:
+    return int(
+        rs_compute_account_address(
+            hex(class_hash),
+            hex(salt),
+            [hex(felt) for felt in constructor_calldata],
+            hex(deployer_address),
+        ),
+        16,
+    )
+
+
+def get_selector_from_name(name: str) -> int:
+    return int(rs_get_selector_from_name(name), 16)
+
+
+def set_max_batch_size(max_batch_size: int) -> None:
+    rs_set_max_batch_size(max_batch_size)
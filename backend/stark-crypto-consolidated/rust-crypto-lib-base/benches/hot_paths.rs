@@ -0,0 +1,126 @@
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use criterion::Throughput;
+use rust_crypto_lib_base::sign_message;
+use rust_crypto_lib_base::starknet_messages::AssetId;
+use rust_crypto_lib_base::starknet_messages::OffChainMessage;
+use rust_crypto_lib_base::starknet_messages::Order;
+use rust_crypto_lib_base::starknet_messages::PositionId;
+use rust_crypto_lib_base::starknet_messages::Timestamp;
+use rust_crypto_lib_base::starknet_messages::SEPOLIA_DOMAIN;
+use starknet_crypto::get_public_key;
+use starknet_crypto::pedersen_hash;
+use starknet_crypto::poseidon_hash_many;
+use starknet_crypto::verify;
+use starknet_crypto::Felt;
+
+fn sample_order() -> Order {
+    Order {
+        position_id: PositionId { value: 100 },
+        base_asset_id: AssetId { value: Felt::TWO },
+        base_amount: 100,
+        quote_asset_id: AssetId { value: Felt::ONE },
+        quote_amount: -156,
+        fee_asset_id: AssetId { value: Felt::ONE },
+        fee_amount: 74,
+        expiration: Timestamp { seconds: 100 },
+        salt: Felt::from(123u64),
+    }
+}
+
+// Order fields in the string form the Python bindings receive them in: the
+// base, quote and fee asset ids as hex, then position id, base amount, quote
+// amount, fee amount, expiration and salt as decimals
+const ORDER_ASSET_IDS_HEX: [&str; 3] = ["0x4254432d3600000000000000000000", "0x1", "0x1"];
+const ORDER_DECIMALS: [&str; 6] = ["100", "100", "-156", "74", "100", "123"];
+
+// Builds the order from already parsed asset ids, parsing the decimal fields,
+// as both rs_get_order_msg and rs_get_order_msg_preparsed do
+fn order_from_parsed_assets(asset_ids: [Felt; 3], decimals: [&str; 6]) -> Order {
+    Order {
+        position_id: PositionId {
+            value: decimals[0].parse().unwrap(),
+        },
+        base_asset_id: AssetId {
+            value: asset_ids[0],
+        },
+        base_amount: decimals[1].parse().unwrap(),
+        quote_asset_id: AssetId {
+            value: asset_ids[1],
+        },
+        quote_amount: decimals[2].parse().unwrap(),
+        fee_asset_id: AssetId {
+            value: asset_ids[2],
+        },
+        fee_amount: decimals[3].parse().unwrap(),
+        expiration: Timestamp {
+            seconds: decimals[4].parse().unwrap(),
+        },
+        salt: Felt::from(decimals[5].parse::<u64>().unwrap()),
+    }
+}
+
+fn parse_asset_ids(asset_ids_hex: [&str; 3]) -> [Felt; 3] {
+    asset_ids_hex.map(|asset_id| Felt::from_hex(asset_id).unwrap())
+}
+
+fn bench_hot_paths(c: &mut Criterion) {
+    let private_key = Felt::from_hex_unchecked(
+        "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+    );
+    let public_key = get_public_key(&private_key);
+    let message = Felt::from_hex_unchecked(
+        "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48",
+    );
+    let signature = sign_message(&message, &private_key).unwrap();
+    let order = sample_order();
+    let felts: Vec<Felt> = (0u64..10).map(Felt::from).collect();
+
+    let mut group = c.benchmark_group("hot_paths");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("sign_message", |b| {
+        b.iter(|| sign_message(black_box(&message), black_box(&private_key)))
+    });
+    group.bench_function("verify", |b| {
+        b.iter(|| {
+            verify(
+                black_box(&public_key),
+                black_box(&message),
+                black_box(&signature.r),
+                black_box(&signature.s),
+            )
+        })
+    });
+    group.bench_function("pedersen_hash", |b| {
+        b.iter(|| pedersen_hash(black_box(&felts[0]), black_box(&felts[1])))
+    });
+    group.bench_function("poseidon_hash_many_10", |b| {
+        b.iter(|| poseidon_hash_many(black_box(&felts)))
+    });
+    group.bench_function("order_message_hash", |b| {
+        b.iter(|| black_box(&order).message_hash(&SEPOLIA_DOMAIN, black_box(public_key)))
+    });
+    // The per-order work of rs_get_order_msg, against rs_get_order_msg_preparsed
+    // with the asset ids parsed once up front. Python call overhead is not
+    // included in either
+    group.bench_function("order_message_hash_from_strings", |b| {
+        b.iter(|| {
+            let asset_ids = parse_asset_ids(black_box(ORDER_ASSET_IDS_HEX));
+            order_from_parsed_assets(asset_ids, black_box(ORDER_DECIMALS))
+                .message_hash(&SEPOLIA_DOMAIN, black_box(public_key))
+        })
+    });
+    let asset_ids = parse_asset_ids(ORDER_ASSET_IDS_HEX);
+    group.bench_function("order_message_hash_preparsed", |b| {
+        b.iter(|| {
+            order_from_parsed_assets(black_box(asset_ids), black_box(ORDER_DECIMALS))
+                .message_hash(&SEPOLIA_DOMAIN, black_box(public_key))
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_hot_paths);
+criterion_main!(benches);
@@ -0,0 +1,347 @@
+use std::collections::BTreeSet;
+
+use num_bigint::BigInt;
+use num_bigint::Sign;
+use serde_json::Map;
+use serde_json::Value;
+use sha3::Digest;
+use sha3::Keccak256;
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+// Domain fields in the order EIP-712 lists them, for when the caller doesn't
+// declare EIP712Domain in the types
+const DOMAIN_FIELDS: [(&str, &str); 5] = [
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+    ("salt", "bytes32"),
+];
+
+fn keccak(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+struct Field {
+    name: String,
+    kind: String,
+}
+
+struct TypedData<'a> {
+    types: &'a Map<String, Value>,
+}
+
+impl TypedData<'_> {
+    fn fields(&self, name: &str) -> Result<Vec<Field>, String> {
+        let fields = self
+            .types
+            .get(name)
+            .and_then(Value::as_array)
+            .ok_or_else(|| format!("Type {} is not defined as a list of fields", name))?;
+        fields
+            .iter()
+            .map(|field| {
+                let field_name = field.get("name").and_then(Value::as_str);
+                let field_kind = field.get("type").and_then(Value::as_str);
+                match (field_name, field_kind) {
+                    (Some(field_name), Some(field_kind)) => Ok(Field {
+                        name: field_name.to_string(),
+                        kind: field_kind.to_string(),
+                    }),
+                    _ => Err(format!("Malformed field {} in type {}", field, name)),
+                }
+            })
+            .collect()
+    }
+
+    fn collect_dependencies(&self, name: &str, found: &mut BTreeSet<String>) -> Result<(), String> {
+        if found.contains(name) || !self.types.contains_key(name) {
+            return Ok(());
+        }
+        found.insert(name.to_string());
+        for field in self.fields(name)? {
+            self.collect_dependencies(base_type(&field.kind), found)?;
+        }
+        Ok(())
+    }
+
+    // The primary type first, then its dependencies sorted by name
+    fn encode_type(&self, name: &str) -> Result<String, String> {
+        let mut dependencies = BTreeSet::new();
+        self.collect_dependencies(name, &mut dependencies)?;
+        dependencies.remove(name);
+        let mut encoded = String::new();
+        for type_name in std::iter::once(name).chain(dependencies.iter().map(String::as_str)) {
+            let members: Vec<String> = self
+                .fields(type_name)?
+                .iter()
+                .map(|field| format!("{} {}", field.kind, field.name))
+                .collect();
+            encoded.push_str(&format!("{}({})", type_name, members.join(",")));
+        }
+        Ok(encoded)
+    }
+
+    fn hash_struct(&self, name: &str, data: &Value) -> Result<[u8; 32], String> {
+        let mut encoded = keccak(self.encode_type(name)?.as_bytes()).to_vec();
+        for field in self.fields(name)? {
+            let value = data
+                .get(&field.name)
+                .ok_or_else(|| format!("Missing field {} of {}", field.name, name))?;
+            encoded.extend_from_slice(&self.encode_value(&field.kind, value)?);
+        }
+        Ok(keccak(&encoded))
+    }
+
+    fn encode_value(&self, kind: &str, value: &Value) -> Result<[u8; 32], String> {
+        if let Some((element_kind, _)) = kind.strip_suffix(']').and_then(|k| k.rsplit_once('[')) {
+            let elements = value
+                .as_array()
+                .ok_or_else(|| format!("Expected an array for {}", kind))?;
+            let mut encoded = Vec::with_capacity(32 * elements.len());
+            for element in elements {
+                encoded.extend_from_slice(&self.encode_value(element_kind, element)?);
+            }
+            return Ok(keccak(&encoded));
+        }
+        if self.types.contains_key(kind) {
+            return self.hash_struct(kind, value);
+        }
+        match kind {
+            "string" => Ok(keccak(expect_str(kind, value)?.as_bytes())),
+            "bytes" => Ok(keccak(&decode_hex(kind, value)?)),
+            "bool" => {
+                let flag = value
+                    .as_bool()
+                    .ok_or_else(|| format!("Expected a bool, got {}", value))?;
+                Ok(left_pad(&[flag as u8]))
+            }
+            "address" => {
+                let bytes = decode_hex(kind, value)?;
+                if bytes.len() != 20 {
+                    return Err(format!("Address {} is not 20 bytes", value));
+                }
+                Ok(left_pad(&bytes))
+            }
+            _ => {
+                if let Some(width) = kind.strip_prefix("bytes") {
+                    encode_fixed_bytes(kind, width, value)
+                } else if let Some(bits) = kind.strip_prefix("uint") {
+                    encode_int(kind, bits, false, value)
+                } else if let Some(bits) = kind.strip_prefix("int") {
+                    encode_int(kind, bits, true, value)
+                } else {
+                    Err(format!("Unknown type {}", kind))
+                }
+            }
+        }
+    }
+
+    // The one type no other type refers to, as ethers.js infers it
+    fn primary_type(&self) -> Result<String, String> {
+        let mut referenced = BTreeSet::new();
+        for name in self.types.keys() {
+            for field in self.fields(name)? {
+                referenced.insert(base_type(&field.kind).to_string());
+            }
+        }
+        let candidates: Vec<&String> = self
+            .types
+            .keys()
+            .filter(|name| *name != DOMAIN_TYPE && !referenced.contains(*name))
+            .collect();
+        match candidates.as_slice() {
+            [primary] => Ok(primary.to_string()),
+            _ => Err(format!(
+                "Could not infer the primary type, candidates are {:?}",
+                candidates
+            )),
+        }
+    }
+}
+
+fn base_type(kind: &str) -> &str {
+    kind.split('[').next().unwrap_or(kind)
+}
+
+fn expect_str<'a>(kind: &str, value: &'a Value) -> Result<&'a str, String> {
+    value
+        .as_str()
+        .ok_or_else(|| format!("Expected a string for {}, got {}", kind, value))
+}
+
+fn decode_hex(kind: &str, value: &Value) -> Result<Vec<u8>, String> {
+    let hex_str = expect_str(kind, value)?;
+    hex::decode(hex_str.trim_start_matches("0x"))
+        .map_err(|e| format!("Failed to decode {} as hex: {:?}", kind, e))
+}
+
+fn left_pad(bytes: &[u8]) -> [u8; 32] {
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(bytes);
+    padded
+}
+
+fn encode_fixed_bytes(kind: &str, width: &str, value: &Value) -> Result<[u8; 32], String> {
+    let width: usize = width
+        .parse()
+        .ok()
+        .filter(|width| (1..=32).contains(width))
+        .ok_or_else(|| format!("Unknown type {}", kind))?;
+    let bytes = decode_hex(kind, value)?;
+    if bytes.len() != width {
+        return Err(format!(
+            "Expected {} bytes for {}, got {}",
+            width,
+            kind,
+            bytes.len()
+        ));
+    }
+    let mut padded = [0u8; 32];
+    padded[..width].copy_from_slice(&bytes);
+    Ok(padded)
+}
+
+// Integers may be JSON numbers or decimal or 0x-prefixed hex strings
+fn encode_int(kind: &str, bits: &str, signed: bool, value: &Value) -> Result<[u8; 32], String> {
+    let bits: u64 = bits
+        .parse()
+        .ok()
+        .filter(|bits| *bits > 0 && *bits <= 256 && bits % 8 == 0)
+        .ok_or_else(|| format!("Unknown type {}", kind))?;
+    let parsed = match value {
+        Value::Number(number) => number.to_string().parse::<BigInt>().ok(),
+        Value::String(text) => match text.strip_prefix("0x") {
+            Some(digits) => BigInt::parse_bytes(digits.as_bytes(), 16),
+            None => text.parse::<BigInt>().ok(),
+        },
+        _ => None,
+    }
+    .ok_or_else(|| format!("Expected an integer for {}, got {}", kind, value))?;
+
+    let (min, max) = if signed {
+        (
+            -(BigInt::from(1) << (bits - 1)),
+            (BigInt::from(1) << (bits - 1)) - 1,
+        )
+    } else {
+        (BigInt::from(0), (BigInt::from(1) << bits) - 1)
+    };
+    if parsed < min || parsed > max {
+        return Err(format!("{} is out of range for {}", parsed, kind));
+    }
+    // Negative values are encoded in 256-bit two's complement
+    let unsigned = if parsed.sign() == Sign::Minus {
+        parsed + (BigInt::from(1) << 256)
+    } else {
+        parsed
+    };
+    Ok(left_pad(&unsigned.to_bytes_be().1))
+}
+
+/// Computes the EIP-712 signing hash
+/// `keccak256(0x1901 || domainSeparator || hashStruct(message))`.
+/// `types` maps type names to their `[{"name", "type"}]` fields. The primary
+/// type is the one no other type references; `EIP712Domain` may be omitted,
+/// in which case it is built from the fields present in `domain`.
+pub fn eip712_hash(domain: &Value, types: &Value, message: &Value) -> Result<[u8; 32], String> {
+    let mut types = types
+        .as_object()
+        .ok_or_else(|| "Types must be a JSON object".to_string())?
+        .clone();
+    if !types.contains_key(DOMAIN_TYPE) {
+        let domain_fields: Vec<Value> = DOMAIN_FIELDS
+            .iter()
+            .filter(|(name, _)| domain.get(name).is_some())
+            .map(|(name, kind)| serde_json::json!({ "name": name, "type": kind }))
+            .collect();
+        types.insert(DOMAIN_TYPE.to_string(), Value::Array(domain_fields));
+    }
+    let typed_data = TypedData { types: &types };
+    let primary_type = typed_data.primary_type()?;
+
+    let mut encoded = vec![0x19, 0x01];
+    encoded.extend_from_slice(&typed_data.hash_struct(DOMAIN_TYPE, domain)?);
+    encoded.extend_from_slice(&typed_data.hash_struct(&primary_type, message)?);
+    Ok(keccak(&encoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    // The example from the EIP-712 specification, which MetaMask's
+    // eth_signTypedData_v4 test suite also uses
+    fn mail() -> (Value, Value, Value) {
+        let domain = json!({
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC",
+        });
+        let types = json!({
+            "Person": [
+                { "name": "name", "type": "string" },
+                { "name": "wallet", "type": "address" },
+            ],
+            "Mail": [
+                { "name": "from", "type": "Person" },
+                { "name": "to", "type": "Person" },
+                { "name": "contents", "type": "string" },
+            ],
+        });
+        let message = json!({
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!",
+        });
+        (domain, types, message)
+    }
+
+    #[test]
+    fn test_eip712_hash_mail() {
+        let (domain, types, message) = mail();
+        let hash = eip712_hash(&domain, &types, &message).unwrap();
+        assert_eq!(
+            hex::encode(hash),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    #[test]
+    fn test_eip712_encode_type() {
+        let (_, types, _) = mail();
+        let typed_data = TypedData {
+            types: types.as_object().unwrap(),
+        };
+        assert_eq!(
+            typed_data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(typed_data.primary_type().unwrap(), "Mail");
+    }
+
+    #[test]
+    fn test_eip712_rejects_bad_values() {
+        let (domain, types, mut message) = mail();
+        message["from"]["wallet"] = json!("0x1234");
+        assert!(eip712_hash(&domain, &types, &message).is_err());
+
+        let (domain, types, mut message) = mail();
+        message.as_object_mut().unwrap().remove("contents");
+        assert!(eip712_hash(&domain, &types, &message).is_err());
+    }
+
+    #[test]
+    fn test_eip712_encode_int() {
+        let minus_one = encode_int("int8", "8", true, &json!(-1)).unwrap();
+        assert_eq!(minus_one, [0xff; 32]);
+        let hex = encode_int("uint16", "16", false, &json!("0x0102")).unwrap();
+        assert_eq!(hex[30..], [0x01, 0x02]);
+        assert!(encode_int("uint8", "8", false, &json!(256)).is_err());
+        assert!(encode_int("int8", "8", true, &json!(-129)).is_err());
+    }
+}
@@ -1,10 +1,57 @@
 use hex;
+use k256::ecdsa::RecoveryId;
+use k256::ecdsa::Signature;
+use k256::ecdsa::VerifyingKey;
 use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use rand::CryptoRng;
+use rand::RngCore;
 use sha2::{Digest, Sha256};
+use sha3::Keccak256;
 use starknet::core::crypto::ecdsa_sign;
+use starknet::core::utils::cairo_short_string_to_felt;
+use starknet::core::utils::parse_cairo_short_string;
+use starknet_crypto::get_public_key;
+use starknet_crypto::pedersen_hash;
+use starknet_crypto::poseidon_hash;
+use starknet_crypto::poseidon_hash_many;
+use starknet_crypto::sign as ecdsa_sign_with_nonce;
 use starknet_crypto::Felt;
+use starknet_types_core::curve::AffinePoint;
+use starknet_types_core::curve::ProjectivePoint;
 use std::str::FromStr;
+use std::sync::LazyLock;
+pub mod eip712;
 pub mod starknet_messages;
+pub mod transaction;
+
+use starknet_messages::batch_commitment;
+use starknet_messages::Hashable;
+use starknet_messages::Order;
+use starknet_messages::StarknetDomain;
+use starknet_messages::TransferArgs;
+
+/// Order of the Stark curve's generator; private keys lie in `[1, EC_ORDER)`.
+pub static EC_ORDER: LazyLock<Felt> = LazyLock::new(|| {
+    Felt::from_hex_unchecked("0x0800000000000010ffffffffffffffffb781126dcae7b2321e66a241adc64d2f")
+});
+
+/// `EC_ORDER / 2`, rounded down. A signature is in canonical low-S form when
+/// its `s` is at most this.
+pub static EC_ORDER_HALF: LazyLock<Felt> = LazyLock::new(|| {
+    Felt::from_hex_unchecked("0x4000000000000087fffffffffffffffdbc08936e573d9190f335120d6e32697")
+});
+
+/// `2^251`. Signature verification only accepts `r` and `s` in
+/// `[1, ELEMENT_UPPER_BOUND)`.
+pub static ELEMENT_UPPER_BOUND: LazyLock<Felt> = LazyLock::new(|| {
+    Felt::from_hex_unchecked("0x800000000000000000000000000000000000000000000000000000000000000")
+});
+
+// Constant term of the Stark curve y^2 = x^3 + x + EC_BETA
+static EC_BETA: LazyLock<Felt> = LazyLock::new(|| {
+    Felt::from_hex_unchecked("0x6f21413efbe40de150e596d72f7a8c5609ad26c15c915c1f4cdfcb99cee9e89")
+});
 
 pub struct StarkSignature {
     pub r: Felt,
@@ -43,19 +90,359 @@ fn grind_key(key_seed: BigUint) -> BigUint {
     }
 }
 
-pub fn get_private_key_from_eth_signature(signature: &str) -> Result<Felt, String> {
+// The grinding seed is the r component of the Ethereum signature
+fn eth_signature_r(signature: &str) -> Result<BigUint, String> {
     let eth_sig_truncated = signature.trim_start_matches("0x");
     if eth_sig_truncated.len() < 64 {
         return Err("Invalid signature length".to_string());
     }
     let r = &eth_sig_truncated[..64];
     let r_bytes = hex::decode(r).map_err(|e| format!("Failed to decode r as hex: {:?}", e))?;
-    let r_int = BigUint::from_bytes_be(&r_bytes);
+    Ok(BigUint::from_bytes_be(&r_bytes))
+}
 
-    let ground_key = grind_key(r_int);
+/// Derives the Stark key the way StarkEx's `grind_key` does, which is what
+/// Extended onboarding expects: SHA-256 of `r || index`, skipping only digests
+/// in the biased tail, then reduced modulo `EC_ORDER`.
+pub fn get_private_key_from_eth_signature(signature: &str) -> Result<Felt, String> {
+    let ground_key = grind_key(eth_signature_r(signature)?);
     return Ok(Felt::from_hex(&ground_key.to_str_radix(16)).unwrap());
 }
 
+fn decode_hex_bytes(field: &str, value: &str, len: usize) -> Result<Vec<u8>, String> {
+    let bytes = hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| format!("Failed to decode {} as hex: {:?}", field, e))?;
+    if bytes.len() != len {
+        return Err(format!(
+            "Invalid {} length: expected {} bytes, got {}",
+            field,
+            len,
+            bytes.len()
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Recovers the Ethereum address that produced a 65-byte `r || s || v`
+/// signature over a 32-byte message hash. `v` may be given as 0/1 or 27/28.
+pub fn recover_eth_address(signature: &str, message_hash: &str) -> Result<[u8; 20], String> {
+    let signature_bytes = decode_hex_bytes("signature", signature, 65)?;
+    let message_hash = decode_hex_bytes("message_hash", message_hash, 32)?;
+    let signature = Signature::from_slice(&signature_bytes[..64])
+        .map_err(|e| format!("Invalid signature: {}", e))?;
+    let v = signature_bytes[64];
+    let recovery_id = RecoveryId::from_byte(if v >= 27 { v - 27 } else { v })
+        .ok_or_else(|| format!("Invalid signature recovery id: {}", v))?;
+    let verifying_key = VerifyingKey::recover_from_prehash(&message_hash, &signature, recovery_id)
+        .map_err(|e| format!("Failed to recover signer: {}", e))?;
+    let public_key = verifying_key.to_encoded_point(false);
+    let hash = Keccak256::digest(&public_key.as_bytes()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    Ok(address)
+}
+
+/// Like `get_private_key_from_eth_signature`, but first checks that the
+/// signature over `message_hash` recovers to `expected_address`, so a key is
+/// never derived from a signature made by someone else.
+pub fn get_private_key_from_eth_signature_checked(
+    signature: &str,
+    message_hash: &str,
+    expected_address: &str,
+) -> Result<Felt, String> {
+    let expected = decode_hex_bytes("expected_address", expected_address, 20)?;
+    let recovered = recover_eth_address(signature, message_hash)?;
+    if recovered[..] != expected[..] {
+        return Err(format!(
+            "Signature was made by 0x{}, not {}",
+            hex::encode(recovered),
+            expected_address
+        ));
+    }
+    get_private_key_from_eth_signature(signature)
+}
+
+/// Generates a fresh private key from the operating system's CSPRNG.
+pub fn generate_private_key() -> Felt {
+    generate_private_key_with_rng(&mut OsRng)
+}
+
+/// Generates a private key from the given RNG by rejection sampling 252-bit
+/// values until one falls in `[1, EC_ORDER)`. Tests pass a seeded RNG to get
+/// reproducible keys; everything else should use `generate_private_key`.
+pub fn generate_private_key_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Felt {
+    loop {
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        bytes[0] &= 0x0f;
+        let key = Felt::from_bytes_be(&bytes);
+        if key != Felt::ZERO && key < *EC_ORDER {
+            return key;
+        }
+    }
+}
+
+/// Generates an order salt whose high 32 bits are `timestamp`, in seconds,
+/// and whose low 32 bits come from the operating system's CSPRNG, so a salt's
+/// age can be read back with `salt_timestamp`.
+pub fn generate_timestamped_salt(timestamp: u32) -> u64 {
+    generate_timestamped_salt_with_rng(timestamp, &mut OsRng)
+}
+
+/// Like `generate_timestamped_salt`, but draws the random low bits from the
+/// given RNG.
+pub fn generate_timestamped_salt_with_rng<R: RngCore + CryptoRng>(
+    timestamp: u32,
+    rng: &mut R,
+) -> u64 {
+    (u64::from(timestamp) << 32) | u64::from(rng.next_u32())
+}
+
+/// Reads back the creation timestamp of a salt from `generate_timestamped_salt`.
+pub fn salt_timestamp(salt: u64) -> u32 {
+    (salt >> 32) as u32
+}
+
+/// Derives the private key at `index` of the fixed test key table:
+/// `poseidon('test_keypair', index, attempt)` for the first attempt landing
+/// in `[1, EC_ORDER)`. Publicly known by construction, so only for tests.
+pub fn test_private_key(index: u64) -> Felt {
+    let tag = Felt::from_bytes_be_slice(b"test_keypair");
+    let mut attempt = 0u64;
+    loop {
+        let key = poseidon_hash_many(&[tag, index.into(), attempt.into()]);
+        if key != Felt::ZERO && key < *EC_ORDER {
+            return key;
+        }
+        attempt += 1;
+    }
+}
+
+/// Derives the signing key of subaccount `index` from a master private key:
+/// `poseidon('subaccount', master_key, index, attempt)` for the first attempt
+/// landing in `[1, EC_ORDER)`. It is one-way, so a leaked subaccount key
+/// reveals neither the master key nor the other subaccounts' keys.
+pub fn derive_subaccount_key(master_key: &Felt, index: u64) -> Result<Felt, String> {
+    if *master_key == Felt::ZERO || *master_key >= *EC_ORDER {
+        return Err("Master key must be in [1, EC_ORDER)".to_string());
+    }
+    let tag = Felt::from_bytes_be_slice(b"subaccount");
+    let mut attempt = 0u64;
+    loop {
+        let key = poseidon_hash_many(&[tag, *master_key, index.into(), attempt.into()]);
+        if key != Felt::ZERO && key < *EC_ORDER {
+            return Ok(key);
+        }
+        attempt += 1;
+    }
+}
+
+/// Returns the full public key point for a private key in `[1, EC_ORDER)`.
+/// `starknet_crypto::get_public_key` only gives the x-coordinate.
+pub fn get_public_key_point(private_key: &Felt) -> Result<AffinePoint, String> {
+    if *private_key == Felt::ZERO || *private_key >= *EC_ORDER {
+        return Err("Private key must be in [1, EC_ORDER)".to_string());
+    }
+    let generator = AffinePoint::generator();
+    let generator = ProjectivePoint::from_affine(generator.x(), generator.y())
+        .map_err(|e| format!("Invalid generator point: {:?}", e))?;
+    (&generator * *private_key)
+        .to_affine()
+        .map_err(|e| format!("Failed to compute public key: {:?}", e))
+}
+
+/// Returns the parity (0 or 1) of the public key's y-coordinate, which
+/// together with the x-coordinate identifies the point.
+pub fn get_public_key_parity(private_key: &Felt) -> Result<u8, String> {
+    get_compressed_public_key(private_key).map(|(_, parity)| parity)
+}
+
+/// Returns the public key's x-coordinate and y-parity, its compressed form.
+pub fn get_compressed_public_key(private_key: &Felt) -> Result<(Felt, u8), String> {
+    let point = get_public_key_point(private_key)?;
+    Ok((point.x(), point.y().to_bytes_be()[31] & 1))
+}
+
+// Lifts an affine point into projective coordinates, rejecting any point that
+// isn't on the curve
+fn curve_point(x: Felt, y: Felt) -> Result<ProjectivePoint, String> {
+    ProjectivePoint::from_affine(x, y).map_err(|_| {
+        format!(
+            "Point ({}, {}) is not on the curve",
+            x.to_hex_string(),
+            y.to_hex_string()
+        )
+    })
+}
+
+/// The standard Stark curve generator, which public keys are multiples of.
+pub fn ec_generator() -> AffinePoint {
+    AffinePoint::generator()
+}
+
+/// Multiplies a point on the Stark curve by a scalar. The scalar is used as
+/// is, so reduce it modulo `EC_ORDER` beforehand if it can exceed the order.
+pub fn ec_mul(x: Felt, y: Felt, scalar: Felt) -> Result<AffinePoint, String> {
+    (&curve_point(x, y)? * scalar)
+        .to_affine()
+        .map_err(|_| "Result is the point at infinity".to_string())
+}
+
+/// Adds two points on the Stark curve, given as `(x, y)` pairs.
+pub fn ec_add(a: (Felt, Felt), b: (Felt, Felt)) -> Result<AffinePoint, String> {
+    (&curve_point(a.0, a.1)? + &curve_point(b.0, b.1)?)
+        .to_affine()
+        .map_err(|_| "Result is the point at infinity".to_string())
+}
+
+/// Whether `x` is the x-coordinate of some point on the Stark curve, i.e.
+/// whether `x^3 + x + BETA` has a square root in the field.
+pub fn is_curve_x(x: &Felt) -> bool {
+    let x = *x;
+    (x * x * x + x + *EC_BETA).sqrt().is_some()
+}
+
+/// Verifies a Stark signature without returning early on invalid input. The
+/// range checks on the message hash, `r` and `s` and the on-curve check of
+/// the public key are all evaluated, any value failing them is swapped for a
+/// fixed valid stand-in, and the curve verification then always runs before
+/// the outcomes are combined. This keeps which check fails from showing in
+/// the latency, but isn't constant time: starknet-crypto's square roots,
+/// inversions and scalar multiplications still take time that depends on the
+/// values, and it can still reject an `s` whose inverse is out of range early.
+pub fn verify_signature_full(public_key: &Felt, message: &Felt, r: &Felt, s: &Felt) -> bool {
+    let in_range = |value: &Felt| (*value != Felt::ZERO) & (*value < *ELEMENT_UPPER_BOUND);
+    let message_ok = *message < *ELEMENT_UPPER_BOUND;
+    let r_ok = in_range(r);
+    let s_ok = in_range(s);
+    let public_key_ok = is_curve_x(public_key);
+    let pick = |ok: bool, value: &Felt, stand_in: Felt| if ok { *value } else { stand_in };
+    let verified = starknet_crypto::verify(
+        &pick(public_key_ok, public_key, ec_generator().x()),
+        &pick(message_ok, message, Felt::ZERO),
+        &pick(r_ok, r, Felt::ONE),
+        &pick(s_ok, s, Felt::ONE),
+    )
+    .unwrap_or(false);
+    message_ok & r_ok & s_ok & public_key_ok & verified
+}
+
+/// Fingerprint of the hashing configuration in use: a Poseidon hash over
+/// known-answer outputs of Pedersen (exercising all of its constant points),
+/// Poseidon, public key derivation and the message type selectors. A
+/// dependency bump that changes any constant table changes this value.
+pub fn hash_config_fingerprint() -> Felt {
+    poseidon_hash_many(&[
+        pedersen_hash(&Felt::MAX, &Felt::MAX),
+        poseidon_hash_many(&[Felt::ONE, Felt::TWO, Felt::THREE]),
+        get_public_key(&Felt::ONE),
+        StarknetDomain::SELECTOR,
+        Order::SELECTOR,
+        TransferArgs::SELECTOR,
+    ])
+}
+
+/// Commitment to a position id under a blinding factor, for commit-reveal
+/// disclosure: `poseidon(position_id, blinding)`.
+pub fn position_commitment(position_id: u32, blinding: Felt) -> Felt {
+    poseidon_hash(position_id.into(), blinding)
+}
+
+/// Checks a revealed position id and blinding factor against a commitment,
+/// comparing in constant time.
+pub fn verify_position_commitment(position_id: u32, blinding: Felt, commitment: Felt) -> bool {
+    felt_ct_eq(&position_commitment(position_id, blinding), &commitment)
+}
+
+/// Commitment to a position's balance in one collateral asset under a
+/// blinding factor, for proof of reserves:
+/// `poseidon(position_id, collateral_asset_id, balance, blinding)`, with a
+/// negative balance encoded as its field negation.
+pub fn collateral_commitment(
+    position_id: u32,
+    collateral_asset_id: Felt,
+    balance: i64,
+    blinding: Felt,
+) -> Felt {
+    poseidon_hash_many(&[
+        position_id.into(),
+        collateral_asset_id,
+        balance.into(),
+        blinding,
+    ])
+}
+
+/// Checks a revealed collateral balance and blinding factor against a
+/// commitment, comparing in constant time.
+pub fn verify_collateral_commitment(
+    position_id: u32,
+    collateral_asset_id: Felt,
+    balance: i64,
+    blinding: Felt,
+    commitment: Felt,
+) -> bool {
+    felt_ct_eq(
+        &collateral_commitment(position_id, collateral_asset_id, balance, blinding),
+        &commitment,
+    )
+}
+
+/// Sealed commitment to a batch of order hashes under a blinding factor:
+/// `poseidon(batch_commitment(hashes), blinding)`.
+pub fn order_batch_commitment(hashes: &[Felt], blinding: Felt) -> Felt {
+    poseidon_hash(batch_commitment(hashes), blinding)
+}
+
+/// Checks a revealed batch of order hashes and blinding factor against a
+/// commitment, comparing in constant time.
+pub fn verify_order_batch_commitment(hashes: &[Felt], blinding: Felt, commitment: Felt) -> bool {
+    felt_ct_eq(&order_batch_commitment(hashes, blinding), &commitment)
+}
+
+/// Compares two felts in time independent of where they differ, by folding
+/// the XOR of all 32 big-endian bytes instead of stopping at the first
+/// mismatch.
+pub fn felt_ct_eq(a: &Felt, b: &Felt) -> bool {
+    let a = a.to_bytes_be();
+    let b = b.to_bytes_be();
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// Derives a read-only viewing key from a private key:
+/// `poseidon('viewing_key', private_key)`. It can't sign and doesn't reveal
+/// the private key. Nothing about it is checkable from the public key alone,
+/// so services have to store it (or a hash of it) to authenticate reads.
+pub fn derive_viewing_key(private_key: &Felt) -> Felt {
+    poseidon_hash(Felt::from_bytes_be_slice(b"viewing_key"), *private_key)
+}
+
+pub fn encode_short_string(value: &str) -> Result<Felt, String> {
+    cairo_short_string_to_felt(value)
+        .map_err(|e| format!("Failed to encode {} as a short string: {}", value, e))
+}
+
+/// Reverses `encode_short_string`, rejecting felts whose bytes aren't ASCII
+/// as the encoding never produces them.
+pub fn decode_short_string(felt: &Felt) -> Result<String, String> {
+    let value = parse_cairo_short_string(felt).map_err(|e| {
+        format!(
+            "Failed to decode {} as a short string: {}",
+            felt.to_hex_string(),
+            e
+        )
+    })?;
+    if !value.is_ascii() {
+        return Err(format!(
+            "Failed to decode {} as a short string: non-ASCII character",
+            felt.to_hex_string()
+        ));
+    }
+    Ok(value)
+}
+
 pub fn sign_message(message: &Felt, private_key: &Felt) -> Result<StarkSignature, String> {
     return ecdsa_sign(private_key, &message)
         .map(|extended_signature| StarkSignature {
@@ -66,6 +453,32 @@ pub fn sign_message(message: &Felt, private_key: &Felt) -> Result<StarkSignature
         .map_err(|e| format!("Failed to sign message: {:?}", e));
 }
 
+/// Signs with a caller-supplied nonce, such as one drawn by an HSM. The nonce
+/// must be given as a full 32 bytes and is reduced modulo `EC_ORDER` instead
+/// of rejected. Returns the signature and the x coordinate of `k * G`.
+pub fn sign_message_with_nonce(
+    message: &Felt,
+    private_key: &Felt,
+    nonce_hex: &str,
+) -> Result<(StarkSignature, Felt), String> {
+    let nonce = BigUint::from_bytes_be(&decode_hex_bytes("nonce", nonce_hex, 32)?);
+    let k = nonce % BigUint::from_bytes_be(&EC_ORDER.to_bytes_be());
+    if k == BigUint::ZERO {
+        return Err("Nonce reduces to zero modulo the curve order".to_string());
+    }
+    let k = Felt::from_bytes_be_slice(&k.to_bytes_be());
+    let signature = ecdsa_sign_with_nonce(private_key, message, &k)
+        .map_err(|e| format!("Failed to sign message: {:?}", e))?;
+    Ok((
+        StarkSignature {
+            r: signature.r,
+            s: signature.s,
+            v: signature.v,
+        },
+        get_public_key(&k),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,4 +497,386 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_get_private_key_from_eth_signature_checked() {
+        let signature = "0xdd521281e9deaf5a834876f03edbfb46a81580fca8c7fabfd725c9caa188341445798c41792e62bc506d975590d99e662e75196acb008fbe74b20f5161f77dd11c";
+        let message_hash = "0x7f2e4580cce3ea1ca6031516d6005592dd47189ca0b8d47846dc1dc2a871994b";
+        let signer = "0x39e6db77941463eea0b323f66509eadf0bf0bf1b";
+
+        assert_eq!(
+            get_private_key_from_eth_signature_checked(signature, message_hash, signer),
+            get_private_key_from_eth_signature(signature)
+        );
+
+        let err = get_private_key_from_eth_signature_checked(
+            signature,
+            message_hash,
+            "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf",
+        )
+        .unwrap_err();
+        assert!(err.contains(signer), "{}", err);
+    }
+
+    #[test]
+    fn test_generate_private_key_with_seeded_rng() {
+        use rand_chacha::rand_core::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        let mut rng = ChaCha20Rng::from_seed([7u8; 32]);
+        let private_key = generate_private_key_with_rng(&mut rng);
+        assert_eq!(
+            private_key,
+            Felt::from_hex_unchecked(
+                "0x400927857aaf64114f561baacb379708c79a1dc1476ab573216a4020764bde5"
+            )
+        );
+
+        let mut rng = ChaCha20Rng::from_seed([7u8; 32]);
+        assert_eq!(generate_private_key_with_rng(&mut rng), private_key);
+    }
+
+    #[test]
+    fn test_generate_timestamped_salt() {
+        use rand_chacha::rand_core::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        let timestamp = 1_700_000_000;
+        let mut rng = ChaCha20Rng::from_seed([7u8; 32]);
+        let salt = generate_timestamped_salt_with_rng(timestamp, &mut rng);
+        assert_eq!(salt_timestamp(salt), timestamp);
+
+        let mut rng = ChaCha20Rng::from_seed([7u8; 32]);
+        assert_eq!(
+            generate_timestamped_salt_with_rng(timestamp, &mut rng),
+            salt
+        );
+        assert_eq!(
+            salt_timestamp(generate_timestamped_salt(u32::MAX)),
+            u32::MAX
+        );
+    }
+
+    #[test]
+    fn test_test_private_key() {
+        assert_eq!(
+            test_private_key(0),
+            Felt::from_hex_unchecked(
+                "0x129423501bbb30c96bc5cea8f02023a8df041974fe11adbe247080f927b6172"
+            )
+        );
+        assert_eq!(
+            test_private_key(1),
+            Felt::from_hex_unchecked(
+                "0x22a054164fc07e6b532874f27ec60e9b146d88e5175b4e36b817b90980ca408"
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_public_key_parity() {
+        let private_key = Felt::from_hex_unchecked(
+            "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+        );
+        let point = get_public_key_point(&private_key).unwrap();
+        assert_eq!(point.x(), starknet_crypto::get_public_key(&private_key));
+        assert_eq!(get_public_key_parity(&private_key), Ok(0));
+        assert_eq!(get_public_key_parity(&Felt::ONE), Ok(1));
+
+        assert!(get_public_key_parity(&Felt::ZERO).is_err());
+        assert!(get_public_key_parity(&EC_ORDER).is_err());
+    }
+
+    #[test]
+    fn test_get_compressed_public_key() {
+        let private_key = Felt::from_hex_unchecked(
+            "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+        );
+        assert_eq!(
+            get_compressed_public_key(&private_key),
+            Ok((starknet_crypto::get_public_key(&private_key), 0))
+        );
+        assert_eq!(
+            get_compressed_public_key(&Felt::ONE),
+            Ok((AffinePoint::generator().x(), 1))
+        );
+        assert!(get_compressed_public_key(&Felt::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_ec_mul_and_add() {
+        let generator = AffinePoint::generator();
+        let (gx, gy) = (generator.x(), generator.y());
+        let double = ec_mul(gx, gy, Felt::TWO).unwrap();
+        assert_eq!(
+            double.x(),
+            Felt::from_hex_unchecked(
+                "0x759ca09377679ecd535a81e83039658bf40959283187c654c5416f439403cf5"
+            )
+        );
+        assert_eq!(
+            double.y(),
+            Felt::from_hex_unchecked(
+                "0x6f524a3400e7708d5c01a28598ad272e7455aa88778b19f93b562d7a9646c41"
+            )
+        );
+        assert_eq!(ec_add((gx, gy), (gx, gy)).unwrap(), double);
+
+        let triple = ec_add((double.x(), double.y()), (gx, gy)).unwrap();
+        assert_eq!(ec_mul(gx, gy, Felt::THREE).unwrap(), triple);
+
+        let private_key = Felt::from_hex_unchecked(
+            "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+        );
+        assert_eq!(
+            ec_mul(gx, gy, private_key).unwrap(),
+            get_public_key_point(&private_key).unwrap()
+        );
+
+        assert!(ec_add((gx, gy), (gx, -gy)).is_err());
+        assert!(ec_mul(gx, gy + Felt::ONE, Felt::TWO).is_err());
+        assert!(ec_add((gx, gy), (Felt::ONE, Felt::ONE)).is_err());
+    }
+
+    #[test]
+    fn test_is_curve_x() {
+        assert!(is_curve_x(&AffinePoint::generator().x()));
+        assert!(is_curve_x(&Felt::from_hex_unchecked(
+            "0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43"
+        )));
+        assert!(is_curve_x(&Felt::from(4u8)));
+        assert!(!is_curve_x(&Felt::from(5u8)));
+        assert!(!is_curve_x(&Felt::from(9u8)));
+    }
+
+    #[test]
+    fn test_hash_config_fingerprint() {
+        assert_eq!(
+            hash_config_fingerprint(),
+            Felt::from_hex_unchecked(
+                "0x47e62f9d7435ccb563403864a36db5f94491ae14e844718a9758cb2096928b"
+            )
+        );
+    }
+
+    #[test]
+    fn test_sign_message_with_nonce() {
+        let private_key = Felt::from_hex_unchecked(
+            "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+        );
+        let message = Felt::from_hex_unchecked("0x1234");
+        let nonce = "0x0000000000000000000000000000000000000000000000000000000000000005";
+        let (signature, r_x) = sign_message_with_nonce(&message, &private_key, nonce).unwrap();
+        assert_eq!(r_x, get_public_key(&Felt::from(5u64)));
+        assert_eq!(signature.r, r_x);
+        assert!(starknet_crypto::verify(
+            &get_public_key(&private_key),
+            &message,
+            &signature.r,
+            &signature.s
+        )
+        .unwrap());
+
+        // 5 + EC_ORDER reduces to the same nonce
+        let (wrapped, _) = sign_message_with_nonce(
+            &message,
+            &private_key,
+            "0x0800000000000010ffffffffffffffffb781126dcae7b2321e66a241adc64d34",
+        )
+        .unwrap();
+        assert_eq!((wrapped.r, wrapped.s), (signature.r, signature.s));
+
+        assert!(sign_message_with_nonce(&message, &private_key, "0x05").is_err());
+        assert!(sign_message_with_nonce(
+            &message,
+            &private_key,
+            "0x0800000000000010ffffffffffffffffb781126dcae7b2321e66a241adc64d2f"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_derive_subaccount_key() {
+        let master_key = Felt::from_hex_unchecked(
+            "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+        );
+        assert_eq!(
+            derive_subaccount_key(&master_key, 0),
+            Ok(Felt::from_hex_unchecked(
+                "0x7e9e177ade0ba20dd28bba01c5eb628ba37b515e5d4073ca0fe09ba75b2bf9"
+            ))
+        );
+        assert_eq!(
+            derive_subaccount_key(&master_key, 1),
+            Ok(Felt::from_hex_unchecked(
+                "0x112cb0b1fd87849668253c996e08619c4916ae64739a0b27a3434c6fddb6a2c"
+            ))
+        );
+        assert!(derive_subaccount_key(&Felt::ZERO, 0).is_err());
+        assert!(derive_subaccount_key(&EC_ORDER, 0).is_err());
+    }
+
+    #[test]
+    fn test_verify_signature_full() {
+        let private_key = Felt::from_hex_unchecked(
+            "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+        );
+        let public_key = get_public_key(&private_key);
+        let message = Felt::from_hex_unchecked("0x1234");
+        let signature = sign_message(&message, &private_key).unwrap();
+        let (r, s) = (signature.r, signature.s);
+
+        assert!(verify_signature_full(&public_key, &message, &r, &s));
+        assert!(!verify_signature_full(
+            &public_key,
+            &Felt::from(0x1235u64),
+            &r,
+            &s
+        ));
+        assert!(!verify_signature_full(&public_key, &message, &s, &r));
+        assert!(!verify_signature_full(
+            &public_key,
+            &message,
+            &Felt::ZERO,
+            &s
+        ));
+        assert!(!verify_signature_full(
+            &public_key,
+            &message,
+            &r,
+            &Felt::MAX
+        ));
+        assert!(!verify_signature_full(&public_key, &Felt::MAX, &r, &s));
+        // 0x5 is not the x-coordinate of any point on the curve
+        assert!(!verify_signature_full(&Felt::from(5u64), &message, &r, &s));
+    }
+
+    #[test]
+    fn test_position_commitment() {
+        let blinding = Felt::from_hex_unchecked("0x1234");
+        let commitment = position_commitment(100, blinding);
+        assert_eq!(
+            commitment,
+            Felt::from_hex_unchecked(
+                "0x6d96e0f4439a0cceb4725a852427d279f7002dae8874d4e674fc19a760a75de"
+            )
+        );
+        assert!(verify_position_commitment(100, blinding, commitment));
+        assert!(!verify_position_commitment(101, blinding, commitment));
+        assert!(!verify_position_commitment(100, Felt::ONE, commitment));
+    }
+
+    #[test]
+    fn test_collateral_commitment() {
+        let blinding = Felt::from_hex_unchecked("0x1234");
+        let commitment = collateral_commitment(100, Felt::ONE, 1000, blinding);
+        assert_eq!(
+            commitment,
+            Felt::from_hex_unchecked(
+                "0x2a697364ad5791f62b1d9b5e5fdbbaccd9d2656ae888191e3a370df0dd8120d"
+            )
+        );
+        assert_eq!(
+            collateral_commitment(100, Felt::ONE, -1000, blinding),
+            Felt::from_hex_unchecked(
+                "0x7727a72e7a996c13b52800d4bb96576876597d92de3c8be6a44b3e83834c9a3"
+            )
+        );
+        assert!(verify_collateral_commitment(
+            100,
+            Felt::ONE,
+            1000,
+            blinding,
+            commitment
+        ));
+        assert!(!verify_collateral_commitment(
+            100,
+            Felt::ONE,
+            1001,
+            blinding,
+            commitment
+        ));
+        assert!(!verify_collateral_commitment(
+            100,
+            Felt::TWO,
+            1000,
+            blinding,
+            commitment
+        ));
+        assert!(!verify_collateral_commitment(
+            100,
+            Felt::ONE,
+            1000,
+            Felt::ONE,
+            commitment
+        ));
+    }
+
+    #[test]
+    fn test_order_batch_commitment() {
+        let hashes = [Felt::ONE, Felt::TWO];
+        let blinding = Felt::from_hex_unchecked("0x1234");
+        let commitment = order_batch_commitment(&hashes, blinding);
+        assert_eq!(
+            commitment,
+            Felt::from_hex_unchecked(
+                "0x4b585f0ac8a1000e2d6ff8f40bad106ab3a9a265c0f9e45a95cc9fafd72b100"
+            )
+        );
+        assert!(verify_order_batch_commitment(&hashes, blinding, commitment));
+        assert!(!verify_order_batch_commitment(
+            &[Felt::TWO, Felt::ONE],
+            blinding,
+            commitment
+        ));
+        assert!(!verify_order_batch_commitment(
+            &hashes[..1],
+            blinding,
+            commitment
+        ));
+        assert!(!verify_order_batch_commitment(
+            &hashes,
+            Felt::ONE,
+            commitment
+        ));
+    }
+
+    #[test]
+    fn test_felt_ct_eq() {
+        let a = Felt::from_hex_unchecked("0x1234");
+        assert!(felt_ct_eq(&a, &Felt::from_dec_str("4660").unwrap()));
+        assert!(!felt_ct_eq(&a, &Felt::from_hex_unchecked("0x1235")));
+        assert!(!felt_ct_eq(&Felt::MAX, &Felt::ZERO));
+    }
+
+    #[test]
+    fn test_derive_viewing_key() {
+        let private_key = Felt::from_hex_unchecked(
+            "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+        );
+        assert_eq!(
+            derive_viewing_key(&private_key),
+            Felt::from_hex_unchecked(
+                "0x686ac09779e44c9271370c6e25a38dfef2613dd6756abf78dff4655434bb139"
+            )
+        );
+        assert_ne!(derive_viewing_key(&private_key), private_key);
+        assert_ne!(
+            derive_viewing_key(&private_key),
+            get_public_key(&private_key)
+        );
+    }
+
+    #[test]
+    fn test_short_string_round_trip() {
+        let encoded = encode_short_string("SN_SEPOLIA").unwrap();
+        assert_eq!(encoded, Felt::from_hex_unchecked("0x534e5f5345504f4c4941"));
+        assert_eq!(decode_short_string(&encoded).unwrap(), "SN_SEPOLIA");
+        assert_eq!(decode_short_string(&Felt::ZERO).unwrap(), "");
+
+        assert!(encode_short_string(&"a".repeat(32)).is_err());
+        assert!(encode_short_string("é").is_err());
+        assert!(decode_short_string(&Felt::from_hex_unchecked("0xe9")).is_err());
+        assert!(decode_short_string(&EC_ORDER).is_err());
+    }
 }
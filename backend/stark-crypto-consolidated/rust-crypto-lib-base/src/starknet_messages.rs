@@ -1,3 +1,10 @@
+//! SNIP-12 messages signed for the exchange and their hashing.
+//!
+//! Layouts behind the `draft-layouts` feature were drafted here ahead of an
+//! exchange spec: the exchange hasn't published them, so their type names and
+//! field order are our own guess and it may reject their hashes.
+
+use num_bigint::BigUint;
 use starknet::core::utils::cairo_short_string_to_felt;
 
 use starknet::macros::selector;
@@ -25,6 +32,17 @@ pub trait OffChainMessage: Hashable {
     }
 }
 
+/// Commits to an ordered sequence of message hashes. The length is absorbed
+/// first so that a batch can't collide with a prefix or extension of itself.
+pub fn batch_commitment(hashes: &[Felt]) -> Felt {
+    let mut hasher = PoseidonHasher::new();
+    hasher.update((hashes.len() as u64).into());
+    for hash in hashes {
+        hasher.update(*hash);
+    }
+    hasher.finalize()
+}
+
 pub struct StarknetDomain {
     pub name: String,
     pub version: String,
@@ -49,6 +67,91 @@ impl Hashable for StarknetDomain {
 pub struct AssetId {
     pub value: Felt,
 }
+
+/// Maps the exchange's small integer asset ids to their felt form: the integer
+/// is shifted left by `shift` bits and `tag` fills the bits it vacates.
+pub struct AssetIdEncoding {
+    pub shift: u32,
+    pub tag: Felt,
+}
+
+impl AssetIdEncoding {
+    // A u64 shifted by more than this no longer fits in a felt
+    const MAX_SHIFT: u32 = 251 - 64;
+
+    fn tag(&self) -> Result<BigUint, String> {
+        if self.shift > Self::MAX_SHIFT {
+            return Err(format!(
+                "Shift {} is above the maximum of {}",
+                self.shift,
+                Self::MAX_SHIFT
+            ));
+        }
+        let tag = BigUint::from_bytes_be(&self.tag.to_bytes_be());
+        if tag.bits() > self.shift as u64 {
+            return Err(format!(
+                "Tag {} does not fit in {} bits",
+                self.tag.to_hex_string(),
+                self.shift
+            ));
+        }
+        Ok(tag)
+    }
+
+    pub fn encode(&self, int_id: u64) -> Result<AssetId, String> {
+        let value = (BigUint::from(int_id) << self.shift) | self.tag()?;
+        Ok(AssetId {
+            value: Felt::from_bytes_be_slice(&value.to_bytes_be()),
+        })
+    }
+
+    pub fn decode(&self, asset_id: &AssetId) -> Result<u64, String> {
+        let tag = self.tag()?;
+        let value = BigUint::from_bytes_be(&asset_id.value.to_bytes_be());
+        let mask = (BigUint::from(1u8) << self.shift) - 1u8;
+        if &value & mask != tag {
+            return Err(format!(
+                "Asset id {} does not carry tag {}",
+                asset_id.value.to_hex_string(),
+                self.tag.to_hex_string()
+            ));
+        }
+        u64::try_from(value >> self.shift).map_err(|_| {
+            format!(
+                "Asset id {} does not encode a u64",
+                asset_id.value.to_hex_string()
+            )
+        })
+    }
+}
+
+/// Bit layout a deployment applies to asset ids before hashing: the id is
+/// masked with `mask`, then shifted left by `shift` bits.
+pub struct AssetIdLayout {
+    shift: u32,
+    mask: BigUint,
+}
+
+impl AssetIdLayout {
+    /// Rejects layouts under which some masked id would shift out of the field.
+    pub fn new(shift: u32, mask: Felt) -> Result<Self, String> {
+        let mask = BigUint::from_bytes_be(&mask.to_bytes_be());
+        if (&mask << shift) > BigUint::from_bytes_be(&Felt::MAX.to_bytes_be()) {
+            return Err(format!(
+                "Mask {:#x} shifted by {} bits does not fit in a felt",
+                mask, shift
+            ));
+        }
+        Ok(AssetIdLayout { shift, mask })
+    }
+
+    pub fn apply(&self, asset_id: Felt) -> Felt {
+        let value = BigUint::from_bytes_be(&asset_id.to_bytes_be());
+        let value = (value & &self.mask) << self.shift;
+        Felt::from_bytes_be_slice(&value.to_bytes_be())
+    }
+}
+
 pub struct PositionId {
     pub value: u32,
 }
@@ -93,6 +196,320 @@ impl Hashable for Order {
 }
 impl OffChainMessage for Order {}
 
+impl Order {
+    /// Notional value of the order: the magnitude of its quote amount, whichever
+    /// side it is on.
+    pub fn notional(&self) -> u64 {
+        self.quote_amount.unsigned_abs()
+    }
+
+    /// Representation-independent fingerprint of the order signed by
+    /// `user_public_key`, for deduplicating orders ingested from different
+    /// sources: the Poseidon hash of its fields as felts, in declaration order,
+    /// followed by the key. Unlike the message hash it doesn't depend on the
+    /// domain.
+    pub fn fingerprint(&self, user_public_key: Felt) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(self.position_id.value.into());
+        hasher.update(self.base_asset_id.value);
+        hasher.update(self.base_amount.into());
+        hasher.update(self.quote_asset_id.value);
+        hasher.update(self.quote_amount.into());
+        hasher.update(self.fee_asset_id.value);
+        hasher.update(self.fee_amount.into());
+        hasher.update(self.expiration.seconds.into());
+        hasher.update(self.salt);
+        hasher.update(user_public_key);
+        hasher.finalize()
+    }
+
+    /// Why this order can't be matched against `other`, or `None` if it can.
+    /// Matchable orders trade the same base asset for the same quote asset on
+    /// opposite sides, each paying quote for base or the reverse, at prices
+    /// that cross: the buyer's limit price is at least the seller's.
+    pub fn match_error(&self, other: &Order) -> Option<&'static str> {
+        if self.base_asset_id.value != other.base_asset_id.value {
+            return Some("different base assets");
+        }
+        if self.quote_asset_id.value != other.quote_asset_id.value {
+            return Some("different quote assets");
+        }
+        for order in [self, other] {
+            if order.base_amount == 0 || order.quote_amount == 0 {
+                return Some("zero amount");
+            }
+            if (order.base_amount > 0) == (order.quote_amount > 0) {
+                return Some("base and quote amounts on the same side");
+            }
+        }
+        if (self.base_amount > 0) == (other.base_amount > 0) {
+            return Some("same side");
+        }
+        let (buy, sell) = if self.base_amount > 0 {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        // buy.quote / buy.base >= sell.quote / sell.base, cross-multiplied
+        let buy_limit = u128::from(buy.notional()) * u128::from(sell.base_amount.unsigned_abs());
+        let sell_limit = u128::from(sell.notional()) * u128::from(buy.base_amount.unsigned_abs());
+        if buy_limit < sell_limit {
+            return Some("prices don't cross");
+        }
+        None
+    }
+}
+
+/// Order layout for reduce-only support, which appends a 0/1 `reduce_only`
+/// flag to the regular order fields. Orders that aren't reduce-only keep
+/// being hashed as a plain `Order`, so their existing hashes don't change.
+#[cfg(feature = "draft-layouts")]
+pub struct ReduceOnlyOrder {
+    pub position_id: PositionId,
+    pub base_asset_id: AssetId,
+    pub base_amount: i64,
+    pub quote_asset_id: AssetId,
+    pub quote_amount: i64,
+    pub fee_asset_id: AssetId,
+    pub fee_amount: u64,
+    pub expiration: Timestamp,
+    pub salt: Felt,
+    pub reduce_only: bool,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for ReduceOnlyOrder {
+    const SELECTOR: Felt = selector!("\"ReduceOnlyOrder\"(\"position_id\":\"felt\",\"base_asset_id\":\"AssetId\",\"base_amount\":\"i64\",\"quote_asset_id\":\"AssetId\",\"quote_amount\":\"i64\",\"fee_asset_id\":\"AssetId\",\"fee_amount\":\"u64\",\"expiration\":\"Timestamp\",\"salt\":\"felt\",\"reduce_only\":\"felt\")\"PositionId\"(\"value\":\"u32\")\"AssetId\"(\"value\":\"felt\")\"Timestamp\"(\"seconds\":\"u64\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.position_id.value.into());
+        hasher.update(self.base_asset_id.value.into());
+        hasher.update(self.base_amount.into());
+        hasher.update(self.quote_asset_id.value.into());
+        hasher.update(self.quote_amount.into());
+        hasher.update(self.fee_asset_id.value.into());
+        hasher.update(self.fee_amount.into());
+        hasher.update(self.expiration.seconds.into());
+        hasher.update(self.salt);
+        hasher.update(u8::from(self.reduce_only).into());
+        hasher.finalize()
+    }
+}
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for ReduceOnlyOrder {}
+
+/// Order layout for post-only support, which appends 0/1 `reduce_only` and
+/// `post_only` flags to the regular order fields so the exchange can enforce
+/// that the order never takes liquidity. Used only for post-only orders;
+/// reduce-only orders that aren't post-only keep the `ReduceOnlyOrder` layout.
+#[cfg(feature = "draft-layouts")]
+pub struct PostOnlyOrder {
+    pub position_id: PositionId,
+    pub base_asset_id: AssetId,
+    pub base_amount: i64,
+    pub quote_asset_id: AssetId,
+    pub quote_amount: i64,
+    pub fee_asset_id: AssetId,
+    pub fee_amount: u64,
+    pub expiration: Timestamp,
+    pub salt: Felt,
+    pub reduce_only: bool,
+    pub post_only: bool,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for PostOnlyOrder {
+    const SELECTOR: Felt = selector!("\"PostOnlyOrder\"(\"position_id\":\"felt\",\"base_asset_id\":\"AssetId\",\"base_amount\":\"i64\",\"quote_asset_id\":\"AssetId\",\"quote_amount\":\"i64\",\"fee_asset_id\":\"AssetId\",\"fee_amount\":\"u64\",\"expiration\":\"Timestamp\",\"salt\":\"felt\",\"reduce_only\":\"felt\",\"post_only\":\"felt\")\"PositionId\"(\"value\":\"u32\")\"AssetId\"(\"value\":\"felt\")\"Timestamp\"(\"seconds\":\"u64\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.position_id.value.into());
+        hasher.update(self.base_asset_id.value.into());
+        hasher.update(self.base_amount.into());
+        hasher.update(self.quote_asset_id.value.into());
+        hasher.update(self.quote_amount.into());
+        hasher.update(self.fee_asset_id.value.into());
+        hasher.update(self.fee_amount.into());
+        hasher.update(self.expiration.seconds.into());
+        hasher.update(self.salt);
+        hasher.update(u8::from(self.reduce_only).into());
+        hasher.update(u8::from(self.post_only).into());
+        hasher.finalize()
+    }
+}
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for PostOnlyOrder {}
+
+/// Order layout for per-order leverage, which appends 0/1 `reduce_only` and
+/// `post_only` flags and the requested `leverage` multiplier to the regular
+/// order fields. Used for every order that carries a leverage, whatever its
+/// flags; orders without one keep their existing layouts and hashes.
+#[cfg(feature = "draft-layouts")]
+pub struct LeveragedOrder {
+    pub position_id: PositionId,
+    pub base_asset_id: AssetId,
+    pub base_amount: i64,
+    pub quote_asset_id: AssetId,
+    pub quote_amount: i64,
+    pub fee_asset_id: AssetId,
+    pub fee_amount: u64,
+    pub expiration: Timestamp,
+    pub salt: Felt,
+    pub reduce_only: bool,
+    pub post_only: bool,
+    pub leverage: u8,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for LeveragedOrder {
+    const SELECTOR: Felt = selector!("\"LeveragedOrder\"(\"position_id\":\"felt\",\"base_asset_id\":\"AssetId\",\"base_amount\":\"i64\",\"quote_asset_id\":\"AssetId\",\"quote_amount\":\"i64\",\"fee_asset_id\":\"AssetId\",\"fee_amount\":\"u64\",\"expiration\":\"Timestamp\",\"salt\":\"felt\",\"reduce_only\":\"felt\",\"post_only\":\"felt\",\"leverage\":\"u8\")\"PositionId\"(\"value\":\"u32\")\"AssetId\"(\"value\":\"felt\")\"Timestamp\"(\"seconds\":\"u64\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.position_id.value.into());
+        hasher.update(self.base_asset_id.value.into());
+        hasher.update(self.base_amount.into());
+        hasher.update(self.quote_asset_id.value.into());
+        hasher.update(self.quote_amount.into());
+        hasher.update(self.fee_asset_id.value.into());
+        hasher.update(self.fee_amount.into());
+        hasher.update(self.expiration.seconds.into());
+        hasher.update(self.salt);
+        hasher.update(u8::from(self.reduce_only).into());
+        hasher.update(u8::from(self.post_only).into());
+        hasher.update(self.leverage.into());
+        hasher.finalize()
+    }
+}
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for LeveragedOrder {}
+
+/// Child order of an OCO or bracket pair, binding the struct hash of the
+/// child order (in whichever order layout applies) to its parent's message
+/// hash.
+#[cfg(feature = "draft-layouts")]
+pub struct LinkedOrder {
+    pub order_hash: Felt,
+    pub parent_order_hash: Felt,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for LinkedOrder {
+    const SELECTOR: Felt =
+        selector!("\"LinkedOrder\"(\"order\":\"felt\",\"parent_order_hash\":\"felt\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.order_hash);
+        hasher.update(self.parent_order_hash);
+        hasher.finalize()
+    }
+}
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for LinkedOrder {}
+
+/// Order carrying an audit tag identifying its originating desk, binding the
+/// struct hash of the order (or of its `LinkedOrder`, for a child order) to
+/// the tag. Orders without a tag aren't wrapped, so their hashes don't change.
+#[cfg(feature = "draft-layouts")]
+pub struct AuditedOrder {
+    pub order_hash: Felt,
+    pub audit_tag: Felt,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for AuditedOrder {
+    const SELECTOR: Felt = selector!("\"AuditedOrder\"(\"order\":\"felt\",\"audit_tag\":\"felt\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.order_hash);
+        hasher.update(self.audit_tag);
+        hasher.finalize()
+    }
+}
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for AuditedOrder {}
+
+/// Order layout for fee tiers, which binds separate maker and taker fees in
+/// place of the single fee amount.
+#[cfg(feature = "draft-layouts")]
+pub struct OrderWithFees {
+    pub position_id: PositionId,
+    pub base_asset_id: AssetId,
+    pub base_amount: i64,
+    pub quote_asset_id: AssetId,
+    pub quote_amount: i64,
+    pub fee_asset_id: AssetId,
+    pub maker_fee_amount: u64,
+    pub taker_fee_amount: u64,
+    pub expiration: Timestamp,
+    pub salt: Felt,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for OrderWithFees {
+    const SELECTOR: Felt = selector!("\"OrderWithFees\"(\"position_id\":\"felt\",\"base_asset_id\":\"AssetId\",\"base_amount\":\"i64\",\"quote_asset_id\":\"AssetId\",\"quote_amount\":\"i64\",\"fee_asset_id\":\"AssetId\",\"maker_fee_amount\":\"u64\",\"taker_fee_amount\":\"u64\",\"expiration\":\"Timestamp\",\"salt\":\"felt\")\"PositionId\"(\"value\":\"u32\")\"AssetId\"(\"value\":\"felt\")\"Timestamp\"(\"seconds\":\"u64\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.position_id.value.into());
+        hasher.update(self.base_asset_id.value.into());
+        hasher.update(self.base_amount.into());
+        hasher.update(self.quote_asset_id.value.into());
+        hasher.update(self.quote_amount.into());
+        hasher.update(self.fee_asset_id.value.into());
+        hasher.update(self.maker_fee_amount.into());
+        hasher.update(self.taker_fee_amount.into());
+        hasher.update(self.expiration.seconds.into());
+        hasher.update(self.salt);
+        hasher.finalize()
+    }
+}
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for OrderWithFees {}
+
+/// Settlement of two matched orders, binding both orders and the amounts and
+/// fees actually filled on each side. Nested orders enter as their struct
+/// hashes.
+#[cfg(feature = "draft-layouts")]
+pub struct Settlement {
+    pub order_a: Order,
+    pub order_b: Order,
+    pub actual_a_amount: i64,
+    pub actual_b_amount: i64,
+    pub actual_a_fee: u64,
+    pub actual_b_fee: u64,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for Settlement {
+    const SELECTOR: Felt = selector!("\"Settlement\"(\"order_a\":\"Order\",\"order_b\":\"Order\",\"actual_a_amount\":\"i64\",\"actual_b_amount\":\"i64\",\"actual_a_fee\":\"u64\",\"actual_b_fee\":\"u64\")\"Order\"(\"position_id\":\"felt\",\"base_asset_id\":\"AssetId\",\"base_amount\":\"i64\",\"quote_asset_id\":\"AssetId\",\"quote_amount\":\"i64\",\"fee_asset_id\":\"AssetId\",\"fee_amount\":\"u64\",\"expiration\":\"Timestamp\",\"salt\":\"felt\")\"PositionId\"(\"value\":\"u32\")\"AssetId\"(\"value\":\"felt\")\"Timestamp\"(\"seconds\":\"u64\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.order_a.hash());
+        hasher.update(self.order_b.hash());
+        hasher.update(self.actual_a_amount.into());
+        hasher.update(self.actual_b_amount.into());
+        hasher.update(self.actual_a_fee.into());
+        hasher.update(self.actual_b_fee.into());
+        hasher.finalize()
+    }
+}
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for Settlement {}
+
+/// Converts a fee rate in basis points into the absolute fee for an order with
+/// the given quote amount, rounding up as the exchange does:
+/// `ceil(|quote_amount| * fee_rate_bps / 10000)`.
+pub fn fee_amount_from_rate(quote_amount: i64, fee_rate_bps: u64) -> Result<u64, String> {
+    let product = quote_amount.unsigned_abs() as u128 * fee_rate_bps as u128;
+    let fee = product.div_ceil(10_000);
+    u64::try_from(fee).map_err(|_| format!("Fee {} does not fit in a u64", fee))
+}
+
 pub struct TransferArgs {
     pub recipient: PositionId,
     pub position_id: PositionId,
@@ -119,6 +536,162 @@ impl Hashable for TransferArgs {
 
 impl OffChainMessage for TransferArgs {}
 
+/// Transfer layout used by newer contract versions, which also bind the
+/// address of the position owner.
+#[cfg(feature = "draft-layouts")]
+pub struct TransferArgsWithOwner {
+    pub recipient: PositionId,
+    pub position_id: PositionId,
+    pub collateral_id: AssetId,
+    pub amount: u64,
+    pub expiration: Timestamp,
+    pub salt: Felt,
+    pub owner: Felt,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for TransferArgsWithOwner {
+    const SELECTOR: Felt = selector!("\"TransferArgsWithOwner\"(\"recipient\":\"PositionId\",\"position_id\":\"PositionId\",\"collateral_id\":\"AssetId\",\"amount\":\"u64\",\"expiration\":\"Timestamp\",\"salt\":\"felt\",\"owner\":\"ContractAddress\")\"PositionId\"(\"value\":\"u32\")\"AssetId\"(\"value\":\"felt\")\"Timestamp\"(\"seconds\":\"u64\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.recipient.value.into());
+        hasher.update(self.position_id.value.into());
+        hasher.update(self.collateral_id.value.into());
+        hasher.update(self.amount.into());
+        hasher.update(self.expiration.seconds.into());
+        hasher.update(self.salt);
+        hasher.update(self.owner);
+        hasher.finalize()
+    }
+}
+
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for TransferArgsWithOwner {}
+
+/// Transfer layout for accounts using nonce-based replay protection, which
+/// bind a per-account nonce that must increase with every transfer.
+#[cfg(feature = "draft-layouts")]
+pub struct TransferArgsWithNonce {
+    pub recipient: PositionId,
+    pub position_id: PositionId,
+    pub collateral_id: AssetId,
+    pub amount: u64,
+    pub expiration: Timestamp,
+    pub salt: Felt,
+    pub nonce: u64,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for TransferArgsWithNonce {
+    const SELECTOR: Felt = selector!("\"TransferArgsWithNonce\"(\"recipient\":\"PositionId\",\"position_id\":\"PositionId\",\"collateral_id\":\"AssetId\",\"amount\":\"u64\",\"expiration\":\"Timestamp\",\"salt\":\"felt\",\"nonce\":\"u64\")\"PositionId\"(\"value\":\"u32\")\"AssetId\"(\"value\":\"felt\")\"Timestamp\"(\"seconds\":\"u64\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.recipient.value.into());
+        hasher.update(self.position_id.value.into());
+        hasher.update(self.collateral_id.value.into());
+        hasher.update(self.amount.into());
+        hasher.update(self.expiration.seconds.into());
+        hasher.update(self.salt);
+        hasher.update(self.nonce.into());
+        hasher.finalize()
+    }
+}
+
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for TransferArgsWithNonce {}
+
+/// Transfer layout for transfers carrying a memo felt, used for
+/// exchange-internal routing, which is bound into the signed message.
+/// Transfers without a memo keep being hashed as plain `TransferArgs`.
+#[cfg(feature = "draft-layouts")]
+pub struct TransferArgsWithMemo {
+    pub recipient: PositionId,
+    pub position_id: PositionId,
+    pub collateral_id: AssetId,
+    pub amount: u64,
+    pub expiration: Timestamp,
+    pub salt: Felt,
+    pub memo: Felt,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for TransferArgsWithMemo {
+    const SELECTOR: Felt = selector!("\"TransferArgsWithMemo\"(\"recipient\":\"PositionId\",\"position_id\":\"PositionId\",\"collateral_id\":\"AssetId\",\"amount\":\"u64\",\"expiration\":\"Timestamp\",\"salt\":\"felt\",\"memo\":\"felt\")\"PositionId\"(\"value\":\"u32\")\"AssetId\"(\"value\":\"felt\")\"Timestamp\"(\"seconds\":\"u64\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.recipient.value.into());
+        hasher.update(self.position_id.value.into());
+        hasher.update(self.collateral_id.value.into());
+        hasher.update(self.amount.into());
+        hasher.update(self.expiration.seconds.into());
+        hasher.update(self.salt);
+        hasher.update(self.memo);
+        hasher.finalize()
+    }
+}
+
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for TransferArgsWithMemo {}
+
+/// Withdrawal of collateral from a position to an address outside the
+/// exchange, such as an L1 Ethereum address. The recipient is typed as a
+/// `ContractAddress` even though it's an L1 address.
+#[cfg(feature = "draft-layouts")]
+pub struct WithdrawArgs {
+    pub recipient: Felt,
+    pub position_id: PositionId,
+    pub collateral_id: AssetId,
+    pub amount: u64,
+    pub expiration: Timestamp,
+    pub salt: Felt,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for WithdrawArgs {
+    const SELECTOR: Felt = selector!("\"WithdrawArgs\"(\"recipient\":\"ContractAddress\",\"position_id\":\"PositionId\",\"collateral_id\":\"AssetId\",\"amount\":\"u64\",\"expiration\":\"Timestamp\",\"salt\":\"felt\")\"PositionId\"(\"value\":\"u32\")\"AssetId\"(\"value\":\"felt\")\"Timestamp\"(\"seconds\":\"u64\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.recipient);
+        hasher.update(self.position_id.value.into());
+        hasher.update(self.collateral_id.value.into());
+        hasher.update(self.amount.into());
+        hasher.update(self.expiration.seconds.into());
+        hasher.update(self.salt);
+        hasher.finalize()
+    }
+}
+
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for WithdrawArgs {}
+
+/// Cancels every open order of a position at once, as an emergency stop.
+#[cfg(feature = "draft-layouts")]
+pub struct CancelAllOrders {
+    pub position_id: PositionId,
+    pub expiration: Timestamp,
+    pub salt: Felt,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl Hashable for CancelAllOrders {
+    const SELECTOR: Felt = selector!("\"CancelAllOrders\"(\"position_id\":\"PositionId\",\"expiration\":\"Timestamp\",\"salt\":\"felt\")\"PositionId\"(\"value\":\"u32\")\"Timestamp\"(\"seconds\":\"u64\")");
+    fn hash(&self) -> Felt {
+        let mut hasher = PoseidonHasher::new();
+        hasher.update(Self::SELECTOR);
+        hasher.update(self.position_id.value.into());
+        hasher.update(self.expiration.seconds.into());
+        hasher.update(self.salt);
+        hasher.finalize()
+    }
+}
+
+#[cfg(feature = "draft-layouts")]
+impl OffChainMessage for CancelAllOrders {}
+
 pub static SEPOLIA_DOMAIN: LazyLock<StarknetDomain> = LazyLock::new(|| StarknetDomain {
     name: "Perpetuals".to_string(),
     version: "v0".to_string(),
@@ -283,4 +856,629 @@ mod tests {
         println!("{}", expected_hash.to_hex_string());
         assert_eq!(hash, expected_hash);
     }
+
+    #[test]
+    fn test_order_notional() {
+        let order = |quote_amount| Order {
+            position_id: PositionId { value: 1 },
+            base_asset_id: AssetId { value: Felt::TWO },
+            base_amount: 3,
+            quote_asset_id: AssetId { value: Felt::ONE },
+            quote_amount,
+            fee_asset_id: AssetId { value: Felt::ONE },
+            fee_amount: 0,
+            expiration: Timestamp { seconds: 8 },
+            salt: Felt::ONE,
+        };
+        assert_eq!(order(156).notional(), 156);
+        assert_eq!(order(-156).notional(), 156);
+        assert_eq!(order(i64::MIN).notional(), 1 << 63);
+    }
+
+    #[test]
+    fn test_order_match_error() {
+        let order = |base_asset_id, base_amount, quote_amount| Order {
+            position_id: PositionId { value: 1 },
+            base_asset_id: AssetId {
+                value: base_asset_id,
+            },
+            base_amount,
+            quote_asset_id: AssetId { value: Felt::ONE },
+            quote_amount,
+            fee_asset_id: AssetId { value: Felt::ONE },
+            fee_amount: 0,
+            expiration: Timestamp { seconds: 8 },
+            salt: Felt::ONE,
+        };
+        // Buys 100 base for at most 156 quote
+        let buy = order(Felt::TWO, 100, -156);
+
+        assert_eq!(buy.match_error(&order(Felt::TWO, -100, 156)), None);
+        assert_eq!(order(Felt::TWO, -100, 156).match_error(&buy), None);
+        // Sells at a lower price, and partially
+        assert_eq!(buy.match_error(&order(Felt::TWO, -50, 70)), None);
+        assert_eq!(
+            buy.match_error(&order(Felt::TWO, -100, 157)),
+            Some("prices don't cross")
+        );
+        assert_eq!(
+            buy.match_error(&order(Felt::TWO, 100, -156)),
+            Some("same side")
+        );
+        assert_eq!(
+            buy.match_error(&order(Felt::THREE, -100, 156)),
+            Some("different base assets")
+        );
+        assert_eq!(
+            buy.match_error(&order(Felt::TWO, -100, -156)),
+            Some("base and quote amounts on the same side")
+        );
+        assert_eq!(
+            buy.match_error(&order(Felt::TWO, 0, 156)),
+            Some("zero amount")
+        );
+    }
+
+    #[test]
+    fn test_order_fingerprint() {
+        let order = Order {
+            position_id: PositionId { value: 100 },
+            base_asset_id: AssetId { value: Felt::TWO },
+            base_amount: 100,
+            quote_asset_id: AssetId { value: Felt::ONE },
+            quote_amount: -156,
+            fee_asset_id: AssetId { value: Felt::ONE },
+            fee_amount: 74,
+            expiration: Timestamp { seconds: 100 },
+            salt: Felt::from(123u64),
+        };
+        let user_public_key = Felt::from_hex_unchecked(
+            "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+        );
+        assert_eq!(
+            order.fingerprint(user_public_key),
+            Felt::from_hex_unchecked(
+                "0x4e11b09c4055f3c80e9f8f3d453353dc17d0a58b3d87f7622009f5a4c92e37a"
+            )
+        );
+        assert_ne!(
+            order.fingerprint(Felt::ONE),
+            order.fingerprint(user_public_key)
+        );
+    }
+
+    #[test]
+    fn test_fee_amount_from_rate() {
+        assert_eq!(fee_amount_from_rate(-156, 4743), Ok(74));
+        assert_eq!(fee_amount_from_rate(156, 4744), Ok(75));
+        assert_eq!(fee_amount_from_rate(10_000, 5), Ok(5));
+        assert_eq!(fee_amount_from_rate(0, 5), Ok(0));
+        assert!(fee_amount_from_rate(i64::MIN, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_asset_id_encoding() {
+        let encoding = AssetIdEncoding {
+            shift: 8,
+            tag: Felt::from(0x1u64),
+        };
+        let asset_id = encoding.encode(5).unwrap();
+        assert_eq!(asset_id.value, felt_hex!("0x501"));
+        assert_eq!(encoding.decode(&asset_id), Ok(5));
+
+        let max = encoding.encode(u64::MAX).unwrap();
+        assert_eq!(max.value, felt_hex!("0xffffffffffffffff01"));
+        assert_eq!(encoding.decode(&max), Ok(u64::MAX));
+
+        let untagged = AssetId {
+            value: felt_hex!("0x502"),
+        };
+        assert!(encoding.decode(&untagged).is_err());
+        let too_wide = AssetId {
+            value: felt_hex!("0x1000000000000000001"),
+        };
+        assert!(encoding.decode(&too_wide).is_err());
+    }
+
+    #[test]
+    fn test_asset_id_encoding_rejects_bad_config() {
+        let wide_tag = AssetIdEncoding {
+            shift: 8,
+            tag: Felt::from(0x100u64),
+        };
+        assert!(wide_tag.encode(5).is_err());
+        let wide_shift = AssetIdEncoding {
+            shift: 188,
+            tag: Felt::ZERO,
+        };
+        assert!(wide_shift.encode(5).is_err());
+    }
+
+    #[test]
+    fn test_asset_id_layout() {
+        let layout = AssetIdLayout::new(4, felt_hex!("0xff")).unwrap();
+        assert_eq!(layout.apply(felt_hex!("0x1234")), felt_hex!("0x340"));
+        assert_eq!(layout.apply(Felt::ZERO), Felt::ZERO);
+
+        let identity = AssetIdLayout::new(0, Felt::MAX).unwrap();
+        assert_eq!(identity.apply(Felt::MAX), Felt::MAX);
+
+        assert!(AssetIdLayout::new(1, Felt::MAX).is_err());
+        assert!(AssetIdLayout::new(200, felt_hex!("0xffffffffffffffff")).is_err());
+        assert!(AssetIdLayout::new(187, felt_hex!("0xffffffffffffffff")).is_ok());
+    }
+
+    #[test]
+    fn test_batch_commitment() {
+        let hashes = [Felt::from(11u64), Felt::from(22u64)];
+        let expected = Felt::from_dec_str(
+            "74462858777397475959220210798039056015164098968277128170232264053340901349",
+        )
+        .unwrap();
+        assert_eq!(batch_commitment(&hashes), expected);
+    }
+
+    // The draft layouts have no published vectors to check against, so these
+    // were computed with our own reference implementation and only guard
+    // against regressions
+    #[cfg(feature = "draft-layouts")]
+    mod draft_layouts {
+        use super::*;
+
+        #[test]
+        fn test_transfer_args_with_owner_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x3654e1a779f0576b81726d2c05579a6a2afc528b303fa8abc83a74ea02c447e",
+            );
+            let actual = TransferArgsWithOwner::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_transfer_args_with_owner_hashing() {
+            let transfer_args = TransferArgsWithOwner {
+                recipient: PositionId { value: 1 },
+                position_id: PositionId { value: 2 },
+                collateral_id: AssetId {
+                    value: Felt::from_dec_str("3").unwrap(),
+                },
+                amount: 4,
+                expiration: Timestamp { seconds: 5 },
+                salt: Felt::from_dec_str("6").unwrap(),
+                owner: Felt::from_dec_str("7").unwrap(),
+            };
+
+            let actual = transfer_args.hash();
+            let expected = Felt::from_dec_str(
+                "1870768712487455284225448365231855345474876501555219417798868114369118184448",
+            )
+            .unwrap();
+            assert_eq!(
+                actual, expected,
+                "Hashes do not match for TransferArgsWithOwner"
+            );
+        }
+
+        #[test]
+        fn test_transfer_args_with_nonce_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x34b430578db6f6faeeab2a11285d90702dab89e902fa2096f309f9d68560f29",
+            );
+            let actual = TransferArgsWithNonce::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_transfer_args_with_nonce_hashing() {
+            let transfer_args = TransferArgsWithNonce {
+                recipient: PositionId { value: 1 },
+                position_id: PositionId { value: 2 },
+                collateral_id: AssetId {
+                    value: Felt::from_dec_str("3").unwrap(),
+                },
+                amount: 4,
+                expiration: Timestamp { seconds: 5 },
+                salt: Felt::from_dec_str("6").unwrap(),
+                nonce: 7,
+            };
+
+            let actual = transfer_args.hash();
+            let expected = Felt::from_dec_str(
+                "2619072702413527327665090837158490856843857763933787438152150687279129337135",
+            )
+            .unwrap();
+            assert_eq!(
+                actual, expected,
+                "Hashes do not match for TransferArgsWithNonce"
+            );
+        }
+
+        #[test]
+        fn test_transfer_args_with_memo_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x3deb1454fd80ba885bbbead0bd8564f659e32207b91f9ca43182a7315c9a8ef",
+            );
+            let actual = TransferArgsWithMemo::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_transfer_args_with_memo_hashing() {
+            let transfer_args = TransferArgsWithMemo {
+                recipient: PositionId { value: 1 },
+                position_id: PositionId { value: 2 },
+                collateral_id: AssetId {
+                    value: Felt::from_dec_str("3").unwrap(),
+                },
+                amount: 4,
+                expiration: Timestamp { seconds: 5 },
+                salt: Felt::from_dec_str("6").unwrap(),
+                memo: Felt::from_dec_str("7").unwrap(),
+            };
+
+            let actual = transfer_args.hash();
+            let expected = Felt::from_dec_str(
+                "1855788677717592322176633982097633392114877781876465040821772828598191844382",
+            )
+            .unwrap();
+            assert_eq!(
+                actual, expected,
+                "Hashes do not match for TransferArgsWithMemo"
+            );
+        }
+
+        #[test]
+        fn test_reduce_only_order_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0xe51d2e0a63e533912f41e76b53cd5adfcef9931a0bbf9847275dc219a9d8a2",
+            );
+            let actual = ReduceOnlyOrder::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_reduce_only_order_hashing() {
+            let order = |reduce_only| ReduceOnlyOrder {
+                position_id: PositionId { value: 1 },
+                base_asset_id: AssetId {
+                    value: Felt::from_dec_str("2").unwrap(),
+                },
+                base_amount: 3,
+                quote_asset_id: AssetId {
+                    value: Felt::from_dec_str("4").unwrap(),
+                },
+                quote_amount: 5,
+                fee_asset_id: AssetId {
+                    value: Felt::from_dec_str("6").unwrap(),
+                },
+                fee_amount: 7,
+                expiration: Timestamp { seconds: 8 },
+                salt: Felt::from_dec_str("9").unwrap(),
+                reduce_only,
+            };
+
+            let expected = Felt::from_dec_str(
+                "2347497424229376313654693682248918713185846669817217999704904349634574040085",
+            )
+            .unwrap();
+            assert_eq!(
+                order(true).hash(),
+                expected,
+                "Hashes do not match for ReduceOnlyOrder"
+            );
+            let expected = Felt::from_dec_str(
+                "3331123258309975076578564485538389521893058039764865001905091534397401116828",
+            )
+            .unwrap();
+            assert_eq!(
+                order(false).hash(),
+                expected,
+                "Hashes do not match for ReduceOnlyOrder"
+            );
+        }
+
+        #[test]
+        fn test_post_only_order_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x1e4ab73bb781d8cd585da09a88da38b08d4f92d5f3cc47a1f441c510f7a5640",
+            );
+            let actual = PostOnlyOrder::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_post_only_order_hashing() {
+            let order = |reduce_only| PostOnlyOrder {
+                position_id: PositionId { value: 1 },
+                base_asset_id: AssetId {
+                    value: Felt::from_dec_str("2").unwrap(),
+                },
+                base_amount: 3,
+                quote_asset_id: AssetId {
+                    value: Felt::from_dec_str("4").unwrap(),
+                },
+                quote_amount: 5,
+                fee_asset_id: AssetId {
+                    value: Felt::from_dec_str("6").unwrap(),
+                },
+                fee_amount: 7,
+                expiration: Timestamp { seconds: 8 },
+                salt: Felt::from_dec_str("9").unwrap(),
+                reduce_only,
+                post_only: true,
+            };
+
+            let expected = Felt::from_dec_str(
+                "2458295061405334513714526767355413665204101331883503783560380676907390275019",
+            )
+            .unwrap();
+            assert_eq!(
+                order(false).hash(),
+                expected,
+                "Hashes do not match for PostOnlyOrder"
+            );
+            let expected = Felt::from_dec_str(
+                "789723256643168338114933910303904198013916199416439009655593018096306611850",
+            )
+            .unwrap();
+            assert_eq!(
+                order(true).hash(),
+                expected,
+                "Hashes do not match for PostOnlyOrder"
+            );
+        }
+
+        #[test]
+        fn test_leveraged_order_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x3f036218b50c2462144353507bb4b5d3230c378fae876cf1094d8d18a368409",
+            );
+            let actual = LeveragedOrder::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_leveraged_order_hashing() {
+            let order = |flags, leverage| LeveragedOrder {
+                position_id: PositionId { value: 1 },
+                base_asset_id: AssetId {
+                    value: Felt::from_dec_str("2").unwrap(),
+                },
+                base_amount: 3,
+                quote_asset_id: AssetId {
+                    value: Felt::from_dec_str("4").unwrap(),
+                },
+                quote_amount: 5,
+                fee_asset_id: AssetId {
+                    value: Felt::from_dec_str("6").unwrap(),
+                },
+                fee_amount: 7,
+                expiration: Timestamp { seconds: 8 },
+                salt: Felt::from_dec_str("9").unwrap(),
+                reduce_only: flags,
+                post_only: flags,
+                leverage,
+            };
+
+            let expected = Felt::from_dec_str(
+                "242234541748750719545787228161481986315768348375532385954666507907293463130",
+            )
+            .unwrap();
+            assert_eq!(
+                order(false, 10).hash(),
+                expected,
+                "Hashes do not match for LeveragedOrder"
+            );
+            let expected = Felt::from_dec_str(
+                "3172827840628333967675485581643693240816190799556555235970751935664368583781",
+            )
+            .unwrap();
+            assert_eq!(
+                order(true, 100).hash(),
+                expected,
+                "Hashes do not match for LeveragedOrder"
+            );
+        }
+
+        #[test]
+        fn test_settlement_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x84ca96c0e87e3bddd13c8f00f9dc9c76016a24c6a6ec27844ef4093de48722",
+            );
+            let actual = Settlement::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_settlement_hashing() {
+            let settlement = Settlement {
+                order_a: Order {
+                    position_id: PositionId { value: 1 },
+                    base_asset_id: AssetId {
+                        value: Felt::from_dec_str("2").unwrap(),
+                    },
+                    base_amount: 3,
+                    quote_asset_id: AssetId {
+                        value: Felt::from_dec_str("4").unwrap(),
+                    },
+                    quote_amount: 5,
+                    fee_asset_id: AssetId {
+                        value: Felt::from_dec_str("6").unwrap(),
+                    },
+                    fee_amount: 7,
+                    expiration: Timestamp { seconds: 8 },
+                    salt: Felt::from_dec_str("9").unwrap(),
+                },
+                order_b: Order {
+                    position_id: PositionId { value: 10 },
+                    base_asset_id: AssetId {
+                        value: Felt::from_dec_str("4").unwrap(),
+                    },
+                    base_amount: -5,
+                    quote_asset_id: AssetId {
+                        value: Felt::from_dec_str("2").unwrap(),
+                    },
+                    quote_amount: 3,
+                    fee_asset_id: AssetId {
+                        value: Felt::from_dec_str("6").unwrap(),
+                    },
+                    fee_amount: 1,
+                    expiration: Timestamp { seconds: 8 },
+                    salt: Felt::from_dec_str("11").unwrap(),
+                },
+                actual_a_amount: 3,
+                actual_b_amount: -5,
+                actual_a_fee: 7,
+                actual_b_fee: 1,
+            };
+
+            let actual = settlement.hash();
+            let expected = Felt::from_dec_str(
+                "1253418936847158980700898008367290294742520587928768977126292461975054198446",
+            )
+            .unwrap();
+            assert_eq!(actual, expected, "Hashes do not match for Settlement");
+        }
+
+        #[test]
+        fn test_withdraw_args_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x250a5fa378e8b771654bd43dcb34844534f9d1e29e16b14760d7936ea7f4b1d",
+            );
+            let actual = WithdrawArgs::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_withdraw_args_hashing() {
+            let withdraw_args = WithdrawArgs {
+                recipient: felt_hex!("0x71C7656EC7ab88b098defB751B7401B5f6d8976F"),
+                position_id: PositionId { value: 2 },
+                collateral_id: AssetId {
+                    value: Felt::from_dec_str("3").unwrap(),
+                },
+                amount: 4,
+                expiration: Timestamp { seconds: 5 },
+                salt: Felt::from_dec_str("6").unwrap(),
+            };
+
+            let actual = withdraw_args.hash();
+            let expected = Felt::from_dec_str(
+                "2504037613952544155894978881157747142906743972856738180662217321205781327577",
+            )
+            .unwrap();
+            assert_eq!(actual, expected, "Hashes do not match for WithdrawArgs");
+        }
+
+        #[test]
+        fn test_cancel_all_orders_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x3b581270bfe03412e9f8fdefa0ce06c2ce9dc2384b47e8f89f16c34d33bd650",
+            );
+            let actual = CancelAllOrders::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_cancel_all_orders_hashing() {
+            let cancel_all = CancelAllOrders {
+                position_id: PositionId { value: 1 },
+                expiration: Timestamp { seconds: 2 },
+                salt: Felt::from_dec_str("3").unwrap(),
+            };
+
+            let actual = cancel_all.hash();
+            let expected = Felt::from_dec_str(
+                "1665931544455040404019372798548829822398457351957302621634660886399710907453",
+            )
+            .unwrap();
+            assert_eq!(actual, expected, "Hashes do not match for CancelAllOrders");
+        }
+
+        #[test]
+        fn test_linked_order_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x1b52cba6814f5d2afe7b4ca76f80b0b001ac9922b515726faa31dd5666b4cdb",
+            );
+            let actual = LinkedOrder::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_linked_order_hashing() {
+            let linked_order = LinkedOrder {
+                order_hash: Felt::ONE,
+                parent_order_hash: Felt::TWO,
+            };
+
+            let actual = linked_order.hash();
+            let expected = Felt::from_dec_str(
+                "480196500139033543647263888578942884949893023556235964261256339149185669991",
+            )
+            .unwrap();
+            assert_eq!(actual, expected, "Hashes do not match for LinkedOrder");
+        }
+
+        #[test]
+        fn test_audited_order_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x2f1457c9df6aa1188a4b03c06ffb7eb71d7177c393a4d02046fafcea17e74d1",
+            );
+            let actual = AuditedOrder::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_audited_order_hashing() {
+            let audited_order = AuditedOrder {
+                order_hash: Felt::ONE,
+                audit_tag: Felt::TWO,
+            };
+
+            let actual = audited_order.hash();
+            let expected = Felt::from_dec_str(
+                "394510124714358233344356309414488116620770717444438272553130063116064756770",
+            )
+            .unwrap();
+            assert_eq!(actual, expected, "Hashes do not match for AuditedOrder");
+        }
+
+        #[test]
+        fn test_order_with_fees_selector() {
+            let expected = Felt::from_hex_unchecked(
+                "0x162154c629ed220f11453a4f88e13578a20489d7c4514ac010a0208f1fa3dad",
+            );
+            let actual = OrderWithFees::SELECTOR;
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_order_with_fees_hashing() {
+            let order = OrderWithFees {
+                position_id: PositionId { value: 1 },
+                base_asset_id: AssetId {
+                    value: Felt::from_dec_str("2").unwrap(),
+                },
+                base_amount: 3,
+                quote_asset_id: AssetId {
+                    value: Felt::from_dec_str("4").unwrap(),
+                },
+                quote_amount: 5,
+                fee_asset_id: AssetId {
+                    value: Felt::from_dec_str("6").unwrap(),
+                },
+                maker_fee_amount: 7,
+                taker_fee_amount: 8,
+                expiration: Timestamp { seconds: 9 },
+                salt: Felt::from_dec_str("10").unwrap(),
+            };
+
+            let actual = order.hash();
+            let expected = Felt::from_dec_str(
+                "1122311625637412040741405630525059519638516897168243228258967876601336341821",
+            )
+            .unwrap();
+            assert_eq!(actual, expected, "Hashes do not match for OrderWithFees");
+        }
+    }
 }
@@ -0,0 +1,221 @@
+use starknet::core::utils::cairo_short_string_to_felt;
+use starknet::core::utils::get_contract_address;
+use starknet::core::utils::get_selector_from_name;
+use starknet_crypto::poseidon_hash_many;
+use starknet_crypto::Felt;
+
+use std::sync::LazyLock;
+
+static INVOKE_FELT: LazyLock<Felt> =
+    LazyLock::new(|| cairo_short_string_to_felt("invoke").unwrap());
+
+const TRANSACTION_VERSION_3: Felt = Felt::THREE;
+
+pub struct ResourceBounds {
+    pub max_amount: u64,
+    pub max_price_per_unit: u128,
+}
+
+impl ResourceBounds {
+    // Packs the bounds as `name << 192 | max_amount << 128 | max_price_per_unit`
+    fn encode(&self, resource_name: &str) -> Felt {
+        let name = cairo_short_string_to_felt(resource_name).unwrap().to_bytes_be();
+        let mut bytes = [0u8; 32];
+        bytes[..8].copy_from_slice(&name[24..]);
+        bytes[8..16].copy_from_slice(&self.max_amount.to_be_bytes());
+        bytes[16..].copy_from_slice(&self.max_price_per_unit.to_be_bytes());
+        Felt::from_bytes_be(&bytes)
+    }
+}
+
+pub struct InvokeTransactionV3 {
+    pub sender_address: Felt,
+    pub calldata: Vec<Felt>,
+    pub nonce: Felt,
+    pub tip: u64,
+    pub l1_gas: ResourceBounds,
+    pub l2_gas: ResourceBounds,
+    pub l1_data_gas: ResourceBounds,
+    pub paymaster_data: Vec<Felt>,
+    pub account_deployment_data: Vec<Felt>,
+    pub nonce_data_availability_mode: u32,
+    pub fee_data_availability_mode: u32,
+    pub chain_id: Felt,
+}
+
+/// One call of a multicall: `selector` invoked on the contract at `to`.
+pub struct Call {
+    pub to: Felt,
+    pub selector: Felt,
+    pub calldata: Vec<Felt>,
+}
+
+/// Encodes calls as the `__execute__` calldata of a Cairo 1 account: the
+/// number of calls, then each call's address, selector, calldata length and
+/// calldata.
+pub fn encode_multicall(calls: &[Call]) -> Vec<Felt> {
+    let mut encoded = vec![calls.len().into()];
+    for call in calls {
+        encoded.push(call.to);
+        encoded.push(call.selector);
+        encoded.push(call.calldata.len().into());
+        encoded.extend_from_slice(&call.calldata);
+    }
+    encoded
+}
+
+/// Hash of a multicall's encoded calldata, as it enters the transaction hash.
+pub fn multicall_hash(calls: &[Call]) -> Felt {
+    poseidon_hash_many(&encode_multicall(calls))
+}
+
+impl InvokeTransactionV3 {
+    /// Transaction hash as defined for v3 invoke transactions since Starknet
+    /// v0.13.4, where the fee commitment covers L1 gas, L2 gas and L1 data gas.
+    pub fn hash(&self) -> Felt {
+        let fee_fields_hash = poseidon_hash_many(&[
+            self.tip.into(),
+            self.l1_gas.encode("L1_GAS"),
+            self.l2_gas.encode("L2_GAS"),
+            self.l1_data_gas.encode("L1_DATA"),
+        ]);
+        let data_availability_modes = ((self.nonce_data_availability_mode as u64) << 32)
+            + self.fee_data_availability_mode as u64;
+        poseidon_hash_many(&[
+            *INVOKE_FELT,
+            TRANSACTION_VERSION_3,
+            self.sender_address,
+            fee_fields_hash,
+            poseidon_hash_many(&self.paymaster_data),
+            self.chain_id,
+            self.nonce,
+            data_availability_modes.into(),
+            poseidon_hash_many(&self.account_deployment_data),
+            poseidon_hash_many(&self.calldata),
+        ])
+    }
+}
+
+/// Address an account contract of `class_hash` deploys to, with the
+/// constructor calldata hashed in as the StarkNet contract address formula
+/// requires. `deployer_address` is zero for counterfactual deployments.
+pub fn compute_account_address(
+    class_hash: Felt,
+    salt: Felt,
+    constructor_calldata: &[Felt],
+    deployer_address: Felt,
+) -> Felt {
+    get_contract_address(salt, class_hash, constructor_calldata, deployer_address)
+}
+
+/// Selector of the entrypoint called `name`, the masked keccak of the name
+/// that calldata and L1 handlers refer to the function by.
+pub fn entrypoint_selector(name: &str) -> Result<Felt, String> {
+    get_selector_from_name(name).map_err(|_| format!("Entrypoint name {} is not ASCII", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt_hex;
+
+    use super::*;
+
+    #[test]
+    fn test_resource_bounds_encoding() {
+        let bounds = ResourceBounds {
+            max_amount: 0x10,
+            max_price_per_unit: 0x20,
+        };
+        let expected = felt_hex!("0x4c315f474153000000000000001000000000000000000000000000000020");
+        assert_eq!(bounds.encode("L1_GAS"), expected);
+    }
+
+    #[test]
+    fn test_invoke_v3_hash() {
+        let transaction = InvokeTransactionV3 {
+            sender_address: Felt::from_hex_unchecked("0x123"),
+            calldata: vec![Felt::ONE, Felt::TWO, Felt::THREE],
+            nonce: Felt::from(5u64),
+            tip: 0,
+            l1_gas: ResourceBounds {
+                max_amount: 100,
+                max_price_per_unit: 1000,
+            },
+            l2_gas: ResourceBounds {
+                max_amount: 200,
+                max_price_per_unit: 2000,
+            },
+            l1_data_gas: ResourceBounds {
+                max_amount: 300,
+                max_price_per_unit: 3000,
+            },
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: 0,
+            fee_data_availability_mode: 0,
+            chain_id: cairo_short_string_to_felt("SN_SEPOLIA").unwrap(),
+        };
+        let expected =
+            felt_hex!("0x7eb28af4d702559e5dfe63d01232197cfda94eea3116ffc2ac837dba6ec5832");
+        assert_eq!(transaction.hash(), expected);
+    }
+
+    #[test]
+    fn test_multicall_hash() {
+        let calls = [
+            Call {
+                to: Felt::from_hex_unchecked("0x123"),
+                selector: entrypoint_selector("transfer").unwrap(),
+                calldata: vec![
+                    Felt::from_hex_unchecked("0x456"),
+                    Felt::from(100u64),
+                    Felt::ZERO,
+                ],
+            },
+            Call {
+                to: Felt::from_hex_unchecked("0x789"),
+                selector: entrypoint_selector("__execute__").unwrap(),
+                calldata: vec![],
+            },
+        ];
+        assert_eq!(encode_multicall(&calls).len(), 1 + 3 + 3 + 3);
+        let expected =
+            felt_hex!("0x3ca9952921038692d919cd0adb2872cf3563a0edbf8cdc69bcaffad8358b23f");
+        assert_eq!(multicall_hash(&calls), expected);
+    }
+
+    #[test]
+    fn test_compute_account_address() {
+        let class_hash =
+            felt_hex!("0x33434ad846cdd5f23eb73ff09fe6fddd568284a0fb7d1be20ee482f044dabe2");
+        let public_key =
+            felt_hex!("0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43");
+
+        let address = compute_account_address(class_hash, public_key, &[public_key], Felt::ZERO);
+        let expected =
+            felt_hex!("0x57dce334bd39e0fcff97b11a95e79d0799c5e373435a3859673304b0e74a134");
+        assert_eq!(address, expected);
+
+        let address = compute_account_address(class_hash, public_key, &[], Felt::ZERO);
+        let expected =
+            felt_hex!("0x6f8696a274280c228a55ced567784de9ea8b08f6e9ce958c1560bc9c1d2033d");
+        assert_eq!(address, expected);
+    }
+
+    #[test]
+    fn test_entrypoint_selector() {
+        assert_eq!(
+            entrypoint_selector("__execute__").unwrap(),
+            felt_hex!("0x15d40a3d6ca2ac30f4031e42be28da9b056fef9bb7357ac5e85627ee876e5ad")
+        );
+        assert_eq!(
+            entrypoint_selector("__validate__").unwrap(),
+            felt_hex!("0x162da33a4585851fe8d3af3c2a9c60b557814e221e0d4f30ff0b2189d9c7775")
+        );
+        assert_eq!(
+            entrypoint_selector("transfer").unwrap(),
+            felt_hex!("0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e")
+        );
+        assert!(entrypoint_selector("tränsfer").is_err());
+    }
+}
@@ -0,0 +1,988 @@
+use num_bigint::BigUint;
+use pyo3::exceptions::PyTypeError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBool;
+use pyo3::types::PyDict;
+use pyo3::types::PyLong;
+use pyo3::types::PyString;
+use std::sync::RwLock;
+
+use rust_crypto_lib_base::starknet_messages::AssetId;
+use rust_crypto_lib_base::starknet_messages::AssetIdEncoding;
+use rust_crypto_lib_base::starknet_messages::AssetIdLayout;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::AuditedOrder;
+use rust_crypto_lib_base::starknet_messages::Hashable;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::LeveragedOrder;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::LinkedOrder;
+use rust_crypto_lib_base::starknet_messages::OffChainMessage;
+use rust_crypto_lib_base::starknet_messages::Order;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::OrderWithFees;
+use rust_crypto_lib_base::starknet_messages::PositionId;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::PostOnlyOrder;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::ReduceOnlyOrder;
+use rust_crypto_lib_base::starknet_messages::StarknetDomain;
+use rust_crypto_lib_base::starknet_messages::Timestamp;
+use rust_crypto_lib_base::starknet_messages::TransferArgs;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::TransferArgsWithMemo;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::TransferArgsWithNonce;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::TransferArgsWithOwner;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::WithdrawArgs;
+use starknet_crypto::Felt;
+
+use crate::str_to_field_element;
+
+// Python ints are a common mistake for string arguments; accept them, but not
+// bools, which Python also treats as ints.
+fn is_int(ob: &PyAny) -> bool {
+    ob.is_instance_of::<PyLong>() && !ob.is_instance_of::<PyBool>()
+}
+
+fn type_mismatch(ob: &PyAny) -> PyErr {
+    // pyo3 prefixes TypeErrors with the name of the argument being extracted
+    match ob.get_type().name() {
+        Ok(name) => PyTypeError::new_err(format!("expected str or int, got {}", name)),
+        Err(e) => e,
+    }
+}
+
+// Options that need a message layout the exchange hasn't published are only
+// hashed in builds with the draft-layouts feature
+#[cfg(not(feature = "draft-layouts"))]
+pub(crate) fn draft_layout_disabled(option: &str) -> String {
+    format!(
+        "{} needs a message layout the exchange hasn't published; build with the draft-layouts feature to use it",
+        option
+    )
+}
+
+/// A base-10 integer argument, given from Python as `str` or `int`.
+pub(crate) struct DecimalArg(pub(crate) String);
+
+impl<'source> FromPyObject<'source> for DecimalArg {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(value) = ob.downcast::<PyString>() {
+            return Ok(DecimalArg(value.to_str()?.to_owned()));
+        }
+        if is_int(ob) {
+            return Ok(DecimalArg(ob.str()?.to_str()?.to_owned()));
+        }
+        Err(type_mismatch(ob))
+    }
+}
+
+impl DecimalArg {
+    pub(crate) fn parse<T>(&self, field: &str) -> Result<T, String>
+    where
+        T: std::str::FromStr,
+        T::Err: std::fmt::Display,
+    {
+        str_to_int(field, &self.0)
+    }
+}
+
+/// A felt argument, given from Python as a hex `str` or an `int`.
+pub(crate) struct HexArg(pub(crate) String);
+
+impl<'source> FromPyObject<'source> for HexArg {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(value) = ob.downcast::<PyString>() {
+            return Ok(HexArg(value.to_str()?.to_owned()));
+        }
+        if is_int(ob) {
+            let hex = ob.call_method1("__format__", ("#x",))?;
+            return Ok(HexArg(hex.extract()?));
+        }
+        Err(type_mismatch(ob))
+    }
+}
+
+impl HexArg {
+    pub(crate) fn parse(&self, field: &str) -> Result<Felt, String> {
+        str_to_field_element(&self.0).map_err(|e| format!("Invalid {}: {}", field, e))
+    }
+
+    // Like `parse`, but rejects values at or above the field prime instead of
+    // reducing them, for values where two encodings of one felt must not both
+    // be accepted
+    pub(crate) fn parse_canonical(&self, field: &str) -> Result<Felt, String> {
+        let felt = self.parse(field)?;
+        let digits = self
+            .0
+            .strip_prefix("0x")
+            .or_else(|| self.0.strip_prefix("0X"))
+            .unwrap_or(&self.0);
+        match BigUint::parse_bytes(digits.as_bytes(), 16) {
+            Some(value) => int_to_felt(field, &value),
+            None => Ok(felt),
+        }
+    }
+}
+
+// Layout set by rs_configure_asset_encoding; None leaves asset ids untouched
+pub(crate) static ASSET_LAYOUT: RwLock<Option<AssetIdLayout>> = RwLock::new(None);
+
+// Applies the configured asset id layout, if any
+pub(crate) fn apply_asset_layout(asset_id: Felt) -> Felt {
+    match &*ASSET_LAYOUT.read().unwrap() {
+        Some(layout) => layout.apply(asset_id),
+        None => asset_id,
+    }
+}
+
+// Parses an asset id and applies the configured layout, if any
+fn parse_asset_id(arg: &HexArg, field: &str) -> Result<Felt, String> {
+    Ok(apply_asset_layout(arg.parse(field)?))
+}
+
+// Converts a decimal string to an integer, naming the field on failure. A
+// well-formed integer that doesn't fit gets a range error instead of a parse
+// one, so a negative value for an unsigned field reads as such
+pub(crate) fn str_to_int<T>(field: &str, value: &str) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value.parse::<T>().map_err(|e| {
+        let digits = value.strip_prefix('-').unwrap_or(value);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return format!("Failed to parse {} from {}: {}", field, value, e);
+        }
+        let signed = "-1".parse::<T>().is_ok();
+        if !signed && value.starts_with('-') {
+            format!("field `{}` must be non-negative, got {}", field, value)
+        } else {
+            format!(
+                "field `{}` must be within the {} range, got {}",
+                field,
+                std::any::type_name::<T>(),
+                value
+            )
+        }
+    })
+}
+
+// Converts a Python int to a felt, naming the field when it is not below the
+// field prime rather than silently reducing it
+pub(crate) fn int_to_felt(field: &str, value: &BigUint) -> Result<Felt, String> {
+    let prime = BigUint::from_bytes_be(&Felt::MAX.to_bytes_be()) + 1u32;
+    if *value >= prime {
+        return Err(format!(
+            "field `{}` must be below the field prime, got {}",
+            field, value
+        ));
+    }
+    let bytes = value.to_bytes_be();
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(Felt::from_bytes_be(&padded))
+}
+
+// Combines a "buy"/"sell" side with an unsigned magnitude into the signed
+// amount an order carries: buys are positive, sells negative
+pub(crate) fn signed_amount(
+    field: &str,
+    side: &str,
+    abs_amount: &DecimalArg,
+) -> Result<DecimalArg, String> {
+    let magnitude = abs_amount.parse::<u64>(&format!("{}_abs_amount", field))?;
+    let magnitude = i64::try_from(magnitude)
+        .map_err(|_| format!("{}_abs_amount {} does not fit in an i64", field, magnitude))?;
+    let amount = match side {
+        "buy" => magnitude,
+        "sell" => -magnitude,
+        _ => {
+            return Err(format!(
+                "Invalid {}_side {}, expected buy or sell",
+                field, side
+            ))
+        }
+    };
+    Ok(DecimalArg(amount.to_string()))
+}
+
+// Scales a human-readable decimal amount such as "-1.25" by the instrument's
+// quantum, in exact integer math, into the integer amount an order carries.
+// Rejects amounts that don't come out whole, rather than rounding them
+pub(crate) fn scaled_amount(
+    field: &str,
+    human_amount: &str,
+    quantum: &DecimalArg,
+) -> Result<DecimalArg, String> {
+    let quantum = quantum.parse::<u64>(&format!("{}_quantum", field))?;
+    let invalid = || {
+        format!(
+            "Invalid {}_human_amount {}, expected a decimal number",
+            field, human_amount
+        )
+    };
+    let (negative, unsigned) = match human_amount.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, human_amount),
+    };
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if whole.is_empty()
+        || !whole.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let overflow = || {
+        format!(
+            "{}_human_amount {} times {}_quantum {} does not fit in an i64",
+            field, human_amount, field, quantum
+        )
+    };
+    let digits = format!("{}{}", whole, fraction);
+    let mantissa = digits.parse::<i128>().map_err(|_| overflow())?;
+    let divisor = u32::try_from(fraction.len())
+        .ok()
+        .and_then(|exponent| 10i128.checked_pow(exponent))
+        .ok_or_else(overflow)?;
+    let scaled = mantissa
+        .checked_mul(i128::from(quantum))
+        .ok_or_else(overflow)?;
+    if scaled % divisor != 0 {
+        return Err(format!(
+            "{}_human_amount {} times {}_quantum {} is not a whole number",
+            field, human_amount, field, quantum
+        ));
+    }
+    let scaled = if negative {
+        -(scaled / divisor)
+    } else {
+        scaled / divisor
+    };
+    let amount = i64::try_from(scaled).map_err(|_| overflow())?;
+    Ok(DecimalArg(amount.to_string()))
+}
+
+// Rejects an expiration outside [min_expiration, max_expiration]; either bound
+// may be left out
+pub(crate) fn check_expiration_window(
+    expiration: &DecimalArg,
+    min_expiration: Option<&DecimalArg>,
+    max_expiration: Option<&DecimalArg>,
+) -> Result<(), String> {
+    let expiration = expiration.parse::<u64>("expiration")?;
+    if let Some(min_expiration) = min_expiration {
+        let min_expiration = min_expiration.parse::<u64>("min_expiration")?;
+        if expiration < min_expiration {
+            return Err(format!(
+                "expiration {} is before min_expiration {}",
+                expiration, min_expiration
+            ));
+        }
+    }
+    if let Some(max_expiration) = max_expiration {
+        let max_expiration = max_expiration.parse::<u64>("max_expiration")?;
+        if expiration > max_expiration {
+            return Err(format!(
+                "expiration {} is after max_expiration {}",
+                expiration, max_expiration
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Domain revisions we can hash. Revision 0 hashes the domain differently
+// (Pedersen over "StarkNetDomain" with a felt chain id), which
+// StarknetDomain::hash doesn't implement, so it is rejected rather than
+// hashed with the revision 1 encoding
+const SUPPORTED_REVISIONS: [u32; 1] = [1];
+
+pub(crate) fn to_domain(
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: &str,
+) -> Result<StarknetDomain, String> {
+    let revision = str_to_int::<u32>("domain_revision", domain_revision)?;
+    if !SUPPORTED_REVISIONS.contains(&revision) {
+        return Err(format!(
+            "Unsupported domain_revision {}, expected one of {:?} (revision 0 is not implemented)",
+            revision, SUPPORTED_REVISIONS
+        ));
+    }
+    Ok(StarknetDomain {
+        name: domain_name,
+        version: domain_version,
+        chain_id: domain_chain_id,
+        revision,
+    })
+}
+
+// Leverage multipliers an order can request
+const MAX_LEVERAGE: u8 = 100;
+
+pub(crate) fn parse_leverage(leverage: &DecimalArg) -> Result<u8, String> {
+    let leverage = leverage.parse::<u64>("leverage")?;
+    if !(1..=u64::from(MAX_LEVERAGE)).contains(&leverage) {
+        return Err(format!(
+            "field `leverage` must be within 1..={}, got {}",
+            MAX_LEVERAGE, leverage
+        ));
+    }
+    Ok(leverage as u8)
+}
+
+// Splits a signature given as one `0x`-prefixed blob of 128 hex digits, as API
+// payloads carry it, into r (the first 64 digits) and s (the last 64)
+pub(crate) fn split_concat_signature(sig_concat: &str) -> Result<(Felt, Felt), String> {
+    let digits = sig_concat
+        .strip_prefix("0x")
+        .ok_or_else(|| "sig_concat must start with 0x".to_string())?;
+    if digits.len() != 128 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!(
+            "sig_concat must be 0x followed by 128 hex digits, got {} characters after 0x",
+            digits.len()
+        ));
+    }
+    let (r, s) = digits.split_at(64);
+    let r = str_to_field_element(&format!("0x{}", r))
+        .map_err(|e| format!("Invalid r in sig_concat: {}", e))?;
+    let s = str_to_field_element(&format!("0x{}", s))
+        .map_err(|e| format!("Invalid s in sig_concat: {}", e))?;
+    Ok((r, s))
+}
+
+// Asset id encoding as received from Python, as a dict with "shift" and "tag"
+#[derive(FromPyObject)]
+pub(crate) struct AssetIdConfig {
+    #[pyo3(item)]
+    pub(crate) shift: u32,
+    #[pyo3(item("tag"))]
+    pub(crate) tag_hex: HexArg,
+}
+
+impl AssetIdConfig {
+    pub(crate) fn to_encoding(&self) -> Result<AssetIdEncoding, String> {
+        Ok(AssetIdEncoding {
+            shift: self.shift,
+            tag: self.tag_hex.parse("tag")?,
+        })
+    }
+}
+
+// Order fields as received from Python, before any parsing. Batch functions
+// take these as dicts keyed by the names used in the Python wrappers.
+#[derive(FromPyObject)]
+pub(crate) struct OrderFields {
+    #[pyo3(item)]
+    pub(crate) position_id: DecimalArg,
+    #[pyo3(item("base_asset_id"))]
+    pub(crate) base_asset_id_hex: HexArg,
+    #[pyo3(item)]
+    pub(crate) base_amount: DecimalArg,
+    #[pyo3(item("quote_asset_id"))]
+    pub(crate) quote_asset_id_hex: HexArg,
+    #[pyo3(item)]
+    pub(crate) quote_amount: DecimalArg,
+    #[pyo3(item("fee_asset_id"))]
+    pub(crate) fee_asset_id_hex: HexArg,
+    #[pyo3(item)]
+    pub(crate) fee_amount: DecimalArg,
+    #[pyo3(item)]
+    pub(crate) expiration: DecimalArg,
+    #[pyo3(item)]
+    pub(crate) salt: DecimalArg,
+    #[pyo3(item("user_public_key"))]
+    pub(crate) user_public_key_hex: HexArg,
+}
+
+// Assembles an order from asset ids that are already parsed and laid out,
+// parsing the decimal fields
+pub(crate) fn build_order(
+    position_id: &DecimalArg,
+    [base_asset_id, quote_asset_id, fee_asset_id]: [Felt; 3],
+    base_amount: &DecimalArg,
+    quote_amount: &DecimalArg,
+    fee_amount: &DecimalArg,
+    expiration: &DecimalArg,
+    salt: &DecimalArg,
+) -> Result<Order, String> {
+    let position_id = position_id.parse::<u32>("position_id")?;
+    let base_amount = base_amount.parse::<i64>("base_amount")?;
+    let quote_amount = quote_amount.parse::<i64>("quote_amount")?;
+    let fee_amount = fee_amount.parse::<u64>("fee_amount")?;
+    let expiration = expiration.parse::<u64>("expiration")?;
+    let salt = salt.parse::<u64>("salt")?;
+
+    Ok(Order {
+        position_id: PositionId { value: position_id },
+        base_asset_id: AssetId {
+            value: base_asset_id,
+        },
+        base_amount,
+        quote_asset_id: AssetId {
+            value: quote_asset_id,
+        },
+        quote_amount,
+        fee_asset_id: AssetId {
+            value: fee_asset_id,
+        },
+        fee_amount,
+        expiration: Timestamp {
+            seconds: expiration,
+        },
+        salt: salt.into(),
+    })
+}
+
+// Trading an asset against itself hashes fine but is rejected on-chain
+pub(crate) fn check_distinct_asset_ids(
+    base_asset_id: Felt,
+    quote_asset_id: Felt,
+) -> Result<(), String> {
+    if base_asset_id == quote_asset_id {
+        return Err(format!(
+            "base_asset_id and quote_asset_id are both {}",
+            base_asset_id.to_hex_string()
+        ));
+    }
+    Ok(())
+}
+
+impl OrderFields {
+    pub(crate) fn to_order(&self) -> Result<Order, String> {
+        let base_asset_id = parse_asset_id(&self.base_asset_id_hex, "base_asset_id")?;
+        let quote_asset_id = parse_asset_id(&self.quote_asset_id_hex, "quote_asset_id")?;
+        let fee_asset_id = parse_asset_id(&self.fee_asset_id_hex, "fee_asset_id")?;
+        build_order(
+            &self.position_id,
+            [base_asset_id, quote_asset_id, fee_asset_id],
+            &self.base_amount,
+            &self.quote_amount,
+            &self.fee_amount,
+            &self.expiration,
+            &self.salt,
+        )
+    }
+
+    pub(crate) fn check_distinct_assets(&self) -> Result<(), String> {
+        check_distinct_asset_ids(
+            parse_asset_id(&self.base_asset_id_hex, "base_asset_id")?,
+            parse_asset_id(&self.quote_asset_id_hex, "quote_asset_id")?,
+        )
+    }
+
+    pub(crate) fn user_public_key(&self) -> Result<Felt, String> {
+        self.user_public_key_hex.parse("user_public_key")
+    }
+
+    // Everything that would make hashing or submitting the order fail
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        self.to_order()?;
+        self.user_public_key()?;
+        self.check_distinct_assets()
+    }
+
+    pub(crate) fn message_hash(&self, domain: &StarknetDomain) -> Result<Felt, String> {
+        let order = self.to_order()?;
+        let user_key = self.user_public_key()?;
+        Ok(order.message_hash(domain, user_key).unwrap())
+    }
+
+    // Hashes with the reduce-only layout, which binds the flag as an extra felt
+    #[cfg(feature = "draft-layouts")]
+    pub(crate) fn reduce_only_message_hash(&self, domain: &StarknetDomain) -> Result<Felt, String> {
+        let user_key = self.user_public_key()?;
+        let message = self.to_reduce_only_order()?;
+        Ok(message.message_hash(domain, user_key).unwrap())
+    }
+
+    // Hashes with the post-only layout, which binds both flags as extra felts
+    #[cfg(feature = "draft-layouts")]
+    pub(crate) fn post_only_message_hash(
+        &self,
+        domain: &StarknetDomain,
+        reduce_only: bool,
+    ) -> Result<Felt, String> {
+        let user_key = self.user_public_key()?;
+        let message = self.to_post_only_order(reduce_only)?;
+        Ok(message.message_hash(domain, user_key).unwrap())
+    }
+
+    // Hashes with the leveraged layout, which binds both flags and the
+    // leverage as extra felts
+    #[cfg(feature = "draft-layouts")]
+    pub(crate) fn leveraged_message_hash(
+        &self,
+        domain: &StarknetDomain,
+        reduce_only: bool,
+        post_only: bool,
+        leverage: u8,
+    ) -> Result<Felt, String> {
+        let user_key = self.user_public_key()?;
+        let message = self.to_leveraged_order(reduce_only, post_only, leverage)?;
+        Ok(message.message_hash(domain, user_key).unwrap())
+    }
+
+    // Struct hash of the order in the plain, reduce-only, post-only or
+    // leveraged layout
+    #[cfg(feature = "draft-layouts")]
+    fn layout_hash(
+        &self,
+        reduce_only: bool,
+        post_only: bool,
+        leverage: Option<u8>,
+    ) -> Result<Felt, String> {
+        Ok(if let Some(leverage) = leverage {
+            self.to_leveraged_order(reduce_only, post_only, leverage)?
+                .hash()
+        } else if post_only {
+            self.to_post_only_order(reduce_only)?.hash()
+        } else if reduce_only {
+            self.to_reduce_only_order()?.hash()
+        } else {
+            self.to_order()?.hash()
+        })
+    }
+
+    // Hashes a child order bound to its parent order hash, wrapping the
+    // order's struct hash in the plain, reduce-only, post-only or leveraged
+    // layout
+    #[cfg(feature = "draft-layouts")]
+    pub(crate) fn linked_message_hash(
+        &self,
+        domain: &StarknetDomain,
+        reduce_only: bool,
+        post_only: bool,
+        leverage: Option<u8>,
+        parent_order_hash: Felt,
+    ) -> Result<Felt, String> {
+        let user_key = self.user_public_key()?;
+        let message = LinkedOrder {
+            order_hash: self.layout_hash(reduce_only, post_only, leverage)?,
+            parent_order_hash,
+        };
+        Ok(message.message_hash(domain, user_key).unwrap())
+    }
+
+    // Hashes an order bound to an audit tag, wrapping the struct hash of the
+    // order in its layout, or of its link to a parent order if it has one
+    #[cfg(feature = "draft-layouts")]
+    pub(crate) fn audited_message_hash(
+        &self,
+        domain: &StarknetDomain,
+        reduce_only: bool,
+        post_only: bool,
+        leverage: Option<u8>,
+        parent_order_hash: Option<Felt>,
+        audit_tag: Felt,
+    ) -> Result<Felt, String> {
+        let user_key = self.user_public_key()?;
+        let mut order_hash = self.layout_hash(reduce_only, post_only, leverage)?;
+        if let Some(parent_order_hash) = parent_order_hash {
+            order_hash = LinkedOrder {
+                order_hash,
+                parent_order_hash,
+            }
+            .hash();
+        }
+        let message = AuditedOrder {
+            order_hash,
+            audit_tag,
+        };
+        Ok(message.message_hash(domain, user_key).unwrap())
+    }
+
+    #[cfg(feature = "draft-layouts")]
+    fn to_reduce_only_order(&self) -> Result<ReduceOnlyOrder, String> {
+        let order = self.to_order()?;
+        Ok(ReduceOnlyOrder {
+            position_id: order.position_id,
+            base_asset_id: order.base_asset_id,
+            base_amount: order.base_amount,
+            quote_asset_id: order.quote_asset_id,
+            quote_amount: order.quote_amount,
+            fee_asset_id: order.fee_asset_id,
+            fee_amount: order.fee_amount,
+            expiration: order.expiration,
+            salt: order.salt,
+            reduce_only: true,
+        })
+    }
+
+    #[cfg(feature = "draft-layouts")]
+    fn to_post_only_order(&self, reduce_only: bool) -> Result<PostOnlyOrder, String> {
+        let order = self.to_order()?;
+        Ok(PostOnlyOrder {
+            position_id: order.position_id,
+            base_asset_id: order.base_asset_id,
+            base_amount: order.base_amount,
+            quote_asset_id: order.quote_asset_id,
+            quote_amount: order.quote_amount,
+            fee_asset_id: order.fee_asset_id,
+            fee_amount: order.fee_amount,
+            expiration: order.expiration,
+            salt: order.salt,
+            reduce_only,
+            post_only: true,
+        })
+    }
+
+    #[cfg(feature = "draft-layouts")]
+    fn to_leveraged_order(
+        &self,
+        reduce_only: bool,
+        post_only: bool,
+        leverage: u8,
+    ) -> Result<LeveragedOrder, String> {
+        let order = self.to_order()?;
+        Ok(LeveragedOrder {
+            position_id: order.position_id,
+            base_asset_id: order.base_asset_id,
+            base_amount: order.base_amount,
+            quote_asset_id: order.quote_asset_id,
+            quote_amount: order.quote_amount,
+            fee_asset_id: order.fee_asset_id,
+            fee_amount: order.fee_amount,
+            expiration: order.expiration,
+            salt: order.salt,
+            reduce_only,
+            post_only,
+            leverage,
+        })
+    }
+
+    // Hashes with the fee-tier layout; fee_amount is ignored in favour of the
+    // separate maker and taker fees
+    #[cfg(feature = "draft-layouts")]
+    pub(crate) fn message_hash_with_fees(
+        &self,
+        domain: &StarknetDomain,
+        maker_fee_amount: &DecimalArg,
+        taker_fee_amount: &DecimalArg,
+    ) -> Result<Felt, String> {
+        let order = self.to_order()?;
+        let user_key = self.user_public_key()?;
+        let message = OrderWithFees {
+            position_id: order.position_id,
+            base_asset_id: order.base_asset_id,
+            base_amount: order.base_amount,
+            quote_asset_id: order.quote_asset_id,
+            quote_amount: order.quote_amount,
+            fee_asset_id: order.fee_asset_id,
+            maker_fee_amount: maker_fee_amount.parse::<u64>("maker_fee_amount")?,
+            taker_fee_amount: taker_fee_amount.parse::<u64>("taker_fee_amount")?,
+            expiration: order.expiration,
+            salt: order.salt,
+        };
+        Ok(message.message_hash(domain, user_key).unwrap())
+    }
+}
+
+// Transfer fields as received from Python, before any parsing
+pub(crate) struct TransferFields {
+    pub(crate) recipient_position_id: DecimalArg,
+    pub(crate) sender_position_id: DecimalArg,
+    pub(crate) collateral_id_hex: HexArg,
+    pub(crate) amount: DecimalArg,
+    pub(crate) expiration: DecimalArg,
+    pub(crate) salt: DecimalArg,
+    pub(crate) user_public_key_hex: HexArg,
+    pub(crate) owner_address_hex: Option<HexArg>,
+    pub(crate) nonce: Option<DecimalArg>,
+    pub(crate) memo_hex: Option<HexArg>,
+}
+
+impl TransferFields {
+    pub(crate) fn to_transfer_args(&self) -> Result<TransferArgs, String> {
+        // hex fields
+        let collateral_id = parse_asset_id(&self.collateral_id_hex, "collateral_id")?;
+
+        // decimal fields
+        let recipient = self
+            .recipient_position_id
+            .parse::<u32>("recipient_position_id")?;
+        let position_id = self.sender_position_id.parse::<u32>("sender_position_id")?;
+        let amount = self.amount.parse::<u64>("amount")?;
+        let expiration = self.expiration.parse::<u64>("expiration")?;
+        let salt = Felt::from_dec_str(&self.salt.0)
+            .map_err(|e| format!("Failed to parse salt from {}: {}", self.salt.0, e))?;
+
+        Ok(TransferArgs {
+            recipient: PositionId { value: recipient },
+            position_id: PositionId { value: position_id },
+            collateral_id: AssetId {
+                value: collateral_id,
+            },
+            amount,
+            expiration: Timestamp {
+                seconds: expiration,
+            },
+            salt,
+        })
+    }
+
+    pub(crate) fn user_public_key(&self) -> Result<Felt, String> {
+        self.user_public_key_hex.parse("user_public_key")
+    }
+
+    // Binds the owner address, the nonce or the memo when given, using the
+    // matching newer transfer layout. No layout binds more than one
+    pub(crate) fn message_hash(&self, domain: &StarknetDomain) -> Result<Felt, String> {
+        let transfer_args = self.to_transfer_args()?;
+        let user_key = self.user_public_key()?;
+        let message = match (&self.owner_address_hex, &self.nonce, &self.memo_hex) {
+            (Some(_), Some(_), _) => {
+                return Err("owner_address and nonce can't both be given".to_string())
+            }
+            (Some(_), None, Some(_)) | (None, Some(_), Some(_)) => {
+                return Err("memo can't be given with owner_address or nonce".to_string())
+            }
+            #[cfg(feature = "draft-layouts")]
+            (None, None, Some(memo_hex)) => {
+                let memo = memo_hex.parse_canonical("memo")?;
+                TransferArgsWithMemo {
+                    recipient: transfer_args.recipient,
+                    position_id: transfer_args.position_id,
+                    collateral_id: transfer_args.collateral_id,
+                    amount: transfer_args.amount,
+                    expiration: transfer_args.expiration,
+                    salt: transfer_args.salt,
+                    memo,
+                }
+                .message_hash(domain, user_key)
+            }
+            #[cfg(feature = "draft-layouts")]
+            (None, Some(nonce), None) => {
+                let nonce = nonce.parse::<u64>("nonce")?;
+                TransferArgsWithNonce {
+                    recipient: transfer_args.recipient,
+                    position_id: transfer_args.position_id,
+                    collateral_id: transfer_args.collateral_id,
+                    amount: transfer_args.amount,
+                    expiration: transfer_args.expiration,
+                    salt: transfer_args.salt,
+                    nonce,
+                }
+                .message_hash(domain, user_key)
+            }
+            #[cfg(feature = "draft-layouts")]
+            (Some(owner_address_hex), None, None) => {
+                let owner = owner_address_hex.parse_canonical("owner_address")?;
+                TransferArgsWithOwner {
+                    recipient: transfer_args.recipient,
+                    position_id: transfer_args.position_id,
+                    collateral_id: transfer_args.collateral_id,
+                    amount: transfer_args.amount,
+                    expiration: transfer_args.expiration,
+                    salt: transfer_args.salt,
+                    owner,
+                }
+                .message_hash(domain, user_key)
+            }
+            #[cfg(not(feature = "draft-layouts"))]
+            (Some(_), None, None) => return Err(draft_layout_disabled("owner_address")),
+            #[cfg(not(feature = "draft-layouts"))]
+            (None, Some(_), None) => return Err(draft_layout_disabled("nonce")),
+            #[cfg(not(feature = "draft-layouts"))]
+            (None, None, Some(_)) => return Err(draft_layout_disabled("memo")),
+            (None, None, None) => transfer_args.message_hash(domain, user_key),
+        };
+        Ok(message.unwrap())
+    }
+}
+
+// Withdrawal-to-address fields as received from Python, before any parsing
+#[cfg(feature = "draft-layouts")]
+pub(crate) struct WithdrawalFields {
+    pub(crate) position_id: DecimalArg,
+    pub(crate) collateral_id_hex: HexArg,
+    pub(crate) amount: DecimalArg,
+    pub(crate) eth_address_hex: HexArg,
+    pub(crate) expiration: DecimalArg,
+    pub(crate) salt: DecimalArg,
+    pub(crate) user_public_key_hex: HexArg,
+}
+
+#[cfg(feature = "draft-layouts")]
+impl WithdrawalFields {
+    pub(crate) fn to_withdraw_args(&self) -> Result<WithdrawArgs, String> {
+        // hex fields
+        let collateral_id = parse_asset_id(&self.collateral_id_hex, "collateral_id")?;
+        // Checked on the digits as given: parsing reduces mod p, which would
+        // turn an oversized value into a different, short address
+        let eth_digits = self
+            .eth_address_hex
+            .0
+            .strip_prefix("0x")
+            .or_else(|| self.eth_address_hex.0.strip_prefix("0X"));
+        if !eth_digits.is_some_and(|digits| {
+            digits.len() == 40 && digits.bytes().all(|byte| byte.is_ascii_hexdigit())
+        }) {
+            return Err(format!(
+                "Invalid eth_address: {} must be 0x followed by exactly 40 hex digits",
+                self.eth_address_hex.0
+            ));
+        }
+        let recipient = self.eth_address_hex.parse("eth_address")?;
+
+        // decimal fields
+        let position_id = self.position_id.parse::<u32>("position_id")?;
+        let amount = self.amount.parse::<u64>("amount")?;
+        let expiration = self.expiration.parse::<u64>("expiration")?;
+        let salt = Felt::from_dec_str(&self.salt.0)
+            .map_err(|e| format!("Failed to parse salt from {}: {}", self.salt.0, e))?;
+
+        Ok(WithdrawArgs {
+            recipient,
+            position_id: PositionId { value: position_id },
+            collateral_id: AssetId {
+                value: collateral_id,
+            },
+            amount,
+            expiration: Timestamp {
+                seconds: expiration,
+            },
+            salt,
+        })
+    }
+
+    pub(crate) fn user_public_key(&self) -> Result<Felt, String> {
+        self.user_public_key_hex.parse("user_public_key")
+    }
+
+    pub(crate) fn message_hash(&self, domain: &StarknetDomain) -> Result<Felt, String> {
+        let withdraw_args = self.to_withdraw_args()?;
+        let user_key = self.user_public_key()?;
+        Ok(withdraw_args.message_hash(domain, user_key).unwrap())
+    }
+}
+
+// Keys each message type needs in the dict given to rs_hash_message
+const ORDER_MESSAGE_KEYS: [&str; 10] = [
+    "position_id",
+    "base_asset_id",
+    "base_amount",
+    "quote_asset_id",
+    "quote_amount",
+    "fee_asset_id",
+    "fee_amount",
+    "expiration",
+    "salt",
+    "user_public_key",
+];
+const TRANSFER_MESSAGE_KEYS: [&str; 7] = [
+    "recipient_position_id",
+    "sender_position_id",
+    "collateral_id",
+    "amount",
+    "expiration",
+    "salt",
+    "user_public_key",
+];
+#[cfg(feature = "draft-layouts")]
+const WITHDRAWAL_MESSAGE_KEYS: [&str; 7] = [
+    "position_id",
+    "collateral_id",
+    "amount",
+    "eth_address",
+    "expiration",
+    "salt",
+    "user_public_key",
+];
+
+// Fields of any message rs_hash_message can hash, selected by message type
+pub(crate) enum MessageFields {
+    Order(OrderFields),
+    Transfer(TransferFields),
+    #[cfg(feature = "draft-layouts")]
+    Withdrawal(WithdrawalFields),
+}
+
+impl MessageFields {
+    pub(crate) fn from_dict(message_type: &str, fields: &PyDict) -> PyResult<Self> {
+        let required_keys: &[&str] = match message_type {
+            "order" => &ORDER_MESSAGE_KEYS,
+            "transfer" => &TRANSFER_MESSAGE_KEYS,
+            #[cfg(feature = "draft-layouts")]
+            "withdrawal" => &WITHDRAWAL_MESSAGE_KEYS,
+            #[cfg(not(feature = "draft-layouts"))]
+            "withdrawal" => return Err(PyValueError::new_err(draft_layout_disabled("withdrawal"))),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown message type {}, expected order, transfer or withdrawal",
+                    message_type
+                )))
+            }
+        };
+        let mut missing_keys = vec![];
+        for key in required_keys {
+            if !fields.contains(*key)? {
+                missing_keys.push(*key);
+            }
+        }
+        if !missing_keys.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "Missing {} fields: {}",
+                message_type,
+                missing_keys.join(", ")
+            )));
+        }
+
+        // Every required key is present, so PyAny::get_item can't fail on a
+        // missing one; only extraction can
+        let item = |key: &str| PyAny::get_item(fields, key);
+        Ok(match message_type {
+            "order" => MessageFields::Order(fields.extract()?),
+            #[cfg(feature = "draft-layouts")]
+            "withdrawal" => MessageFields::Withdrawal(WithdrawalFields {
+                position_id: item("position_id")?.extract()?,
+                collateral_id_hex: item("collateral_id")?.extract()?,
+                amount: item("amount")?.extract()?,
+                eth_address_hex: item("eth_address")?.extract()?,
+                expiration: item("expiration")?.extract()?,
+                salt: item("salt")?.extract()?,
+                user_public_key_hex: item("user_public_key")?.extract()?,
+            }),
+            _ => MessageFields::Transfer(TransferFields {
+                recipient_position_id: item("recipient_position_id")?.extract()?,
+                sender_position_id: item("sender_position_id")?.extract()?,
+                collateral_id_hex: item("collateral_id")?.extract()?,
+                amount: item("amount")?.extract()?,
+                expiration: item("expiration")?.extract()?,
+                salt: item("salt")?.extract()?,
+                user_public_key_hex: item("user_public_key")?.extract()?,
+                owner_address_hex: fields
+                    .get_item("owner_address")?
+                    .map(|owner_address| owner_address.extract())
+                    .transpose()?,
+                nonce: fields
+                    .get_item("nonce")?
+                    .map(|nonce| nonce.extract())
+                    .transpose()?,
+                memo_hex: fields
+                    .get_item("memo")?
+                    .map(|memo| memo.extract())
+                    .transpose()?,
+            }),
+        })
+    }
+
+    // Orders get the same same-asset check rs_get_order_msg applies by default
+    pub(crate) fn message_hash(&self, domain: &StarknetDomain) -> Result<Felt, String> {
+        match self {
+            MessageFields::Order(order) => {
+                order.check_distinct_assets()?;
+                order.message_hash(domain)
+            }
+            MessageFields::Transfer(transfer) => transfer.message_hash(domain),
+            #[cfg(feature = "draft-layouts")]
+            MessageFields::Withdrawal(withdrawal) => withdrawal.message_hash(domain),
+        }
+    }
+}
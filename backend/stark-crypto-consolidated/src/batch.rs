@@ -0,0 +1,112 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use pyo3::exceptions::PyTimeoutError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use rayon::prelude::*;
+
+// Upper bound on the number of items a batch function accepts, so a malformed
+// request fails fast instead of tying up the worker
+pub(crate) static MAX_BATCH_SIZE: AtomicUsize = AtomicUsize::new(1_000_000);
+
+// Number of items processed in parallel between two deadline checks
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+pub(crate) enum BatchError {
+    Invalid(String),
+    TimedOut {
+        timeout_ms: u64,
+        completed: usize,
+        total: usize,
+    },
+}
+
+impl From<String> for BatchError {
+    fn from(message: String) -> Self {
+        BatchError::Invalid(message)
+    }
+}
+
+impl From<BatchError> for PyErr {
+    fn from(error: BatchError) -> Self {
+        match error {
+            BatchError::Invalid(message) => PyValueError::new_err(message),
+            BatchError::TimedOut {
+                timeout_ms,
+                completed,
+                total,
+            } => PyTimeoutError::new_err(format!(
+                "Batch timed out after {} ms with {} of {} items completed",
+                timeout_ms, completed, total
+            )),
+        }
+    }
+}
+
+pub(crate) fn check_batch_size(field: &str, len: usize) -> Result<(), String> {
+    check_batch_size_against(field, len, MAX_BATCH_SIZE.load(Ordering::Relaxed))
+}
+
+pub(crate) fn check_batch_size_against(field: &str, len: usize, max: usize) -> Result<(), String> {
+    if len > max {
+        return Err(format!(
+            "{} has {} items, more than the maximum batch size of {}",
+            field, len, max
+        ));
+    }
+    Ok(())
+}
+
+// Checks the list's length before converting any item, so an oversized batch
+// is rejected without first being copied out of Python
+pub(crate) fn extract_batch<'py, T>(field: &str, items: &'py PyList) -> PyResult<Vec<T>>
+where
+    T: FromPyObject<'py>,
+{
+    check_batch_size(field, items.len()).map_err(PyValueError::new_err)?;
+    items.extract()
+}
+
+// Maps `f` over `items` in parallel, preserving order. With a timeout, the
+// items are processed in chunks and the deadline is checked between them.
+pub(crate) fn par_map_with_deadline<T, R, F>(
+    items: &[T],
+    timeout_ms: Option<u64>,
+    f: F,
+) -> Result<Vec<R>, BatchError>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(usize, &T) -> Result<R, String> + Sync,
+{
+    let Some(timeout_ms) = timeout_ms else {
+        return Ok(items
+            .par_iter()
+            .enumerate()
+            .map(|(index, item)| f(index, item))
+            .collect::<Result<Vec<R>, String>>()?);
+    };
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    let mut results = Vec::with_capacity(items.len());
+    for chunk in items.chunks(DEADLINE_CHECK_INTERVAL) {
+        if Instant::now() >= deadline {
+            return Err(BatchError::TimedOut {
+                timeout_ms,
+                completed: results.len(),
+                total: items.len(),
+            });
+        }
+        let offset = results.len();
+        let chunk_results = chunk
+            .par_iter()
+            .enumerate()
+            .map(|(index, item)| f(offset + index, item))
+            .collect::<Result<Vec<R>, String>>()?;
+        results.extend(chunk_results);
+    }
+    Ok(results)
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use rust_crypto_lib_base::EC_ORDER;
+use starknet_crypto::Felt;
+use zeroize::Zeroizing;
+
+// Registered private keys by handle, as big-endian bytes that are wiped when
+// the entry is dropped. Boxed so the map rehashing never copies key bytes
+// into memory it then frees without wiping
+static KEYS: LazyLock<Mutex<HashMap<u64, Box<Zeroizing<[u8; 32]>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Handles are never reused, so a stale handle can't reach a newer key
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(1);
+
+pub(crate) fn register_key(private_key: &Felt) -> Result<u64, String> {
+    if *private_key == Felt::ZERO || *private_key >= *EC_ORDER {
+        return Err("Private key must be in [1, EC_ORDER)".to_string());
+    }
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    let key = Box::new(Zeroizing::new(private_key.to_bytes_be()));
+    KEYS.lock().unwrap().insert(handle, key);
+    Ok(handle)
+}
+
+// Runs `f` with the key behind `handle`, holding the store lock only long
+// enough to copy the key out, and wiping the copy as soon as `f` returns
+pub(crate) fn with_key<T>(handle: u64, f: impl FnOnce(&Felt) -> T) -> Result<T, String> {
+    let mut key = match KEYS.lock().unwrap().get(&handle) {
+        Some(key) => Felt::from_bytes_be(key),
+        None => return Err(format!("Unknown key handle {}", handle)),
+    };
+    let result = f(&key);
+    // SAFETY: as in `with_combined_key`
+    unsafe { zeroize::zeroize_flat_type(&mut key) };
+    Ok(result)
+}
+
+pub(crate) fn drop_key(handle: u64) -> bool {
+    KEYS.lock().unwrap().remove(&handle).is_some()
+}
+
+// Runs `f` with the key two additive shares sum to mod EC_ORDER, wiping the
+// reconstructed key as soon as `f` returns
+pub(crate) fn with_combined_key<T>(
+    share_a: &Felt,
+    share_b: &Felt,
+    f: impl FnOnce(&Felt) -> T,
+) -> Result<T, String> {
+    for (name, share) in [("share_a", share_a), ("share_b", share_b)] {
+        if *share == Felt::ZERO || *share >= *EC_ORDER {
+            return Err(format!("{} must be in [1, EC_ORDER)", name));
+        }
+    }
+    // Both shares are below EC_ORDER, so the sum wraps exactly when
+    // share_a >= EC_ORDER - share_b, and neither branch leaves the field
+    let complement = *EC_ORDER - *share_b;
+    let mut key = if *share_a >= complement {
+        *share_a - complement
+    } else {
+        *share_a + *share_b
+    };
+    if key == Felt::ZERO {
+        return Err("Key shares sum to zero mod EC_ORDER".to_string());
+    }
+    let result = f(&key);
+    // SAFETY: Felt is plain limbs with no pointers or drop glue, and an
+    // all-zero Felt is a valid value
+    unsafe { zeroize::zeroize_flat_type(&mut key) };
+    Ok(result)
+}
@@ -1,22 +1,226 @@
+use num_bigint::BigUint;
 use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+use pyo3::types::PyBytes;
+use pyo3::types::PyDict;
+use pyo3::types::PyList;
 use pyo3::types::PyModule;
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
 
+mod args;
+mod batch;
+mod keystore;
+mod wire;
+
+use args::apply_asset_layout;
+use args::build_order;
+use args::check_distinct_asset_ids;
+use args::check_expiration_window;
+#[cfg(not(feature = "draft-layouts"))]
+use args::draft_layout_disabled;
+use args::int_to_felt;
+use args::parse_leverage;
+use args::scaled_amount;
+use args::signed_amount;
+use args::split_concat_signature;
+use args::to_domain;
+use args::AssetIdConfig;
+use args::DecimalArg;
+use args::HexArg;
+use args::MessageFields;
+use args::OrderFields;
+use args::TransferFields;
+#[cfg(feature = "draft-layouts")]
+use args::WithdrawalFields;
+use args::ASSET_LAYOUT;
+use batch::check_batch_size;
+use batch::extract_batch;
+use batch::par_map_with_deadline;
+use batch::BatchError;
+use batch::MAX_BATCH_SIZE;
+use keystore::drop_key;
+use keystore::register_key;
+use keystore::with_combined_key;
+use keystore::with_key;
+use wire::SignedOrder;
+
+use rust_crypto_lib_base::collateral_commitment;
+use rust_crypto_lib_base::decode_short_string;
+use rust_crypto_lib_base::derive_subaccount_key;
+use rust_crypto_lib_base::derive_viewing_key;
+use rust_crypto_lib_base::ec_add;
+use rust_crypto_lib_base::ec_generator;
+use rust_crypto_lib_base::ec_mul;
+use rust_crypto_lib_base::eip712::eip712_hash;
+use rust_crypto_lib_base::encode_short_string;
+use rust_crypto_lib_base::felt_ct_eq;
+use rust_crypto_lib_base::generate_private_key;
+use rust_crypto_lib_base::generate_timestamped_salt;
+use rust_crypto_lib_base::get_compressed_public_key;
 use rust_crypto_lib_base::get_private_key_from_eth_signature;
+use rust_crypto_lib_base::get_private_key_from_eth_signature_checked;
+use rust_crypto_lib_base::get_public_key_parity;
+use rust_crypto_lib_base::get_public_key_point;
+use rust_crypto_lib_base::hash_config_fingerprint;
+use rust_crypto_lib_base::is_curve_x;
+use rust_crypto_lib_base::order_batch_commitment;
+use rust_crypto_lib_base::position_commitment;
+use rust_crypto_lib_base::salt_timestamp;
 use rust_crypto_lib_base::sign_message;
+use rust_crypto_lib_base::sign_message_with_nonce;
+use rust_crypto_lib_base::starknet_messages::batch_commitment;
+use rust_crypto_lib_base::starknet_messages::fee_amount_from_rate;
 use rust_crypto_lib_base::starknet_messages::AssetId;
+use rust_crypto_lib_base::starknet_messages::AssetIdLayout;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::CancelAllOrders;
+use rust_crypto_lib_base::starknet_messages::Hashable;
 use rust_crypto_lib_base::starknet_messages::OffChainMessage;
 use rust_crypto_lib_base::starknet_messages::Order;
 use rust_crypto_lib_base::starknet_messages::PositionId;
-use rust_crypto_lib_base::starknet_messages::StarknetDomain;
+#[cfg(feature = "draft-layouts")]
+use rust_crypto_lib_base::starknet_messages::Settlement;
 use rust_crypto_lib_base::starknet_messages::Timestamp;
-use rust_crypto_lib_base::starknet_messages::TransferArgs;
+use rust_crypto_lib_base::test_private_key;
+use rust_crypto_lib_base::transaction::compute_account_address;
+use rust_crypto_lib_base::transaction::entrypoint_selector;
+use rust_crypto_lib_base::transaction::multicall_hash;
+use rust_crypto_lib_base::transaction::Call;
+use rust_crypto_lib_base::transaction::InvokeTransactionV3;
+use rust_crypto_lib_base::transaction::ResourceBounds;
+use rust_crypto_lib_base::verify_collateral_commitment;
+use rust_crypto_lib_base::verify_order_batch_commitment;
+use rust_crypto_lib_base::verify_position_commitment;
+use rust_crypto_lib_base::verify_signature_full;
+use rust_crypto_lib_base::EC_ORDER;
+use rust_crypto_lib_base::EC_ORDER_HALF;
+use rust_crypto_lib_base::ELEMENT_UPPER_BOUND;
 use starknet_crypto::get_public_key as fetch_public_key;
 use starknet_crypto::pedersen_hash;
+use starknet_crypto::poseidon_hash;
 use starknet_crypto::verify as verify_signature;
 use starknet_crypto::Felt;
+use starknet_crypto::VerifyError;
+
+/// Sets the maximum number of items the batch functions accept.
+#[pyfunction]
+fn rs_set_max_batch_size(max_batch_size: usize) -> PyResult<()> {
+    if max_batch_size == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "max_batch_size must be at least 1",
+        ));
+    }
+    MAX_BATCH_SIZE.store(max_batch_size, Ordering::Relaxed);
+    Ok(())
+}
+
+// When set, hex inputs must be lowercase and 0x-prefixed
+static STRICT_HEX: AtomicBool = AtomicBool::new(false);
+
+/// Toggles strict hex parsing. While enabled, every hex input must be
+/// lowercase and `0x`-prefixed, and anything else raises. Inputs that also
+/// take a decimal felt accept only hex then. Off by default.
+#[pyfunction]
+fn rs_set_strict_hex(strict: bool) {
+    STRICT_HEX.store(strict, Ordering::Relaxed);
+}
+
+// Hex format of every felt returned to Python, as an index into
+// HEX_OUTPUT_MODES
+static HEX_OUTPUT_MODE: AtomicU8 = AtomicU8::new(0);
+const HEX_OUTPUT_MODES: [&str; 3] = ["minimal", "even", "padded64"];
+
+/// Sets how every function returning a felt as hex formats it: `"minimal"`
+/// (the default) drops all leading zeros, `"even"` keeps one where needed for
+/// an even number of digits, and `"padded64"` pads to 64 digits.
+#[pyfunction]
+fn rs_set_hex_output_mode(mode: &str) -> PyResult<()> {
+    let index = HEX_OUTPUT_MODES
+        .iter()
+        .position(|known| *known == mode)
+        .ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown hex output mode {}, expected one of {:?}",
+                mode, HEX_OUTPUT_MODES
+            ))
+        })?;
+    HEX_OUTPUT_MODE.store(index as u8, Ordering::Relaxed);
+    Ok(())
+}
+
+fn format_hex(felt: &Felt, mode: u8) -> String {
+    match HEX_OUTPUT_MODES[mode as usize] {
+        "even" => {
+            let minimal = felt.to_hex_string();
+            if minimal.len() % 2 == 1 {
+                format!("0x0{}", &minimal[2..])
+            } else {
+                minimal
+            }
+        }
+        "padded64" => felt.to_fixed_hex_string(),
+        _ => felt.to_hex_string(),
+    }
+}
+
+// Formatting for felts returned to Python, following rs_set_hex_output_mode
+trait ToOutputHex {
+    fn to_output_hex(&self) -> String;
+}
+
+impl ToOutputHex for Felt {
+    fn to_output_hex(&self) -> String {
+        format_hex(self, HEX_OUTPUT_MODE.load(Ordering::Relaxed))
+    }
+}
+
+/// Makes the message-hash functions mask every asset id with `mask_hex` and
+/// then shift it left by `shift` bits before hashing. Raises if that could
+/// leave the field. Calling it with no arguments restores the default of
+/// hashing asset ids as given.
+#[pyfunction]
+#[pyo3(signature = (shift = 0, mask_hex = None))]
+fn rs_configure_asset_encoding(shift: u32, mask_hex: Option<HexArg>) -> PyResult<()> {
+    let layout = match mask_hex {
+        Some(mask_hex) => mask_hex
+            .parse("mask")
+            .and_then(|mask| AssetIdLayout::new(shift, mask))
+            .map(Some),
+        None if shift == 0 => Ok(None),
+        None => Err("A shift needs a mask to keep asset ids in the field".to_string()),
+    }
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+    *ASSET_LAYOUT.write().unwrap() = layout;
+    Ok(())
+}
+
+fn check_canonical_hex(hex_str: &str) -> Result<(), String> {
+    let digits = hex_str
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("Hex string {} is not 0x-prefixed", hex_str))?;
+    if digits.is_empty()
+        || !digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+    {
+        return Err(format!("Hex string {} is not lowercase hex", hex_str));
+    }
+    Ok(())
+}
 
 // Converts a hexadecimal string to a FieldElement
 fn str_to_field_element(hex_str: &str) -> Result<Felt, String> {
+    if STRICT_HEX.load(Ordering::Relaxed) {
+        check_canonical_hex(hex_str)?;
+    }
     Felt::from_hex(hex_str).map_err(|e| {
         format!(
             "Failed to convert hex string {} to FieldElement: {}",
@@ -25,6 +229,104 @@ fn str_to_field_element(hex_str: &str) -> Result<Felt, String> {
     })
 }
 
+// Parses a felt given either as 0x-prefixed hex or as a decimal string. Strict
+// hex mode only accepts canonical hex, so it rules out the decimal form too
+fn str_to_felt_any(value: &str) -> Result<Felt, String> {
+    if value.starts_with("0x") || value.starts_with("0X") || STRICT_HEX.load(Ordering::Relaxed) {
+        return str_to_field_element(value);
+    }
+    Felt::from_dec_str(value)
+        .map_err(|e| format!("Failed to convert {} to FieldElement: {}", value, e))
+}
+
+/// Compares two felts by value, each given as hex or decimal, so that e.g.
+/// `"0x0A"`, `"0xa"` and `"10"` are all equal.
+#[pyfunction]
+fn rs_felt_eq(a: &str, b: &str) -> PyResult<bool> {
+    str_to_felt_any(a)
+        .and_then(|a| str_to_felt_any(b).map(|b| a == b))
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Orders two felts, each given as hex or decimal, by their canonical integer
+/// value. Returns -1, 0 or 1.
+#[pyfunction]
+fn rs_felt_cmp(a: &str, b: &str) -> PyResult<i8> {
+    str_to_felt_any(a)
+        .and_then(|a| str_to_felt_any(b).map(|b| a.cmp(&b) as i8))
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Compares two hex felts in constant time, for checking a recomputed hash
+/// against an expected one without the timing leak of a string compare.
+#[pyfunction]
+fn rs_felt_ct_eq(a_hex: &str, b_hex: &str) -> PyResult<bool> {
+    str_to_field_element(a_hex)
+        .and_then(|a| str_to_field_element(b_hex).map(|b| felt_ct_eq(&a, &b)))
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Returns whether `hex_str` parses as a felt below the field prime, without
+/// raising. Values the parser would silently reduce modulo the prime count
+/// as invalid.
+#[pyfunction]
+fn rs_is_valid_felt(hex_str: &str) -> bool {
+    let Ok(felt) = str_to_field_element(hex_str) else {
+        return false;
+    };
+    // A reduced value no longer matches the digits it was parsed from
+    let digits = hex_str
+        .strip_prefix("0x")
+        .or_else(|| hex_str.strip_prefix("0X"))
+        .unwrap_or(hex_str);
+    !digits.is_empty()
+        && digits
+            .trim_start_matches('0')
+            .eq_ignore_ascii_case(felt.to_hex_string()[2..].trim_start_matches('0'))
+}
+
+/// Returns the minimal number of big-endian bytes needed to hold the felt,
+/// from 1 to 32. Zero takes one byte.
+#[pyfunction]
+fn rs_felt_byte_length(hex: &str) -> PyResult<usize> {
+    str_to_field_element(hex)
+        .map(|felt| {
+            let bytes = felt.to_bytes_be();
+            let leading_zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+            (bytes.len() - leading_zeros).max(1)
+        })
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// A felt parsed once from hex or an `int`, exposed to Python as `Felt`.
+/// Passing one where a function takes a `Felt` skips parsing it again, which
+/// adds up for values reused across many calls, such as asset ids.
+#[pyclass(name = "Felt", frozen)]
+struct FeltValue(Felt);
+
+#[pymethods]
+impl FeltValue {
+    #[new]
+    fn new(value: HexArg) -> PyResult<Self> {
+        value
+            .parse("value")
+            .map(FeltValue)
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+    }
+
+    fn hex(&self) -> String {
+        self.0.to_output_hex()
+    }
+
+    fn __int__(&self) -> BigUint {
+        BigUint::from_bytes_be(&self.0.to_bytes_be())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Felt({})", self.0.to_hex_string())
+    }
+}
+
 #[pyfunction]
 fn rs_get_public_key(py: Python, private_key_hex: String) -> PyResult<String> {
     py.allow_threads(move || {
@@ -34,6 +336,147 @@ fn rs_get_public_key(py: Python, private_key_hex: String) -> PyResult<String> {
     })
 }
 
+/// Returns the x-coordinate of the public key as hex. With the parity from
+/// `rs_public_key_parity` this is enough to store the key in 33 bytes.
+#[pyfunction]
+fn rs_public_key_x(py: Python, priv_key_hex: String) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let private_key = str_to_field_element(&priv_key_hex)?;
+        Ok(get_public_key_point(&private_key)?.x().to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Returns the parity (0 or 1) of the public key's y-coordinate.
+#[pyfunction]
+fn rs_public_key_parity(py: Python, priv_key_hex: String) -> PyResult<u8> {
+    py.allow_threads(move || -> Result<u8, String> {
+        let private_key = str_to_field_element(&priv_key_hex)?;
+        get_public_key_parity(&private_key)
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Batch form of `rs_public_key_x` and `rs_public_key_parity`: returns the
+/// compressed public key `(x, parity)` of each private key, x as hex, in input
+/// order. With `timeout_ms`, raises `TimeoutError` once the deadline passes.
+#[pyfunction]
+#[pyo3(signature = (private_keys, timeout_ms = None))]
+fn rs_get_compressed_public_keys(
+    py: Python,
+    private_keys: &PyList,
+    timeout_ms: Option<u64>,
+) -> PyResult<Vec<(String, u8)>> {
+    let private_keys: Vec<String> = extract_batch("private_keys", private_keys)?;
+    py.allow_threads(move || -> Result<Vec<(String, u8)>, BatchError> {
+        par_map_with_deadline(&private_keys, timeout_ms, |index, priv_key_hex| {
+            let compress = || -> Result<(String, u8), String> {
+                let private_key = str_to_field_element(priv_key_hex)?;
+                let (x, parity) = get_compressed_public_key(&private_key)?;
+                Ok((x.to_output_hex(), parity))
+            };
+            compress().map_err(|e| format!("private_keys[{}]: {}", index, e))
+        })
+    })
+    .map_err(PyErr::from)
+}
+
+/// Derives the public key of every private key in `keys`, returning a dict
+/// from each name to its public key as hex, in the input's order. With
+/// `timeout_ms`, raises `TimeoutError` once the deadline passes.
+#[pyfunction]
+#[pyo3(signature = (keys, timeout_ms = None))]
+fn rs_get_public_keys_map<'py>(
+    py: Python<'py>,
+    keys: &'py PyDict,
+    timeout_ms: Option<u64>,
+) -> PyResult<&'py PyDict> {
+    check_batch_size("keys", keys.len())
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+    let mut names = Vec::with_capacity(keys.len());
+    let mut labels = Vec::with_capacity(keys.len());
+    let mut private_keys = Vec::with_capacity(keys.len());
+    for (name, private_key) in keys.iter() {
+        labels.push(name.repr()?.to_string());
+        names.push(name);
+        private_keys.push(private_key.extract::<HexArg>()?);
+    }
+    let public_keys = py
+        .allow_threads(|| -> Result<Vec<String>, BatchError> {
+            par_map_with_deadline(&private_keys, timeout_ms, |index, private_key| {
+                private_key
+                    .parse("private_key")
+                    .map(|private_key| fetch_public_key(&private_key).to_output_hex())
+                    .map_err(|e| format!("keys[{}]: {}", labels[index], e))
+            })
+        })
+        .map_err(PyErr::from)?;
+    let result = PyDict::new(py);
+    for (name, public_key) in names.into_iter().zip(public_keys) {
+        result.set_item(name, public_key)?;
+    }
+    Ok(result)
+}
+
+/// Returns the standard Stark curve generator `(x, y)` as hex.
+#[pyfunction]
+fn rs_ec_generator() -> (String, String) {
+    let generator = ec_generator();
+    (generator.x().to_output_hex(), generator.y().to_output_hex())
+}
+
+/// Multiplies a point on the Stark curve by a scalar, returning `(x, y)` as
+/// hex. Raises if the point is not on the curve. Public keys are the x of
+/// the generator from `rs_ec_generator` times the private key.
+#[pyfunction]
+fn rs_ec_mul(
+    py: Python,
+    point_x_hex: String,
+    point_y_hex: String,
+    scalar_hex: String,
+) -> PyResult<(String, String)> {
+    py.allow_threads(move || -> Result<(String, String), String> {
+        let x = str_to_field_element(&point_x_hex)?;
+        let y = str_to_field_element(&point_y_hex)?;
+        let scalar = str_to_field_element(&scalar_hex)?;
+        let point = ec_mul(x, y, scalar)?;
+        Ok((point.x().to_output_hex(), point.y().to_output_hex()))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Adds two points on the Stark curve, returning `(x, y)` as hex. Raises if
+/// either point is off the curve or the sum is the point at infinity.
+#[pyfunction]
+fn rs_ec_add(
+    py: Python,
+    ax_hex: String,
+    ay_hex: String,
+    bx_hex: String,
+    by_hex: String,
+) -> PyResult<(String, String)> {
+    py.allow_threads(move || -> Result<(String, String), String> {
+        let a = (
+            str_to_field_element(&ax_hex)?,
+            str_to_field_element(&ay_hex)?,
+        );
+        let b = (
+            str_to_field_element(&bx_hex)?,
+            str_to_field_element(&by_hex)?,
+        );
+        let point = ec_add(a, b)?;
+        Ok((point.x().to_output_hex(), point.y().to_output_hex()))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Returns a fingerprint of the hash constants and tables in use, for startup
+/// checks that the encoding hasn't silently changed under a dependency bump.
+#[pyfunction]
+fn rs_hash_config_fingerprint() -> String {
+    hash_config_fingerprint().to_output_hex()
+}
+
 #[pyfunction]
 fn rs_compute_pedersen_hash(py: Python, left_hex: String, right_hex: String) -> PyResult<String> {
     py.allow_threads(move || {
@@ -47,6 +490,66 @@ fn rs_compute_pedersen_hash(py: Python, left_hex: String, right_hex: String) ->
     })
 }
 
+/// `pedersen(x, 0)`, the closing step of many encodings, returned in decimal
+/// like `rs_compute_pedersen_hash`.
+#[pyfunction]
+fn rs_pedersen_with_zero(py: Python, x_hex: String) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let x = str_to_field_element(&x_hex)?;
+        Ok(pedersen_hash(&x, &Felt::ZERO).to_string())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Hashes two felts with the algorithm named by `algo`, either `"pedersen"`
+/// or `"poseidon"`, so callers can pick the hash from data instead of code.
+#[pyfunction]
+fn rs_hash2(py: Python, algo: String, left_hex: String, right_hex: String) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let left = str_to_field_element(&left_hex)?;
+        let right = str_to_field_element(&right_hex)?;
+        let hash = match algo.as_str() {
+            "pedersen" => pedersen_hash(&left, &right),
+            "poseidon" => poseidon_hash(left, right),
+            _ => {
+                return Err(format!(
+                    "Unknown hash algorithm {}, expected pedersen or poseidon",
+                    algo
+                ))
+            }
+        };
+        Ok(hash.to_string())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Folds `leaf_hex` up a Merkle path with Pedersen and compares the result to
+/// `root_hex`. Each proof step is `(sibling_hex, sibling_is_left)`: a left
+/// sibling hashes as `H(sibling, node)`, a right one as `H(node, sibling)`.
+#[pyfunction]
+fn rs_pedersen_verify_proof(
+    py: Python,
+    leaf_hex: String,
+    proof: Vec<(String, bool)>,
+    root_hex: String,
+) -> PyResult<bool> {
+    py.allow_threads(move || -> Result<bool, String> {
+        let root = str_to_field_element(&root_hex)?;
+        let mut node = str_to_field_element(&leaf_hex)?;
+        for (index, (sibling_hex, sibling_is_left)) in proof.iter().enumerate() {
+            let sibling = str_to_field_element(sibling_hex)
+                .map_err(|e| format!("Invalid proof[{}]: {}", index, e))?;
+            node = if *sibling_is_left {
+                pedersen_hash(&sibling, &node)
+            } else {
+                pedersen_hash(&node, &sibling)
+            };
+        }
+        Ok(node == root)
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
 #[pyfunction]
 fn rs_sign_message(
     py: Python,
@@ -66,6 +569,371 @@ fn rs_sign_message(
     })
 }
 
+pyo3::create_exception!(
+    fast_stark_crypto,
+    SigningError,
+    pyo3::exceptions::PyValueError,
+    "Raised when a freshly produced signature fails to verify."
+);
+
+/// Like `rs_sign_message`, but verifies the signature against the key's own
+/// public key before returning it, raising `SigningError` if that fails. Costs
+/// a key derivation and a verification on top of signing, in exchange for
+/// never handing out a signature the exchange would reject.
+#[pyfunction]
+fn rs_sign_message_checked(
+    py: Python,
+    priv_key_hex: String,
+    msg_hash_hex: String,
+) -> PyResult<(String, String)> {
+    py.allow_threads(move || -> PyResult<(String, String)> {
+        let parse = |hex: &str| {
+            str_to_field_element(hex).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+        };
+        let priv_key = parse(&priv_key_hex)?;
+        let msg_hash = parse(&msg_hash_hex)?;
+        let signature = sign_message(&msg_hash, &priv_key)
+            .map_err(|e| SigningError::new_err(format!("Signing operation failed: {}", e)))?;
+        let public_key = fetch_public_key(&priv_key);
+        if !matches!(
+            verify_signature(&public_key, &msg_hash, &signature.r, &signature.s),
+            Ok(true)
+        ) {
+            return Err(SigningError::new_err(
+                "Signature failed verification against the signing key",
+            ));
+        }
+        Ok((signature.r.to_string(), signature.s.to_string()))
+    })
+}
+
+/// Parses a private key once and keeps it in an internal store, returning an
+/// opaque handle for `rs_sign_with_handle`. The key is wiped from the store
+/// when `rs_drop_key` releases the handle.
+#[pyfunction]
+fn rs_register_key(py: Python, priv_key_hex: String) -> PyResult<u64> {
+    py.allow_threads(move || -> Result<u64, String> {
+        register_key(&str_to_field_element(&priv_key_hex)?)
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Like `rs_sign_message`, but signs with a key registered through
+/// `rs_register_key`. Raises on an unknown or dropped handle.
+#[pyfunction]
+fn rs_sign_with_handle(
+    py: Python,
+    handle: u64,
+    msg_hash_hex: String,
+) -> PyResult<(String, String)> {
+    py.allow_threads(move || -> Result<(String, String), String> {
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let signature = with_key(handle, |priv_key| sign_message(&msg_hash, priv_key))?
+            .map_err(|e| format!("Signing operation failed: {}", e))?;
+        Ok((signature.r.to_string(), signature.s.to_string()))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Like `rs_sign_message`, but with the key held as two additive shares that
+/// sum to it mod the curve order. The key is only reconstructed for the
+/// signing call and wiped right after it.
+#[pyfunction]
+fn rs_sign_with_key_shares(
+    py: Python,
+    share_a_hex: String,
+    share_b_hex: String,
+    msg_hash_hex: String,
+) -> PyResult<(String, String)> {
+    py.allow_threads(move || -> Result<(String, String), String> {
+        let share_a = str_to_field_element(&share_a_hex)?;
+        let share_b = str_to_field_element(&share_b_hex)?;
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let signature = with_combined_key(&share_a, &share_b, |priv_key| {
+            sign_message(&msg_hash, priv_key)
+        })?
+        .map_err(|e| format!("Signing operation failed: {}", e))?;
+        Ok((signature.r.to_string(), signature.s.to_string()))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Releases a handle from `rs_register_key`, wiping its key. Returns whether
+/// the handle was registered.
+#[pyfunction]
+fn rs_drop_key(handle: u64) -> bool {
+    drop_key(handle)
+}
+
+/// Like `rs_sign_message`, but returns `(msg_hash, r, s)` as hex, echoing the
+/// hash back so it has the same shape as the functions that hash and sign in
+/// one call, such as `rs_reexpire_order`.
+#[pyfunction]
+fn rs_sign_message_with_hash(
+    py: Python,
+    priv_key_hex: String,
+    msg_hash_hex: String,
+) -> PyResult<(String, String, String)> {
+    py.allow_threads(move || -> Result<(String, String, String), String> {
+        let priv_key = str_to_field_element(&priv_key_hex)?;
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let signature = sign_message(&msg_hash, &priv_key)
+            .map_err(|e| format!("Signing operation failed: {}", e))?;
+        Ok((
+            msg_hash.to_output_hex(),
+            signature.r.to_output_hex(),
+            signature.s.to_output_hex(),
+        ))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Like `rs_sign_message`, but returns `r` and `s` as fixed-width hex: `0x`
+/// followed by exactly 64 zero-padded digits, ready for `CHAR(66)` columns.
+#[pyfunction]
+fn rs_sign_message_padded(
+    py: Python,
+    priv_key_hex: String,
+    msg_hash_hex: String,
+) -> PyResult<(String, String)> {
+    py.allow_threads(move || -> Result<(String, String), String> {
+        let priv_key = str_to_field_element(&priv_key_hex)?;
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let signature = sign_message(&msg_hash, &priv_key)
+            .map_err(|e| format!("Signing operation failed: {}", e))?;
+        Ok((
+            signature.r.to_fixed_hex_string(),
+            signature.s.to_fixed_hex_string(),
+        ))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Signs with an externally supplied nonce, e.g. from an HSM. `nonce_hex` must
+/// be a full 32-byte value and is reduced modulo the curve order rather than
+/// rejected. Returns `(r, s, R_x)`, `R_x` being the x coordinate of `k * G`.
+#[pyfunction]
+fn rs_sign_message_with_external_nonce(
+    py: Python,
+    priv_key_hex: String,
+    msg_hash_hex: String,
+    nonce_hex: String,
+) -> PyResult<(String, String, String)> {
+    py.allow_threads(move || -> Result<(String, String, String), String> {
+        let priv_key = str_to_field_element(&priv_key_hex)?;
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let (signature, r_x) = sign_message_with_nonce(&msg_hash, &priv_key, &nonce_hex)
+            .map_err(|e| format!("Signing operation failed: {}", e))?;
+        Ok((
+            signature.r.to_output_hex(),
+            signature.s.to_output_hex(),
+            r_x.to_output_hex(),
+        ))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+fn sign_pair(
+    index: usize,
+    priv_key_hex: &str,
+    msg_hash_hex: &str,
+) -> Result<(String, String), String> {
+    str_to_field_element(priv_key_hex)
+        .and_then(|priv_key| {
+            str_to_field_element(msg_hash_hex).and_then(|msg_hash| {
+                sign_message(&msg_hash, &priv_key)
+                    .map(|signature| (signature.r.to_string(), signature.s.to_string()))
+                    .map_err(|e| format!("Signing operation failed: {}", e))
+            })
+        })
+        .map_err(|e| format!("Pair {}: {}", index, e))
+}
+
+/// Signs each message hash with its paired private key, in parallel. Output
+/// matches `rs_sign_message`, one `(r, s)` per pair in input order. Errors
+/// name the index of the pair that failed. With `timeout_ms`, raises
+/// `TimeoutError` once the deadline passes.
+#[pyfunction]
+#[pyo3(signature = (pairs, timeout_ms = None))]
+fn rs_sign_pairs(
+    py: Python,
+    pairs: &PyList,
+    timeout_ms: Option<u64>,
+) -> PyResult<Vec<(String, String)>> {
+    let pairs: Vec<(String, String)> = extract_batch("pairs", pairs)?;
+    py.allow_threads(move || -> Result<Vec<(String, String)>, BatchError> {
+        par_map_with_deadline(&pairs, timeout_ms, |index, (priv_key_hex, msg_hash_hex)| {
+            sign_pair(index, priv_key_hex, msg_hash_hex)
+        })
+    })
+    .map_err(PyErr::from)
+}
+
+/// Like `rs_sign_pairs`, but signs in chunks of `chunk_size` and calls
+/// `progress(completed)` after each one. Signing runs without the GIL, which
+/// is taken back only to invoke the callback; an exception raised by the
+/// callback aborts the batch.
+#[pyfunction]
+#[pyo3(signature = (pairs, progress, chunk_size = 1024))]
+fn rs_sign_pairs_with_progress(
+    py: Python,
+    pairs: &PyList,
+    progress: PyObject,
+    chunk_size: usize,
+) -> PyResult<Vec<(String, String)>> {
+    let pairs: Vec<(String, String)> = extract_batch("pairs", pairs)?;
+    if chunk_size == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "chunk_size must be at least 1",
+        ));
+    }
+    let mut signatures = Vec::with_capacity(pairs.len());
+    for chunk in pairs.chunks(chunk_size) {
+        let offset = signatures.len();
+        let chunk_signatures = py
+            .allow_threads(|| {
+                chunk
+                    .par_iter()
+                    .enumerate()
+                    .map(|(index, (priv_key_hex, msg_hash_hex))| {
+                        sign_pair(offset + index, priv_key_hex, msg_hash_hex)
+                    })
+                    .collect::<Result<Vec<_>, String>>()
+            })
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        signatures.extend(chunk_signatures);
+        progress.call1(py, (signatures.len(),))?;
+    }
+    Ok(signatures)
+}
+
+// Lines rs_sign_file reads and signs in parallel before writing them out
+const SIGN_FILE_CHUNK_LINES: usize = 1024;
+
+/// Signs every hash in `input_path`, one hex hash per line, writing a
+/// `hash,r,s` row of hex values per hash to `output_path`. The file is read
+/// and written a chunk at a time, so memory use doesn't grow with its size.
+/// Blank lines are skipped. A line that fails to parse or sign raises with
+/// its 1-based line number, leaving the rows before it in the output. Returns
+/// the number of rows written.
+#[pyfunction]
+fn rs_sign_file(
+    py: Python,
+    input_path: String,
+    output_path: String,
+    priv_key_hex: String,
+) -> PyResult<usize> {
+    py.allow_threads(move || -> Result<usize, String> {
+        let priv_key = str_to_field_element(&priv_key_hex)?;
+        let input =
+            File::open(&input_path).map_err(|e| format!("Failed to open {}: {}", input_path, e))?;
+        // Creating the output truncates it, which would empty an input that is
+        // the same file before a single line is read
+        if let (Ok(input_file), Ok(output_file)) = (
+            std::fs::canonicalize(&input_path),
+            std::fs::canonicalize(&output_path),
+        ) {
+            if input_file == output_file {
+                return Err(format!(
+                    "output_path {} is the same file as input_path {}",
+                    output_path, input_path
+                ));
+            }
+        }
+        let output = File::create(&output_path)
+            .map_err(|e| format!("Failed to create {}: {}", output_path, e))?;
+        let write_error = |e: std::io::Error| format!("Failed to write {}: {}", output_path, e);
+        let mut lines = BufReader::new(input).lines().enumerate();
+        let mut writer = BufWriter::new(output);
+        let mut written = 0;
+        loop {
+            let mut chunk = Vec::with_capacity(SIGN_FILE_CHUNK_LINES);
+            for (index, line) in lines.by_ref().take(SIGN_FILE_CHUNK_LINES) {
+                let line = line.map_err(|e| {
+                    format!("Failed to read line {} of {}: {}", index + 1, input_path, e)
+                })?;
+                if !line.trim().is_empty() {
+                    chunk.push((index + 1, line));
+                }
+            }
+            if chunk.is_empty() {
+                break;
+            }
+            let rows: Vec<Result<String, String>> = chunk
+                .par_iter()
+                .map(|(line_number, line)| {
+                    let sign_line = || -> Result<String, String> {
+                        let msg_hash = str_to_field_element(line.trim())?;
+                        let signature = sign_message(&msg_hash, &priv_key)
+                            .map_err(|e| format!("Signing operation failed: {}", e))?;
+                        Ok(format!(
+                            "{},{},{}\n",
+                            msg_hash.to_output_hex(),
+                            signature.r.to_output_hex(),
+                            signature.s.to_output_hex()
+                        ))
+                    };
+                    sign_line().map_err(|e| format!("Line {}: {}", line_number, e))
+                })
+                .collect();
+            for row in rows {
+                writer.write_all(row?.as_bytes()).map_err(write_error)?;
+                written += 1;
+            }
+        }
+        writer.flush().map_err(write_error)?;
+        Ok(written)
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Signs a transaction hash and lays the signature out as the felt array an
+/// account contract expects: `[r, s]`, or `[r, s, v]` when `recoverable` is
+/// set, where `v` is the y-parity of the signing point.
+#[pyfunction]
+#[pyo3(signature = (tx_hash_hex, priv_key_hex, recoverable=false))]
+fn rs_sign_for_invoke(
+    py: Python,
+    tx_hash_hex: String,
+    priv_key_hex: String,
+    recoverable: bool,
+) -> PyResult<Vec<String>> {
+    py.allow_threads(move || -> Result<Vec<String>, String> {
+        let priv_key = str_to_field_element(&priv_key_hex)?;
+        let tx_hash = str_to_field_element(&tx_hash_hex)?;
+        let signature = sign_message(&tx_hash, &priv_key)
+            .map_err(|e| format!("Signing operation failed: {}", e))?;
+        let mut felts = vec![signature.r, signature.s];
+        if recoverable {
+            felts.push(signature.v);
+        }
+        Ok(felts.iter().map(|felt| felt.to_output_hex()).collect())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Lays out `(r, s)` as account calldata felts. `style` is either `"plain"`
+/// for `[r, s]` or `"length_prefixed"` for `[2, r, s]`, as accounts differ in
+/// which of the two they expect.
+#[pyfunction]
+fn rs_signature_to_calldata(r_hex: HexArg, s_hex: HexArg, style: &str) -> PyResult<Vec<String>> {
+    let felts = || -> Result<Vec<Felt>, String> {
+        let r = r_hex.parse("r")?;
+        let s = s_hex.parse("s")?;
+        match style {
+            "plain" => Ok(vec![r, s]),
+            "length_prefixed" => Ok(vec![Felt::TWO, r, s]),
+            _ => Err(format!(
+                "Unknown signature style {}, expected plain or length_prefixed",
+                style
+            )),
+        }
+    };
+    felts()
+        .map(|felts| felts.iter().map(|felt| felt.to_output_hex()).collect())
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
 #[pyfunction]
 fn rs_verify_signature(
     py: Python,
@@ -89,257 +957,6614 @@ fn rs_verify_signature(
     })
 }
 
+/// Like `rs_verify_signature`, but takes Python ints. Raises `ValueError` for
+/// a value that isn't below the field prime, or that verification rejects as
+/// out of range, instead of returning `False`.
 #[pyfunction]
-fn rs_get_transfer_msg(
+fn rs_verify_signature_int(
     py: Python,
-    recipient_position_id: String,
-    sender_position_id: String,
-    collateral_id_hex: String,
-    amount: String,
-    expiration: String,
-    salt: String,
-    user_public_key_hex: String,
-
-    domain_name: String,
-    domain_version: String,
-    domain_chain_id: String,
-    domain_revision: String,
-) -> PyResult<String> {
-    py.allow_threads(move || {
-        // hex fields
-        let collateral_id = Felt::from_hex(&collateral_id_hex).unwrap();
-        let user_key = Felt::from_hex(&user_public_key_hex).unwrap();
-
-        // decimal fields
-        let recipient = u32::from_str_radix(&recipient_position_id, 10).unwrap();
-        let position_id = u32::from_str_radix(&sender_position_id, 10).unwrap();
-        let amount = u64::from_str_radix(&amount, 10).unwrap();
-        let expiration = u64::from_str_radix(&expiration, 10).unwrap();
-        let salt = Felt::from_dec_str(&salt).unwrap();
-
-        let transfer_args = TransferArgs {
-            recipient: PositionId { value: recipient },
-            position_id: PositionId { value: position_id },
-            collateral_id: AssetId {
-                value: collateral_id,
-            },
-            amount,
-            expiration: Timestamp {
-                seconds: expiration,
-            },
-            salt,
-        };
-        let domain = StarknetDomain {
-            name: domain_name,
-            version: domain_version,
-            chain_id: domain_chain_id,
-            revision: u32::from_str_radix(&domain_revision, 10).unwrap(),
-        };
-        let message = transfer_args.message_hash(&domain, user_key).unwrap();
-        Ok(message.to_hex_string())
+    public_key_int: BigUint,
+    msg_hash_int: BigUint,
+    r_int: BigUint,
+    s_int: BigUint,
+) -> PyResult<bool> {
+    py.allow_threads(move || -> Result<bool, String> {
+        let public_key = int_to_felt("public_key", &public_key_int)?;
+        let msg_hash = int_to_felt("msg_hash", &msg_hash_int)?;
+        let r = int_to_felt("r", &r_int)?;
+        let s = int_to_felt("s", &s_int)?;
+        verify_signature(&public_key, &msg_hash, &r, &s).map_err(|_| {
+            rejection_reason(&public_key, &msg_hash, &r, &s)
+                .unwrap()
+                .to_string()
+        })
     })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
 }
 
+/// Like `rs_verify_signature`, with arguments in audit-log order. Values out
+/// of range for the curve verify as `False` instead of panicking; malformed
+/// hex still raises `ValueError`.
 #[pyfunction]
-fn rs_get_order_msg(
+fn rs_verify_hash_signature(
     py: Python,
-    position_id: String,
-    base_asset_id_hex: String,
-    base_amount: String,
-    quote_asset_id_hex: String,
-    quote_amount: String,
-    fee_asset_id_hex: String,
-    fee_amount: String,
-    expiration: String,
-    salt: String,
-    user_public_key_hex: String,
-
-    domain_name: String,
-    domain_version: String,
-    domain_chain_id: String,
-    domain_revision: String,
-) -> PyResult<String> {
-    py.allow_threads(move || {
-        //hex fields
-        let base_asset_id = Felt::from_hex(&base_asset_id_hex).unwrap();
-        let quote_asset_id = Felt::from_hex(&quote_asset_id_hex).unwrap();
-        let fee_asset_id = Felt::from_hex(&fee_asset_id_hex).unwrap();
-        let user_key = Felt::from_hex(&user_public_key_hex).unwrap();
-
-        //decimal fields
-        let position_id = u32::from_str_radix(&position_id, 10).unwrap();
-        let base_amount = i64::from_str_radix(&base_amount, 10).unwrap();
-        let quote_amount = i64::from_str_radix(&quote_amount, 10).unwrap();
-        let fee_amount = u64::from_str_radix(&fee_amount, 10).unwrap();
-        let expiration = u64::from_str_radix(&expiration, 10).unwrap();
-        let salt = u64::from_str_radix(&salt, 10).unwrap();
-
-        let order = Order {
-            position_id: PositionId { value: position_id },
-            base_asset_id: AssetId {
-                value: base_asset_id,
-            },
-            base_amount: base_amount,
-            quote_asset_id: AssetId {
-                value: quote_asset_id,
-            },
-            quote_amount: quote_amount,
-            fee_asset_id: AssetId {
-                value: fee_asset_id,
-            },
-            fee_amount: fee_amount,
-            expiration: Timestamp {
-                seconds: expiration,
-            },
-            salt: salt.try_into().unwrap(),
-        };
-        let domain = StarknetDomain {
-            name: domain_name,
-            version: domain_version,
-            chain_id: domain_chain_id,
-            revision: u32::from_str_radix(&domain_revision, 10).unwrap(),
-        };
-        let message = order.message_hash(&domain, user_key).unwrap();
-        Ok(message.to_hex_string())
+    hash_hex: String,
+    r_hex: String,
+    s_hex: String,
+    public_key_hex: String,
+) -> PyResult<bool> {
+    py.allow_threads(move || -> Result<bool, String> {
+        let msg_hash = str_to_field_element(&hash_hex)?;
+        let r = str_to_field_element(&r_hex)?;
+        let s = str_to_field_element(&s_hex)?;
+        let public_key = str_to_field_element(&public_key_hex)?;
+        Ok(verify_signature(&public_key, &msg_hash, &r, &s).unwrap_or(false))
     })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+// Parses a fixed-width big-endian scalar, rejecting anything that isn't
+// exactly 32 bytes or that isn't reduced modulo the curve order
+fn strict_scalar(field: &str, bytes: &[u8]) -> Result<Felt, String> {
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| format!("{} must be exactly 32 bytes, got {}", field, bytes.len()))?;
+    if bytes >= EC_ORDER.to_bytes_be() {
+        return Err(format!("{} is not below the curve order", field));
+    }
+    Ok(Felt::from_bytes_be(&bytes))
 }
 
+/// Like `rs_verify_signature`, but takes `r` and `s` as fixed 32-byte
+/// big-endian encodings and raises on any other length or on values not below
+/// the curve order, instead of accepting whatever parses as hex.
 #[pyfunction]
-fn rs_generate_keypair_from_eth_signature(
-    _py: Python,
-    signature: String,
-) -> PyResult<(String, String)> {
-    return get_private_key_from_eth_signature(&signature)
-        .and_then(|private_key| {
-            let public_key = fetch_public_key(&private_key);
-            let private_key_hex = private_key.to_hex_string();
-            let public_key_hex = public_key.to_hex_string();
-            Ok((private_key_hex, public_key_hex))
+fn rs_verify_signature_strict_encoding(
+    py: Python,
+    public_key_hex: String,
+    msg_hash_hex: String,
+    r_bytes: &[u8],
+    s_bytes: &[u8],
+) -> PyResult<bool> {
+    py.allow_threads(move || -> Result<bool, String> {
+        let public_key = str_to_field_element(&public_key_hex)?;
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let r = strict_scalar("r", r_bytes)?;
+        let s = strict_scalar("s", s_bytes)?;
+        Ok(verify_signature(&public_key, &msg_hash, &r, &s).unwrap_or(false))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Outcome of `rs_verify_signature_detailed`. `reason` is `None` exactly when
+/// `valid` is true.
+#[pyclass(get_all, frozen)]
+struct VerifyResult {
+    valid: bool,
+    reason: Option<String>,
+}
+
+impl VerifyResult {
+    fn new(reason: Option<&str>) -> Self {
+        VerifyResult {
+            valid: reason.is_none(),
+            reason: reason.map(str::to_string),
+        }
+    }
+}
+
+#[pymethods]
+impl VerifyResult {
+    fn __repr__(&self) -> String {
+        match &self.reason {
+            Some(reason) => format!("VerifyResult(valid={}, reason={:?})", self.valid, reason),
+            None => format!("VerifyResult(valid={}, reason=None)", self.valid),
+        }
+    }
+}
+
+/// Like `rs_verify_signature`, but says why a signature was rejected. Meant
+/// for debugging; malformed hex still raises `ValueError`.
+#[pyfunction]
+fn rs_verify_signature_detailed(
+    py: Python,
+    public_key_hex: String,
+    msg_hash_hex: String,
+    r_hex: String,
+    s_hex: String,
+) -> PyResult<VerifyResult> {
+    py.allow_threads(move || -> Result<VerifyResult, String> {
+        let public_key = str_to_field_element(&public_key_hex)?;
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let r = str_to_field_element(&r_hex)?;
+        let s = str_to_field_element(&s_hex)?;
+        Ok(VerifyResult::new(rejection_reason(
+            &public_key,
+            &msg_hash,
+            &r,
+            &s,
+        )))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+// Why verification fails, or None if the signature is valid
+fn rejection_reason(
+    public_key: &Felt,
+    msg_hash: &Felt,
+    r: &Felt,
+    s: &Felt,
+) -> Option<&'static str> {
+    match verify_signature(public_key, msg_hash, r, s) {
+        Ok(true) => None,
+        Ok(false) => Some("hash mismatch"),
+        Err(VerifyError::InvalidMessageHash) => Some("message hash out of range"),
+        Err(VerifyError::InvalidR) => Some("r out of range"),
+        Err(VerifyError::InvalidS) => Some("s out of range"),
+        Err(VerifyError::InvalidPublicKey) => Some("point not on curve"),
+    }
+}
+
+/// Like `rs_verify_signature_detailed`, but also rejects signatures whose `s`
+/// is in the upper half of the curve order, as `(r, s)` and `(r, n - s)` both
+/// verify. Such signatures come back with reason `"non-canonical s"`, which is
+/// only reported when the signature is otherwise valid.
+#[pyfunction]
+fn rs_verify_signature_canonical(
+    py: Python,
+    public_key_hex: String,
+    msg_hash_hex: String,
+    r_hex: String,
+    s_hex: String,
+) -> PyResult<VerifyResult> {
+    py.allow_threads(move || -> Result<VerifyResult, String> {
+        let public_key = str_to_field_element(&public_key_hex)?;
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let r = str_to_field_element(&r_hex)?;
+        let s = str_to_field_element(&s_hex)?;
+        let reason = rejection_reason(&public_key, &msg_hash, &r, &s).or(if s > *EC_ORDER_HALF {
+            Some("non-canonical s")
+        } else {
+            None
+        });
+        Ok(VerifyResult::new(reason))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Like `rs_verify_signature`, but for untrusted input: out-of-range values
+/// and public keys off the curve return `False` only after the full
+/// verification has run, so latency doesn't reveal which check failed. It is
+/// not constant time, as the curve arithmetic underneath isn't. Hex that
+/// doesn't parse still raises `ValueError` straight away.
+#[pyfunction]
+fn rs_verify_signature_timing_safe(
+    py: Python,
+    public_key_hex: String,
+    msg_hash_hex: String,
+    r_hex: String,
+    s_hex: String,
+) -> PyResult<bool> {
+    py.allow_threads(move || -> Result<bool, String> {
+        let public_key = str_to_field_element(&public_key_hex)?;
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let r = str_to_field_element(&r_hex)?;
+        let s = str_to_field_element(&s_hex)?;
+        Ok(verify_signature_full(&public_key, &msg_hash, &r, &s))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Checks that `r` is the x-coordinate of a point on the curve, as the `r` of
+/// an honestly produced signature always is, being the x of the nonce point
+/// `k * G`. A signature whose `r` fails this was crafted or corrupted.
+#[pyfunction]
+fn rs_signature_r_on_curve(py: Python, r_hex: String) -> PyResult<bool> {
+    py.allow_threads(move || -> Result<bool, String> {
+        let r = str_to_field_element(&r_hex)?;
+        Ok(is_curve_x(&r))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Checks only that `r` and `s` lie in `[1, 2^251)`, the range verification
+/// accepts, without touching the curve. Returns `"ok"`, `"r_out_of_range"`,
+/// `"s_out_of_range"` or `"both"`. Malformed hex still raises `ValueError`.
+#[pyfunction]
+fn rs_signature_range_check(py: Python, r_hex: String, s_hex: String) -> PyResult<&'static str> {
+    py.allow_threads(move || -> Result<&'static str, String> {
+        let r = str_to_field_element(&r_hex)?;
+        let s = str_to_field_element(&s_hex)?;
+        let in_range = |value: &Felt| *value != Felt::ZERO && *value < *ELEMENT_UPPER_BOUND;
+        Ok(match (in_range(&r), in_range(&s)) {
+            (true, true) => "ok",
+            (false, true) => "r_out_of_range",
+            (true, false) => "s_out_of_range",
+            (false, false) => "both",
         })
-        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>);
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+fn is_valid_signature(public_key_hex: &str, msg_hash_hex: &str, r_hex: &str, s_hex: &str) -> bool {
+    let felts = (
+        str_to_field_element(public_key_hex),
+        str_to_field_element(msg_hash_hex),
+        str_to_field_element(r_hex),
+        str_to_field_element(s_hex),
+    );
+    match felts {
+        (Ok(public_key), Ok(msg_hash), Ok(r), Ok(s)) => {
+            verify_signature(&public_key, &msg_hash, &r, &s).unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+/// Verifies `(public_key, msg_hash, r, s)` tuples in parallel and returns the
+/// indices of the ones that fail, in ascending order. Malformed hex counts as
+/// a failure rather than aborting the whole batch. With `timeout_ms`, raises
+/// `TimeoutError` once the deadline passes.
+#[pyfunction]
+#[pyo3(signature = (items, timeout_ms = None))]
+fn rs_invalid_signature_indices(
+    py: Python,
+    items: &PyList,
+    timeout_ms: Option<u64>,
+) -> PyResult<Vec<usize>> {
+    let items: Vec<(String, String, String, String)> = extract_batch("items", items)?;
+    py.allow_threads(move || -> Result<Vec<usize>, BatchError> {
+        let valid = par_map_with_deadline(
+            &items,
+            timeout_ms,
+            |_, (public_key_hex, msg_hash_hex, r_hex, s_hex)| {
+                Ok(is_valid_signature(
+                    public_key_hex,
+                    msg_hash_hex,
+                    r_hex,
+                    s_hex,
+                ))
+            },
+        )?;
+        Ok(valid
+            .iter()
+            .enumerate()
+            .filter(|(_, valid)| !**valid)
+            .map(|(index, _)| index)
+            .collect())
+    })
+    .map_err(PyErr::from)
+}
+
+/// Checks the signature against every key in `pub_keys_hex` and returns the
+/// index of the first one it verifies against, or `None`. All keys are checked
+/// to be on the curve, even past a match, so a bad allowlist entry is caught
+/// early instead of only when it happens to be reached.
+#[pyfunction]
+fn rs_verify_any(
+    py: Python,
+    pub_keys_hex: Vec<String>,
+    msg_hash_hex: String,
+    r_hex: String,
+    s_hex: String,
+) -> PyResult<Option<usize>> {
+    py.allow_threads(move || -> Result<Option<usize>, String> {
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let r = str_to_field_element(&r_hex)?;
+        let s = str_to_field_element(&s_hex)?;
+        let mut matched = None;
+        for (index, public_key_hex) in pub_keys_hex.iter().enumerate() {
+            let public_key = str_to_field_element(public_key_hex)
+                .map_err(|e| format!("Invalid pub_keys[{}]: {}", index, e))?;
+            match verify_signature(&public_key, &msg_hash, &r, &s) {
+                Ok(true) if matched.is_none() => matched = Some(index),
+                Ok(_) => {}
+                Err(VerifyError::InvalidPublicKey) => {
+                    return Err(format!("pub_keys[{}] is not on the curve", index))
+                }
+                Err(e) => return Err(format!("Verification failed: {:?}", e)),
+            }
+        }
+        Ok(matched)
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Checks `(pub_key, r, s)` signatures from several signers over one message
+/// hash and returns whether at least `threshold` distinct public keys produced
+/// a valid one. A key listed more than once counts toward the threshold only
+/// once. Raises for a public key that isn't on the curve.
+#[pyfunction]
+fn rs_verify_multisig(
+    py: Python,
+    msg_hash_hex: String,
+    signers: Vec<(String, String, String)>,
+    threshold: usize,
+) -> PyResult<bool> {
+    py.allow_threads(move || -> Result<bool, String> {
+        if threshold == 0 {
+            return Err("threshold must be at least 1".to_string());
+        }
+        let msg_hash = str_to_field_element(&msg_hash_hex)?;
+        let mut approved: Vec<Felt> = Vec::new();
+        for (index, (public_key_hex, r_hex, s_hex)) in signers.iter().enumerate() {
+            let parse = |value: &str| {
+                str_to_field_element(value)
+                    .map_err(|e| format!("Invalid signers[{}]: {}", index, e))
+            };
+            let public_key = parse(public_key_hex)?;
+            let r = parse(r_hex)?;
+            let s = parse(s_hex)?;
+            match verify_signature(&public_key, &msg_hash, &r, &s) {
+                Ok(true) if !approved.contains(&public_key) => approved.push(public_key),
+                Ok(_) => {}
+                Err(VerifyError::InvalidPublicKey) => {
+                    return Err(format!("signers[{}] public key is not on the curve", index))
+                }
+                Err(e) => {
+                    return Err(format!(
+                        "Verification of signers[{}] failed: {:?}",
+                        index, e
+                    ))
+                }
+            }
+        }
+        Ok(approved.len() >= threshold)
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+// Positional arguments of rs_get_order_msg and rs_get_transfer_msg, in order
+const ORDER_FIELD_NAMES: [&str; 14] = [
+    "position_id",
+    "base_asset_id_hex",
+    "base_amount",
+    "quote_asset_id_hex",
+    "quote_amount",
+    "fee_asset_id_hex",
+    "fee_amount",
+    "expiration",
+    "salt",
+    "user_public_key_hex",
+    "domain_name",
+    "domain_version",
+    "domain_chain_id",
+    "domain_revision",
+];
+const TRANSFER_FIELD_NAMES: [&str; 11] = [
+    "recipient_position_id",
+    "sender_position_id",
+    "collateral_id_hex",
+    "amount",
+    "expiration",
+    "salt",
+    "user_public_key_hex",
+    "domain_name",
+    "domain_version",
+    "domain_chain_id",
+    "domain_revision",
+];
+
+/// Names of the positional arguments `rs_get_order_msg` takes, in order.
+#[pyfunction]
+fn rs_order_field_names() -> Vec<&'static str> {
+    ORDER_FIELD_NAMES.to_vec()
+}
+
+/// Names of the positional arguments `rs_get_transfer_msg` takes, in order.
+#[pyfunction]
+fn rs_transfer_field_names() -> Vec<&'static str> {
+    TRANSFER_FIELD_NAMES.to_vec()
+}
+
+/// Returns the domain separator hash the message-hash functions bind into
+/// every message, for checking a configured domain against a published one.
+#[pyfunction]
+fn rs_compute_domain_hash(
+    py: Python,
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        Ok(domain.hash().to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Encodes up to 31 ASCII characters as a Cairo short string felt, the form
+/// domain fields such as `name` and `chain_id` take in the hash.
+#[pyfunction]
+fn rs_encode_short_string(value: &str) -> PyResult<String> {
+    encode_short_string(value)
+        .map(|felt| felt.to_output_hex())
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Decodes a Cairo short string felt back into its ASCII characters.
+#[pyfunction]
+fn rs_decode_short_string(felt_hex: &str) -> PyResult<String> {
+    str_to_field_element(felt_hex)
+        .and_then(|felt| decode_short_string(&felt))
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Passing `owner_address_hex` hashes the transfer in a layout that also
+/// binds the position owner; without it the original layout is used.
+/// Accounts on nonce-based replay protection pass `nonce` instead, which
+/// hashes a nonce layout; the two can't be combined.
+/// Passing `memo_hex` binds a memo felt for internal routing, which combines
+/// with neither of those; without it the hash is unchanged.
+/// Those three layouts are drafts, so each of them is an error unless built
+/// with the `draft-layouts` feature.
+/// `min_expiration` and `max_expiration` optionally bound the expiration, to
+/// catch e.g. milliseconds passed where seconds are expected.
+/// Every argument can be passed by keyword, under the names
+/// `rs_transfer_field_names` returns.
+#[pyfunction]
+#[pyo3(signature = (
+    recipient_position_id,
+    sender_position_id,
+    collateral_id_hex,
+    amount,
+    expiration,
+    salt,
+    user_public_key_hex,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+    owner_address_hex = None,
+    min_expiration = None,
+    max_expiration = None,
+    nonce = None,
+    memo_hex = None,
+))]
+fn rs_get_transfer_msg(
+    py: Python,
+    recipient_position_id: DecimalArg,
+    sender_position_id: DecimalArg,
+    collateral_id_hex: HexArg,
+    amount: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+    owner_address_hex: Option<HexArg>,
+    min_expiration: Option<DecimalArg>,
+    max_expiration: Option<DecimalArg>,
+    nonce: Option<DecimalArg>,
+    memo_hex: Option<HexArg>,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        check_expiration_window(
+            &expiration,
+            min_expiration.as_ref(),
+            max_expiration.as_ref(),
+        )?;
+        let transfer = TransferFields {
+            recipient_position_id,
+            sender_position_id,
+            collateral_id_hex,
+            amount,
+            expiration,
+            salt,
+            user_public_key_hex,
+            owner_address_hex,
+            nonce,
+            memo_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message = transfer.message_hash(&domain)?;
+        Ok(message.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Like `rs_get_transfer_msg` in the original layout, but returns the
+/// message hash as a Python `int` rather than hex.
+#[pyfunction]
+fn rs_get_transfer_msg_int(
+    py: Python,
+    recipient_position_id: DecimalArg,
+    sender_position_id: DecimalArg,
+    collateral_id_hex: HexArg,
+    amount: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<BigUint> {
+    py.allow_threads(move || -> Result<BigUint, String> {
+        let transfer = TransferFields {
+            recipient_position_id,
+            sender_position_id,
+            collateral_id_hex,
+            amount,
+            expiration,
+            salt,
+            user_public_key_hex,
+            owner_address_hex: None,
+            nonce: None,
+            memo_hex: None,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message = transfer.message_hash(&domain)?;
+        Ok(BigUint::from_bytes_be(&message.to_bytes_be()))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Hashes a withdrawal from a position to an L1 Ethereum address, which must
+/// be given as `0x` followed by exactly 40 hex digits.
+/// Only built with the `draft-layouts` feature.
+#[cfg(feature = "draft-layouts")]
+#[pyfunction]
+#[pyo3(signature = (
+    position_id,
+    collateral_id_hex,
+    amount,
+    eth_address_hex,
+    expiration,
+    salt,
+    user_public_key_hex,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+))]
+fn rs_get_withdrawal_to_address_msg(
+    py: Python,
+    position_id: DecimalArg,
+    collateral_id_hex: HexArg,
+    amount: DecimalArg,
+    eth_address_hex: HexArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let withdrawal = WithdrawalFields {
+            position_id,
+            collateral_id_hex,
+            amount,
+            eth_address_hex,
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message = withdrawal.message_hash(&domain)?;
+        Ok(message.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Hashes a request to cancel every open order of a position, as sent by an
+/// emergency stop.
+/// Only built with the `draft-layouts` feature.
+#[cfg(feature = "draft-layouts")]
+#[pyfunction]
+fn rs_get_cancel_all_msg(
+    py: Python,
+    position_id: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let cancel_all = CancelAllOrders {
+            position_id: PositionId {
+                value: position_id.parse::<u32>("position_id")?,
+            },
+            expiration: Timestamp {
+                seconds: expiration.parse::<u64>("expiration")?,
+            },
+            salt: Felt::from_dec_str(&salt.0)
+                .map_err(|e| format!("Failed to parse salt from {}: {}", salt.0, e))?,
+        };
+        let user_key = user_public_key_hex.parse("user_public_key")?;
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message = cancel_all.message_hash(&domain, user_key).unwrap();
+        Ok(message.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Hashes the settlement of two matched orders, the message a market maker
+/// co-signs. Orders are dicts keyed like `rs_hash_message` order fields; the
+/// hash binds the signer's key rather than either order's `user_public_key`.
+/// Only built with the `draft-layouts` feature.
+#[cfg(feature = "draft-layouts")]
+#[pyfunction]
+fn rs_get_settlement_msg(
+    py: Python,
+    order_a: OrderFields,
+    order_b: OrderFields,
+    actual_a_amount: DecimalArg,
+    actual_b_amount: DecimalArg,
+    actual_a_fee: DecimalArg,
+    actual_b_fee: DecimalArg,
+    signer_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let settlement = Settlement {
+            order_a: order_a.to_order()?,
+            order_b: order_b.to_order()?,
+            actual_a_amount: actual_a_amount.parse::<i64>("actual_a_amount")?,
+            actual_b_amount: actual_b_amount.parse::<i64>("actual_b_amount")?,
+            actual_a_fee: actual_a_fee.parse::<u64>("actual_a_fee")?,
+            actual_b_fee: actual_b_fee.parse::<u64>("actual_b_fee")?,
+        };
+        let signer_key = signer_public_key_hex.parse("signer_public_key")?;
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message = settlement.message_hash(&domain, signer_key).unwrap();
+        Ok(message.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Pre-trade check that two orders, given as dicts like `rs_get_settlement_msg`
+/// takes, can be matched: same base and quote assets, opposite sides, and
+/// prices that cross. `rs_orders_mismatch_reason` says why not.
+#[pyfunction]
+fn rs_orders_matchable(py: Python, order_a: OrderFields, order_b: OrderFields) -> PyResult<bool> {
+    py.allow_threads(move || -> Result<bool, String> {
+        Ok(order_a
+            .to_order()?
+            .match_error(&order_b.to_order()?)
+            .is_none())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Like `rs_orders_matchable`, but returns why the orders can't be matched,
+/// or `None` if they can.
+#[pyfunction]
+fn rs_orders_mismatch_reason(
+    py: Python,
+    order_a: OrderFields,
+    order_b: OrderFields,
+) -> PyResult<Option<&'static str>> {
+    py.allow_threads(move || -> Result<Option<&'static str>, String> {
+        Ok(order_a.to_order()?.match_error(&order_b.to_order()?))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Hashes an order, transfer or withdrawal given its fields as a dict, picking
+/// the layout from `message_type` (`"order"`, `"transfer"` or `"withdrawal"`).
+/// Dict keys are the field names without any `_hex` suffix; a transfer may
+/// also carry `owner_address`, `nonce` or `memo`. Withdrawals are an error
+/// unless built with the `draft-layouts` feature.
+#[pyfunction]
+fn rs_hash_message(
+    py: Python,
+    message_type: &str,
+    fields: &PyDict,
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<String> {
+    let message = MessageFields::from_dict(message_type, fields)?;
+    py.allow_threads(move || -> Result<String, String> {
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        Ok(message.message_hash(&domain)?.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Rejects orders whose base and quote asset ids are equal, which are almost
+/// always a bug, unless `allow_same_assets` is set. Reduce-only orders are
+/// hashed with the flag bound in; the default keeps the plain order hash.
+/// Post-only orders are hashed in a layout binding both flags.
+/// Passing `leverage`, from 1 to 100, hashes the order in a layout binding
+/// both flags and the leverage; leaving it out keeps the hashes above.
+/// Passing `parent_order_hash_hex` links a child order of an OCO or bracket
+/// pair to its parent's message hash.
+/// Passing `audit_tag_hex`, a felt naming the originating desk, wraps the
+/// order (or its link to a parent) with the tag bound in; leaving it out keeps
+/// the hashes above.
+/// All of these options hash draft layouts, so each is an error unless built
+/// with the `draft-layouts` feature.
+/// Every argument can be passed by keyword, under the names
+/// `rs_order_field_names` returns.
+#[pyfunction]
+#[pyo3(signature = (
+    position_id,
+    base_asset_id_hex,
+    base_amount,
+    quote_asset_id_hex,
+    quote_amount,
+    fee_asset_id_hex,
+    fee_amount,
+    expiration,
+    salt,
+    user_public_key_hex,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+    allow_same_assets = false,
+    reduce_only = false,
+    parent_order_hash_hex = None,
+    post_only = false,
+    leverage = None,
+    audit_tag_hex = None,
+))]
+fn rs_get_order_msg(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id_hex: HexArg,
+    base_amount: DecimalArg,
+    quote_asset_id_hex: HexArg,
+    quote_amount: DecimalArg,
+    fee_asset_id_hex: HexArg,
+    fee_amount: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+    allow_same_assets: bool,
+    reduce_only: bool,
+    parent_order_hash_hex: Option<HexArg>,
+    post_only: bool,
+    leverage: Option<DecimalArg>,
+    audit_tag_hex: Option<HexArg>,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let order = OrderFields {
+            position_id,
+            base_asset_id_hex,
+            base_amount,
+            quote_asset_id_hex,
+            quote_amount,
+            fee_asset_id_hex,
+            fee_amount,
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        if !allow_same_assets {
+            order.check_distinct_assets()?;
+        }
+        let leverage = leverage.as_ref().map(parse_leverage).transpose()?;
+        let parent_order_hash = parent_order_hash_hex
+            .map(|parent_order_hash_hex| parent_order_hash_hex.parse_canonical("parent_order_hash"))
+            .transpose()?;
+        #[cfg(feature = "draft-layouts")]
+        if let Some(audit_tag_hex) = audit_tag_hex {
+            let audit_tag = audit_tag_hex.parse_canonical("audit_tag")?;
+            let message = order.audited_message_hash(
+                &domain,
+                reduce_only,
+                post_only,
+                leverage,
+                parent_order_hash,
+                audit_tag,
+            )?;
+            return Ok(message.to_output_hex());
+        }
+        #[cfg(not(feature = "draft-layouts"))]
+        if audit_tag_hex.is_some() {
+            return Err(draft_layout_disabled("audit_tag"));
+        }
+        let message = match (parent_order_hash, leverage) {
+            #[cfg(feature = "draft-layouts")]
+            (Some(parent_order_hash), _) => order.linked_message_hash(
+                &domain,
+                reduce_only,
+                post_only,
+                leverage,
+                parent_order_hash,
+            )?,
+            #[cfg(not(feature = "draft-layouts"))]
+            (Some(_), _) => return Err(draft_layout_disabled("parent_order_hash")),
+            #[cfg(feature = "draft-layouts")]
+            (None, Some(leverage)) => {
+                order.leveraged_message_hash(&domain, reduce_only, post_only, leverage)?
+            }
+            #[cfg(not(feature = "draft-layouts"))]
+            (None, Some(_)) => return Err(draft_layout_disabled("leverage")),
+            #[cfg(feature = "draft-layouts")]
+            (None, None) if post_only => order.post_only_message_hash(&domain, reduce_only)?,
+            #[cfg(not(feature = "draft-layouts"))]
+            (None, None) if post_only => return Err(draft_layout_disabled("post_only")),
+            #[cfg(feature = "draft-layouts")]
+            (None, None) if reduce_only => order.reduce_only_message_hash(&domain)?,
+            #[cfg(not(feature = "draft-layouts"))]
+            (None, None) if reduce_only => return Err(draft_layout_disabled("reduce_only")),
+            (None, None) => order.message_hash(&domain)?,
+        };
+        Ok(message.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Testing helper that hashes an order like `rs_get_order_msg`, but under
+/// `forced_revision` without checking it is a revision the exchange accepts,
+/// to generate expected hashes for a domain upgrade before it goes live.
+/// Only the revision number bound into the domain changes: the domain is
+/// still hashed with the revision 1 encoding, so this can't produce hashes
+/// for a revision that encodes its domain differently, such as revision 0.
+/// Never sign its output for production: a signature over a revision the
+/// exchange doesn't run yet is at best rejected, and is replayable there once
+/// it does. Only built with the `testing` feature.
+#[cfg(feature = "testing")]
+#[pyfunction]
+fn rs_get_order_msg_with_revision_override(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id_hex: HexArg,
+    base_amount: DecimalArg,
+    quote_asset_id_hex: HexArg,
+    quote_amount: DecimalArg,
+    fee_asset_id_hex: HexArg,
+    fee_amount: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    forced_revision: DecimalArg,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let order = OrderFields {
+            position_id,
+            base_asset_id_hex,
+            base_amount,
+            quote_asset_id_hex,
+            quote_amount,
+            fee_asset_id_hex,
+            fee_amount,
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = rust_crypto_lib_base::starknet_messages::StarknetDomain {
+            name: domain_name,
+            version: domain_version,
+            chain_id: domain_chain_id,
+            revision: forced_revision.parse::<u32>("forced_revision")?,
+        };
+        let message = order.message_hash(&domain)?;
+        Ok(message.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Hashes an order whose base and quote amounts are given as a `"buy"` or
+/// `"sell"` side plus an unsigned magnitude; buys are positive, sells negative.
+#[pyfunction]
+#[pyo3(signature = (
+    position_id,
+    base_asset_id_hex,
+    base_side,
+    base_abs_amount,
+    quote_asset_id_hex,
+    quote_side,
+    quote_abs_amount,
+    fee_asset_id_hex,
+    fee_amount,
+    expiration,
+    salt,
+    user_public_key_hex,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+))]
+fn rs_get_order_msg_sided(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id_hex: HexArg,
+    base_side: String,
+    base_abs_amount: DecimalArg,
+    quote_asset_id_hex: HexArg,
+    quote_side: String,
+    quote_abs_amount: DecimalArg,
+    fee_asset_id_hex: HexArg,
+    fee_amount: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let order = OrderFields {
+            position_id,
+            base_asset_id_hex,
+            base_amount: signed_amount("base", &base_side, &base_abs_amount)?,
+            quote_asset_id_hex,
+            quote_amount: signed_amount("quote", &quote_side, &quote_abs_amount)?,
+            fee_asset_id_hex,
+            fee_amount,
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message = order.message_hash(&domain)?;
+        Ok(message.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Hashes an order whose amounts are given as human-readable decimal strings,
+/// each scaled by its instrument's quantum in exact integer math, e.g. "0.1"
+/// with a quantum of 1000 hashes as 100. Raises for an amount that doesn't
+/// scale to a whole number instead of rounding it.
+#[pyfunction]
+#[pyo3(signature = (
+    position_id,
+    base_asset_id_hex,
+    base_human_amount,
+    base_quantum,
+    quote_asset_id_hex,
+    quote_human_amount,
+    quote_quantum,
+    fee_asset_id_hex,
+    fee_human_amount,
+    fee_quantum,
+    expiration,
+    salt,
+    user_public_key_hex,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+))]
+fn rs_get_order_msg_scaled(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id_hex: HexArg,
+    base_human_amount: String,
+    base_quantum: DecimalArg,
+    quote_asset_id_hex: HexArg,
+    quote_human_amount: String,
+    quote_quantum: DecimalArg,
+    fee_asset_id_hex: HexArg,
+    fee_human_amount: String,
+    fee_quantum: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let order = OrderFields {
+            position_id,
+            base_asset_id_hex,
+            base_amount: scaled_amount("base", &base_human_amount, &base_quantum)?,
+            quote_asset_id_hex,
+            quote_amount: scaled_amount("quote", &quote_human_amount, &quote_quantum)?,
+            fee_asset_id_hex,
+            fee_amount: scaled_amount("fee", &fee_human_amount, &fee_quantum)?,
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message = order.message_hash(&domain)?;
+        Ok(message.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Like `rs_get_order_msg` with every option left at its default, but
+/// returns the message hash as a Python `int` rather than hex.
+#[pyfunction]
+fn rs_get_order_msg_int(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id_hex: HexArg,
+    base_amount: DecimalArg,
+    quote_asset_id_hex: HexArg,
+    quote_amount: DecimalArg,
+    fee_asset_id_hex: HexArg,
+    fee_amount: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<BigUint> {
+    py.allow_threads(move || -> Result<BigUint, String> {
+        let order = OrderFields {
+            position_id,
+            base_asset_id_hex,
+            base_amount,
+            quote_asset_id_hex,
+            quote_amount,
+            fee_asset_id_hex,
+            fee_amount,
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        order.check_distinct_assets()?;
+        let message = order.message_hash(&domain)?;
+        Ok(BigUint::from_bytes_be(&message.to_bytes_be()))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
 }
 
-#[pymodule]
-fn fast_stark_crypto(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
-    m.add_function(wrap_pyfunction!(rs_get_public_key, m)?)?;
-    m.add_function(wrap_pyfunction!(rs_compute_pedersen_hash, m)?)?;
-    m.add_function(wrap_pyfunction!(rs_sign_message, m)?)?;
-    m.add_function(wrap_pyfunction!(rs_verify_signature, m)?)?;
-    m.add_function(wrap_pyfunction!(rs_get_order_msg, m)?)?;
-    m.add_function(wrap_pyfunction!(rs_get_transfer_msg, m)?)?;
-    m.add_function(wrap_pyfunction!(rs_generate_keypair_from_eth_signature, m)?)?;
-    Ok(())
-}
+// Each order field with a change bumping it by one, as rs_order_field_sensitivity
+// applies them. Integer fields wrap, which still changes the value
+type OrderPerturbation = (&'static str, fn(&mut Order, &mut Felt));
+const ORDER_PERTURBATIONS: [OrderPerturbation; 10] = [
+    ("position_id", |order, _| {
+        order.position_id.value = order.position_id.value.wrapping_add(1)
+    }),
+    ("base_asset_id_hex", |order, _| {
+        order.base_asset_id.value += Felt::ONE
+    }),
+    ("base_amount", |order, _| {
+        order.base_amount = order.base_amount.wrapping_add(1)
+    }),
+    ("quote_asset_id_hex", |order, _| {
+        order.quote_asset_id.value += Felt::ONE
+    }),
+    ("quote_amount", |order, _| {
+        order.quote_amount = order.quote_amount.wrapping_add(1)
+    }),
+    ("fee_asset_id_hex", |order, _| {
+        order.fee_asset_id.value += Felt::ONE
+    }),
+    ("fee_amount", |order, _| {
+        order.fee_amount = order.fee_amount.wrapping_add(1)
+    }),
+    ("expiration", |order, _| {
+        order.expiration.seconds = order.expiration.seconds.wrapping_add(1)
+    }),
+    ("salt", |order, _| order.salt += Felt::ONE),
+    ("user_public_key_hex", |_, user_key| *user_key += Felt::ONE),
+];
+
+// Unchanged order's hash, then each field name with its bumped order's hash
+type FieldSensitivity = (String, Vec<(&'static str, String)>);
+
+/// Debugging tool for differential testing against the exchange, not for
+/// signing. Bumps each order field by one in turn and returns the unchanged
+/// order's message hash along with `(field_name, message_hash)` for every
+/// resulting order, in `rs_order_field_names` order and naming. A hash equal
+/// to the unchanged one means that field isn't bound into the signature.
+#[pyfunction]
+fn rs_order_field_sensitivity(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id_hex: HexArg,
+    base_amount: DecimalArg,
+    quote_asset_id_hex: HexArg,
+    quote_amount: DecimalArg,
+    fee_asset_id_hex: HexArg,
+    fee_amount: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<FieldSensitivity> {
+    py.allow_threads(move || -> Result<FieldSensitivity, String> {
+        let order = OrderFields {
+            position_id,
+            base_asset_id_hex,
+            base_amount,
+            quote_asset_id_hex,
+            quote_amount,
+            fee_asset_id_hex,
+            fee_amount,
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let baseline = order.message_hash(&domain)?;
+        let sensitivity = ORDER_PERTURBATIONS
+            .iter()
+            .map(|(field, perturb)| {
+                let mut message = order.to_order()?;
+                let mut user_key = order.user_public_key()?;
+                perturb(&mut message, &mut user_key);
+                let hash = message.message_hash(&domain, user_key).unwrap();
+                Ok((*field, hash.to_output_hex()))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok((baseline.to_output_hex(), sensitivity))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Like `rs_get_order_msg` with every option left at its default, but takes
+/// the asset ids as `Felt` objects, so a hot loop over orders in the same
+/// markets parses them once rather than on every call. The configured asset
+/// id layout still applies.
+#[pyfunction]
+fn rs_get_order_msg_preparsed(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id: PyRef<FeltValue>,
+    base_amount: DecimalArg,
+    quote_asset_id: PyRef<FeltValue>,
+    quote_amount: DecimalArg,
+    fee_asset_id: PyRef<FeltValue>,
+    fee_amount: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<String> {
+    let (base_asset_id, quote_asset_id, fee_asset_id) =
+        (base_asset_id.0, quote_asset_id.0, fee_asset_id.0);
+    py.allow_threads(move || -> Result<String, String> {
+        let base_asset_id = apply_asset_layout(base_asset_id);
+        let quote_asset_id = apply_asset_layout(quote_asset_id);
+        check_distinct_asset_ids(base_asset_id, quote_asset_id)?;
+        let order = build_order(
+            &position_id,
+            [
+                base_asset_id,
+                quote_asset_id,
+                apply_asset_layout(fee_asset_id),
+            ],
+            &base_amount,
+            &quote_amount,
+            &fee_amount,
+            &expiration,
+            &salt,
+        )?;
+        let user_key = user_public_key_hex.parse("user_public_key")?;
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        Ok(order
+            .message_hash(&domain, user_key)
+            .unwrap()
+            .to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Hashes an order whose fee is given as a rate in basis points of the quote
+/// amount, converting it with the exchange's round-up convention first.
+#[pyfunction]
+#[pyo3(signature = (
+    position_id,
+    base_asset_id_hex,
+    base_amount,
+    quote_asset_id_hex,
+    quote_amount,
+    fee_asset_id_hex,
+    fee_rate_bps,
+    expiration,
+    salt,
+    user_public_key_hex,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+))]
+fn rs_get_order_msg_fee_rate(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id_hex: HexArg,
+    base_amount: DecimalArg,
+    quote_asset_id_hex: HexArg,
+    quote_amount: DecimalArg,
+    fee_asset_id_hex: HexArg,
+    fee_rate_bps: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let fee_amount = fee_amount_from_rate(
+            quote_amount.parse::<i64>("quote_amount")?,
+            fee_rate_bps.parse::<u64>("fee_rate_bps")?,
+        )?;
+        let order = OrderFields {
+            position_id,
+            base_asset_id_hex,
+            base_amount,
+            quote_asset_id_hex,
+            quote_amount,
+            fee_asset_id_hex,
+            fee_amount: DecimalArg(fee_amount.to_string()),
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message = order.message_hash(&domain)?;
+        Ok(message.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Converts the exchange's integer asset id to its felt form, shifting it left
+/// by `config["shift"]` bits and filling the low bits with `config["tag"]`.
+#[pyfunction]
+fn rs_asset_id_from_int(py: Python, int_id: DecimalArg, config: AssetIdConfig) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let int_id = int_id.parse::<u64>("int_id")?;
+        let asset_id = config.to_encoding()?.encode(int_id)?;
+        Ok(asset_id.value.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Inverse of `rs_asset_id_from_int`. Raises if the felt doesn't carry the
+/// configured tag or doesn't encode a u64.
+#[pyfunction]
+fn rs_asset_id_to_int(py: Python, asset_id_hex: HexArg, config: AssetIdConfig) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let asset_id = AssetId {
+            value: asset_id_hex.parse("asset_id")?,
+        };
+        let int_id = config.to_encoding()?.decode(&asset_id)?;
+        Ok(int_id.to_string())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Hashes an order for the fee-tier layout, which binds separate maker and
+/// taker fees in the fee asset. A fee left out defaults to zero.
+/// Only built with the `draft-layouts` feature.
+#[cfg(feature = "draft-layouts")]
+#[pyfunction]
+#[pyo3(signature = (
+    position_id,
+    base_asset_id_hex,
+    base_amount,
+    quote_asset_id_hex,
+    quote_amount,
+    fee_asset_id_hex,
+    expiration,
+    salt,
+    user_public_key_hex,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+    maker_fee_amount = DecimalArg("0".to_string()),
+    taker_fee_amount = DecimalArg("0".to_string()),
+))]
+fn rs_get_order_msg_with_fees(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id_hex: HexArg,
+    base_amount: DecimalArg,
+    quote_asset_id_hex: HexArg,
+    quote_amount: DecimalArg,
+    fee_asset_id_hex: HexArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+    maker_fee_amount: DecimalArg,
+    taker_fee_amount: DecimalArg,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let order = OrderFields {
+            position_id,
+            base_asset_id_hex,
+            base_amount,
+            quote_asset_id_hex,
+            quote_amount,
+            fee_asset_id_hex,
+            fee_amount: DecimalArg("0".to_string()),
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message =
+            order.message_hash_with_fees(&domain, &maker_fee_amount, &taker_fee_amount)?;
+        Ok(message.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Returns the order's struct hash alongside the final domain-bound message
+/// hash, so a mismatch can be traced to either the encoding or the domain.
+#[pyfunction]
+#[pyo3(signature = (
+    position_id,
+    base_asset_id_hex,
+    base_amount,
+    quote_asset_id_hex,
+    quote_amount,
+    fee_asset_id_hex,
+    fee_amount,
+    expiration,
+    salt,
+    user_public_key_hex,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+))]
+fn rs_get_order_hashes(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id_hex: HexArg,
+    base_amount: DecimalArg,
+    quote_asset_id_hex: HexArg,
+    quote_amount: DecimalArg,
+    fee_asset_id_hex: HexArg,
+    fee_amount: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+) -> PyResult<(String, String)> {
+    py.allow_threads(move || -> Result<(String, String), String> {
+        let order = OrderFields {
+            position_id,
+            base_asset_id_hex,
+            base_amount,
+            quote_asset_id_hex,
+            quote_amount,
+            fee_asset_id_hex,
+            fee_amount,
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let struct_hash = order.to_order()?.hash();
+        let message = order.message_hash(&domain)?;
+        Ok((struct_hash.to_output_hex(), message.to_output_hex()))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Hashes every order in the batch and commits to the resulting sequence with
+/// a single Poseidon hash. Returns the per-order hashes, in input order, next
+/// to the commitment so inclusion of any one order can be shown later. With
+/// `timeout_ms`, raises `TimeoutError` once the deadline passes.
+#[pyfunction]
+#[pyo3(signature = (
+    orders,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+    timeout_ms = None,
+))]
+fn rs_hash_order_batch(
+    py: Python,
+    orders: &PyList,
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+    timeout_ms: Option<u64>,
+) -> PyResult<(Vec<String>, String)> {
+    let orders: Vec<OrderFields> = extract_batch("orders", orders)?;
+    py.allow_threads(move || -> Result<(Vec<String>, String), BatchError> {
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let hashes = par_map_with_deadline(&orders, timeout_ms, |index, order| {
+            order
+                .message_hash(&domain)
+                .map_err(|e| format!("Order {}: {}", index, e))
+        })?;
+        let commitment = batch_commitment(&hashes);
+        Ok((
+            hashes.iter().map(|hash| hash.to_output_hex()).collect(),
+            commitment.to_output_hex(),
+        ))
+    })
+    .map_err(PyErr::from)
+}
+
+/// Validates every order in the batch and returns `(index, error)` for each
+/// invalid one, in index order, so all problems can be reported in one pass.
+/// An empty list means the whole batch is valid.
+#[pyfunction]
+fn rs_validate_order_batch(py: Python, orders: &PyList) -> PyResult<Vec<(usize, String)>> {
+    let orders: Vec<OrderFields> = extract_batch("orders", orders)?;
+    py.allow_threads(move || -> Result<Vec<(usize, String)>, BatchError> {
+        Ok(orders
+            .par_iter()
+            .enumerate()
+            .filter_map(|(index, order)| order.validate().err().map(|e| (index, e)))
+            .collect())
+    })
+    .map_err(PyErr::from)
+}
+
+/// Computes the EIP-712 signing hash of `message_json` for the L1 deposit
+/// flow. `types_json` maps type names to their fields; the primary type is
+/// the one no other type references. Returns the hash as 0x-prefixed hex.
+#[pyfunction]
+fn rs_eip712_hash(
+    py: Python,
+    domain_json: String,
+    types_json: String,
+    message_json: String,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let parse = |field: &str, json: &str| -> Result<serde_json::Value, String> {
+            serde_json::from_str(json).map_err(|e| format!("Invalid {}: {}", field, e))
+        };
+        let hash = eip712_hash(
+            &parse("domain_json", &domain_json)?,
+            &parse("types_json", &types_json)?,
+            &parse("message_json", &message_json)?,
+        )?;
+        let digits: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+        Ok(format!("0x{}", digits))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Sums the notional of a batch of orders, i.e. the magnitudes of their quote
+/// amounts, and returns the total as a decimal string. The sum is taken in
+/// 128 bits, so it cannot overflow for any batch under the size limit.
+#[pyfunction]
+fn rs_sum_order_notionals(py: Python, orders: &PyList) -> PyResult<String> {
+    let orders: Vec<OrderFields> = extract_batch("orders", orders)?;
+    py.allow_threads(move || -> Result<String, BatchError> {
+        let mut total: u128 = 0;
+        for (index, order) in orders.iter().enumerate() {
+            let order = order
+                .to_order()
+                .map_err(|e| format!("Order {}: {}", index, e))?;
+            total += u128::from(order.notional());
+        }
+        Ok(total.to_string())
+    })
+    .map_err(PyErr::from)
+}
+
+/// Hashes and signs an order and returns the JSON body to submit: the order
+/// fields exactly as they were hashed, the message hash, the public key and
+/// the signature. Raises if `priv_key_hex` doesn't belong to the order's
+/// `user_public_key`, since the signature could never verify.
+#[pyfunction]
+#[pyo3(signature = (
+    position_id,
+    base_asset_id_hex,
+    base_amount,
+    quote_asset_id_hex,
+    quote_amount,
+    fee_asset_id_hex,
+    fee_amount,
+    expiration,
+    salt,
+    user_public_key_hex,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+    priv_key_hex,
+))]
+fn rs_build_signed_order_payload(
+    py: Python,
+    position_id: DecimalArg,
+    base_asset_id_hex: HexArg,
+    base_amount: DecimalArg,
+    quote_asset_id_hex: HexArg,
+    quote_amount: DecimalArg,
+    fee_asset_id_hex: HexArg,
+    fee_amount: DecimalArg,
+    expiration: DecimalArg,
+    salt: DecimalArg,
+    user_public_key_hex: HexArg,
+
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+    priv_key_hex: String,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let fields = OrderFields {
+            position_id,
+            base_asset_id_hex,
+            base_amount,
+            quote_asset_id_hex,
+            quote_amount,
+            fee_asset_id_hex,
+            fee_amount,
+            expiration,
+            salt,
+            user_public_key_hex,
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let priv_key = str_to_field_element(&priv_key_hex)?;
+        let user_key = fields.user_public_key()?;
+        if fetch_public_key(&priv_key) != user_key {
+            return Err("Private key does not match user_public_key".to_string());
+        }
+        let order = fields.to_order()?;
+        let message = order.message_hash(&domain, user_key).unwrap();
+        let signature = sign_message(&message, &priv_key)
+            .map_err(|e| format!("Signing operation failed: {}", e))?;
+        let payload = serde_json::json!({
+            "position_id": order.position_id.value.to_string(),
+            "base_asset_id": order.base_asset_id.value.to_output_hex(),
+            "base_amount": order.base_amount.to_string(),
+            "quote_asset_id": order.quote_asset_id.value.to_output_hex(),
+            "quote_amount": order.quote_amount.to_string(),
+            "fee_asset_id": order.fee_asset_id.value.to_output_hex(),
+            "fee_amount": order.fee_amount.to_string(),
+            "expiration": order.expiration.seconds.to_string(),
+            "salt": order.salt.to_string(),
+            "user_public_key": user_key.to_output_hex(),
+            "message_hash": message.to_output_hex(),
+            "signature": {
+                "r": signature.r.to_output_hex(),
+                "s": signature.s.to_output_hex(),
+            },
+        });
+        Ok(payload.to_string())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Replaces the order's expiration, then hashes and signs it in one call.
+/// The expiration sits in the middle of the Poseidon sponge input, so there is
+/// no shortcut: this is a full recompute of the message hash.
+/// Returns `(message_hash, r, s)`.
+#[pyfunction]
+fn rs_reexpire_order(
+    py: Python,
+    order: OrderFields,
+    new_expiration: DecimalArg,
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+    priv_key_hex: String,
+) -> PyResult<(String, String, String)> {
+    py.allow_threads(move || -> Result<(String, String, String), String> {
+        let order = OrderFields {
+            expiration: new_expiration,
+            ..order
+        };
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let priv_key = str_to_field_element(&priv_key_hex)?;
+        let message = order.message_hash(&domain)?;
+        let signature = sign_message(&message, &priv_key)
+            .map_err(|e| format!("Signing operation failed: {}", e))?;
+        Ok((
+            message.to_output_hex(),
+            signature.r.to_output_hex(),
+            signature.s.to_output_hex(),
+        ))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Re-signs orders for a domain migration, e.g. a chain id change. Each order
+/// is hashed under both domains and signed under the new one with the private
+/// key at the same index, giving `(old_hash, new_hash, r, s)` per order in
+/// input order. Domains are `(name, version, chain_id, revision)` tuples.
+/// With `timeout_ms`, raises `TimeoutError` once the deadline passes.
+#[pyfunction]
+#[pyo3(signature = (orders, old_domain, new_domain, priv_keys, timeout_ms = None))]
+fn rs_migrate_orders(
+    py: Python,
+    orders: &PyList,
+    old_domain: (String, String, String, DecimalArg),
+    new_domain: (String, String, String, DecimalArg),
+    priv_keys: &PyList,
+    timeout_ms: Option<u64>,
+) -> PyResult<Vec<(String, String, String, String)>> {
+    let orders: Vec<OrderFields> = extract_batch("orders", orders)?;
+    let priv_keys: Vec<String> = extract_batch("priv_keys", priv_keys)?;
+    py.allow_threads(
+        move || -> Result<Vec<(String, String, String, String)>, BatchError> {
+            if priv_keys.len() != orders.len() {
+                return Err(format!(
+                    "Got {} orders but {} private keys",
+                    orders.len(),
+                    priv_keys.len()
+                )
+                .into());
+            }
+            let (name, version, chain_id, revision) = old_domain;
+            let old_domain = to_domain(name, version, chain_id, &revision.0)?;
+            let (name, version, chain_id, revision) = new_domain;
+            let new_domain = to_domain(name, version, chain_id, &revision.0)?;
+            par_map_with_deadline(&orders, timeout_ms, |index, order| {
+                let migrate = || -> Result<(String, String, String, String), String> {
+                    let old_hash = order.message_hash(&old_domain)?;
+                    let new_hash = order.message_hash(&new_domain)?;
+                    let priv_key = str_to_field_element(&priv_keys[index])?;
+                    let signature = sign_message(&new_hash, &priv_key)
+                        .map_err(|e| format!("Signing operation failed: {}", e))?;
+                    Ok((
+                        old_hash.to_output_hex(),
+                        new_hash.to_output_hex(),
+                        signature.r.to_output_hex(),
+                        signature.s.to_output_hex(),
+                    ))
+                };
+                migrate().map_err(|e| format!("Order {}: {}", index, e))
+            })
+        },
+    )
+    .map_err(PyErr::from)
+}
+
+/// Hashes the order from its raw fields and verifies `(r, s)` against its
+/// `user_public_key`, so the hash checked is the one this crate computes.
+/// Returns `(valid, message_hash)`, the hash being there for logging.
+#[pyfunction]
+fn rs_verify_order_signature(
+    py: Python,
+    order: OrderFields,
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+    r_hex: HexArg,
+    s_hex: HexArg,
+) -> PyResult<(bool, String)> {
+    py.allow_threads(move || -> Result<(bool, String), String> {
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message = order.message_hash(&domain)?;
+        let public_key = order.user_public_key()?;
+        let r = r_hex.parse("r")?;
+        let s = s_hex.parse("s")?;
+        let valid = verify_signature(&public_key, &message, &r, &s).unwrap_or(false);
+        Ok((valid, message.to_output_hex()))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Computes a fingerprint of the order for deduplication that doesn't depend
+/// on how its fields were written: hex or decimal, padded or not, every field
+/// is first parsed to its felt. The domain isn't part of it.
+#[pyfunction]
+fn rs_order_fingerprint(order: OrderFields) -> PyResult<String> {
+    let fingerprint = || -> Result<Felt, String> {
+        let user_public_key = order.user_public_key()?;
+        Ok(order.to_order()?.fingerprint(user_public_key))
+    };
+    fingerprint()
+        .map(|fingerprint| fingerprint.to_output_hex())
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Like `rs_verify_order_signature`, but verifies against `pub_key_hex` a
+/// signature given as one `0x`-prefixed blob of 128 hex digits, r then s, the
+/// way API payloads carry it. Raises for a blob of any other shape.
+#[pyfunction]
+fn rs_verify_order_concat_sig(
+    py: Python,
+    order: OrderFields,
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+    pub_key_hex: HexArg,
+    sig_concat: String,
+) -> PyResult<bool> {
+    py.allow_threads(move || -> Result<bool, String> {
+        let (r, s) = split_concat_signature(&sig_concat)?;
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        let message = order.message_hash(&domain)?;
+        let public_key = pub_key_hex.parse("pub_key")?;
+        Ok(verify_signature(&public_key, &message, &r, &s).unwrap_or(false))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Reconciles `(order, public_key, r, s)` records in parallel: each order is
+/// hashed from its fields and `(r, s)` verified against the record's
+/// `public_key`, giving one bool per record in input order. Malformed
+/// signatures or keys count as invalid, while an order that can't be hashed
+/// raises. With `timeout_ms`, raises `TimeoutError` once the deadline passes.
+#[pyfunction]
+#[pyo3(signature = (
+    records,
+    domain_name,
+    domain_version,
+    domain_chain_id,
+    domain_revision,
+    timeout_ms = None,
+))]
+fn rs_verify_order_batch(
+    py: Python,
+    records: &PyList,
+    domain_name: String,
+    domain_version: String,
+    domain_chain_id: String,
+    domain_revision: DecimalArg,
+    timeout_ms: Option<u64>,
+) -> PyResult<Vec<bool>> {
+    let records: Vec<(OrderFields, HexArg, HexArg, HexArg)> = extract_batch("records", records)?;
+    py.allow_threads(move || -> Result<Vec<bool>, BatchError> {
+        let domain = to_domain(
+            domain_name,
+            domain_version,
+            domain_chain_id,
+            &domain_revision.0,
+        )?;
+        par_map_with_deadline(
+            &records,
+            timeout_ms,
+            |index, (order, public_key_hex, r_hex, s_hex)| {
+                let message = order
+                    .message_hash(&domain)
+                    .map_err(|e| format!("Record {}: {}", index, e))?;
+                Ok(is_valid_signature(
+                    &public_key_hex.0,
+                    &message.to_hex_string(),
+                    &r_hex.0,
+                    &s_hex.0,
+                ))
+            },
+        )
+    })
+    .map_err(PyErr::from)
+}
+
+/// Packs a signed order into a fixed-length binary layout for the internal
+/// message bus: felts as 32-byte big-endian, amounts and timestamps as 8-byte
+/// big-endian ints. `rs_signed_order_from_bytes` reverses it.
+#[pyfunction]
+fn rs_signed_order_to_bytes<'py>(
+    py: Python<'py>,
+    order: OrderFields,
+    message_hash_hex: HexArg,
+    r_hex: HexArg,
+    s_hex: HexArg,
+) -> PyResult<&'py PyBytes> {
+    let bytes = py
+        .allow_threads(move || -> Result<Vec<u8>, String> {
+            let signed_order = SignedOrder {
+                order: order.to_order()?,
+                user_public_key: order.user_public_key()?,
+                message_hash: message_hash_hex.parse("message_hash")?,
+                r: r_hex.parse("r")?,
+                s: s_hex.parse("s")?,
+            };
+            Ok(signed_order.to_bytes())
+        })
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+    Ok(PyBytes::new(py, &bytes))
+}
+
+/// Unpacks the output of `rs_signed_order_to_bytes` into
+/// `(order, message_hash, r, s)`, with the order as a dict keyed like the
+/// batch functions expect. Raises on a wrong length or non-canonical felt.
+#[pyfunction]
+fn rs_signed_order_from_bytes(
+    py: Python,
+    data: &[u8],
+) -> PyResult<(PyObject, String, String, String)> {
+    let signed_order =
+        SignedOrder::from_bytes(data).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+    let order = &signed_order.order;
+    let fields = [
+        ("position_id", order.position_id.value.to_string()),
+        ("base_asset_id", order.base_asset_id.value.to_output_hex()),
+        ("base_amount", order.base_amount.to_string()),
+        ("quote_asset_id", order.quote_asset_id.value.to_output_hex()),
+        ("quote_amount", order.quote_amount.to_string()),
+        ("fee_asset_id", order.fee_asset_id.value.to_output_hex()),
+        ("fee_amount", order.fee_amount.to_string()),
+        ("expiration", order.expiration.seconds.to_string()),
+        ("salt", order.salt.to_string()),
+        (
+            "user_public_key",
+            signed_order.user_public_key.to_output_hex(),
+        ),
+    ];
+    Ok((
+        fields.into_py_dict(py).into(),
+        signed_order.message_hash.to_output_hex(),
+        signed_order.r.to_output_hex(),
+        signed_order.s.to_output_hex(),
+    ))
+}
+
+fn parse_felts(field: &str, values: &[HexArg]) -> Result<Vec<Felt>, String> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| value.parse(&format!("{}[{}]", field, index)))
+        .collect()
+}
+
+/// Computes the address an account of `class_hash` deploys to under `salt`.
+/// `constructor_calldata` is hashed into the address, so it must match what
+/// the class's constructor takes; many accounts take just the public key.
+#[pyfunction]
+#[pyo3(signature = (class_hash, salt, constructor_calldata, deployer_address = HexArg("0x0".to_string())))]
+fn rs_compute_account_address(
+    py: Python,
+    class_hash: HexArg,
+    salt: HexArg,
+    constructor_calldata: Vec<HexArg>,
+    deployer_address: HexArg,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let address = compute_account_address(
+            class_hash.parse("class_hash")?,
+            salt.parse("salt")?,
+            &parse_felts("constructor_calldata", &constructor_calldata)?,
+            deployer_address.parse("deployer_address")?,
+        );
+        Ok(address.to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Computes the selector calldata uses to call the entrypoint `name`, e.g.
+/// `__execute__` or `transfer`.
+#[pyfunction]
+fn rs_get_selector_from_name(name: &str) -> PyResult<String> {
+    entrypoint_selector(name)
+        .map(|selector| selector.to_output_hex())
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Computes the hash of a multicall's `__execute__` calldata, given as
+/// `(to, selector, calldata)` calls, the same way the calldata enters the
+/// invoke transaction hash.
+#[pyfunction]
+fn rs_compute_multicall_hash(
+    py: Python,
+    calls: Vec<(HexArg, HexArg, Vec<HexArg>)>,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let calls = calls
+            .iter()
+            .enumerate()
+            .map(|(index, (to, selector, calldata))| {
+                Ok(Call {
+                    to: to.parse(&format!("calls[{}].to", index))?,
+                    selector: selector.parse(&format!("calls[{}].selector", index))?,
+                    calldata: parse_felts(&format!("calls[{}].calldata", index), calldata)?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(multicall_hash(&calls).to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Computes the hash of a v3 invoke transaction, ready to be signed.
+/// `chain_id` is the chain's short string name, e.g. "SN_SEPOLIA".
+#[pyfunction]
+#[pyo3(signature = (
+    sender_address,
+    calldata,
+    nonce,
+    tip,
+    l1_gas_max_amount,
+    l1_gas_max_price_per_unit,
+    l2_gas_max_amount,
+    l2_gas_max_price_per_unit,
+    l1_data_gas_max_amount,
+    l1_data_gas_max_price_per_unit,
+    chain_id,
+    paymaster_data = vec![],
+    account_deployment_data = vec![],
+    nonce_data_availability_mode = 0,
+    fee_data_availability_mode = 0,
+))]
+fn rs_compute_invoke_tx_hash_v3(
+    py: Python,
+    sender_address: HexArg,
+    calldata: Vec<HexArg>,
+    nonce: HexArg,
+    tip: DecimalArg,
+    l1_gas_max_amount: DecimalArg,
+    l1_gas_max_price_per_unit: DecimalArg,
+    l2_gas_max_amount: DecimalArg,
+    l2_gas_max_price_per_unit: DecimalArg,
+    l1_data_gas_max_amount: DecimalArg,
+    l1_data_gas_max_price_per_unit: DecimalArg,
+    chain_id: String,
+    paymaster_data: Vec<HexArg>,
+    account_deployment_data: Vec<HexArg>,
+    nonce_data_availability_mode: u32,
+    fee_data_availability_mode: u32,
+) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let transaction = InvokeTransactionV3 {
+            sender_address: sender_address.parse("sender_address")?,
+            calldata: parse_felts("calldata", &calldata)?,
+            nonce: nonce.parse("nonce")?,
+            tip: tip.parse("tip")?,
+            l1_gas: ResourceBounds {
+                max_amount: l1_gas_max_amount.parse("l1_gas_max_amount")?,
+                max_price_per_unit: l1_gas_max_price_per_unit.parse("l1_gas_max_price_per_unit")?,
+            },
+            l2_gas: ResourceBounds {
+                max_amount: l2_gas_max_amount.parse("l2_gas_max_amount")?,
+                max_price_per_unit: l2_gas_max_price_per_unit.parse("l2_gas_max_price_per_unit")?,
+            },
+            l1_data_gas: ResourceBounds {
+                max_amount: l1_data_gas_max_amount.parse("l1_data_gas_max_amount")?,
+                max_price_per_unit: l1_data_gas_max_price_per_unit
+                    .parse("l1_data_gas_max_price_per_unit")?,
+            },
+            paymaster_data: parse_felts("paymaster_data", &paymaster_data)?,
+            account_deployment_data: parse_felts(
+                "account_deployment_data",
+                &account_deployment_data,
+            )?,
+            nonce_data_availability_mode,
+            fee_data_availability_mode,
+            chain_id: encode_short_string(&chain_id)?,
+        };
+        Ok(transaction.hash().to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Commits to a position id with a blinding factor, returning the commitment
+/// as hex. Reveal both later and check with `rs_verify_position_commitment`.
+#[pyfunction]
+fn rs_commit_position(position_id: DecimalArg, blinding_hex: HexArg) -> PyResult<String> {
+    let commitment = || -> Result<Felt, String> {
+        let position_id = position_id.parse::<u32>("position_id")?;
+        let blinding = blinding_hex.parse_canonical("blinding")?;
+        Ok(position_commitment(position_id, blinding))
+    };
+    commitment()
+        .map(|commitment| commitment.to_output_hex())
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Checks a revealed position id and blinding factor against a commitment
+/// from `rs_commit_position`, comparing in constant time.
+#[pyfunction]
+fn rs_verify_position_commitment(
+    position_id: DecimalArg,
+    blinding_hex: HexArg,
+    commitment_hex: HexArg,
+) -> PyResult<bool> {
+    let verify = || -> Result<bool, String> {
+        let position_id = position_id.parse::<u32>("position_id")?;
+        let blinding = blinding_hex.parse_canonical("blinding")?;
+        let commitment = commitment_hex.parse("commitment")?;
+        Ok(verify_position_commitment(
+            position_id,
+            blinding,
+            commitment,
+        ))
+    };
+    verify().map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Commits to a position's balance in one collateral asset with a blinding
+/// factor, returning the commitment as hex. `balance` must fit an `i64`.
+/// Reveal the balance and blinding later and check with
+/// `rs_verify_collateral_commitment`.
+#[pyfunction]
+fn rs_commit_collateral(
+    position_id: DecimalArg,
+    collateral_asset_hex: HexArg,
+    balance: DecimalArg,
+    blinding_hex: HexArg,
+) -> PyResult<String> {
+    let commitment = || -> Result<Felt, String> {
+        let position_id = position_id.parse::<u32>("position_id")?;
+        let collateral_asset_id = collateral_asset_hex.parse("collateral_asset")?;
+        let balance = balance.parse::<i64>("balance")?;
+        let blinding = blinding_hex.parse_canonical("blinding")?;
+        Ok(collateral_commitment(
+            position_id,
+            collateral_asset_id,
+            balance,
+            blinding,
+        ))
+    };
+    commitment()
+        .map(|commitment| commitment.to_output_hex())
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Checks a revealed collateral balance and blinding factor against a
+/// commitment from `rs_commit_collateral`, comparing in constant time.
+#[pyfunction]
+fn rs_verify_collateral_commitment(
+    position_id: DecimalArg,
+    collateral_asset_hex: HexArg,
+    balance: DecimalArg,
+    blinding_hex: HexArg,
+    commitment_hex: HexArg,
+) -> PyResult<bool> {
+    let verify = || -> Result<bool, String> {
+        let position_id = position_id.parse::<u32>("position_id")?;
+        let collateral_asset_id = collateral_asset_hex.parse("collateral_asset")?;
+        let balance = balance.parse::<i64>("balance")?;
+        let blinding = blinding_hex.parse_canonical("blinding")?;
+        let commitment = commitment_hex.parse("commitment")?;
+        Ok(verify_collateral_commitment(
+            position_id,
+            collateral_asset_id,
+            balance,
+            blinding,
+            commitment,
+        ))
+    };
+    verify().map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Commits to a batch of orders for a sealed bid, returning
+/// `poseidon(batch_commitment(struct_hashes), blinding)` as hex. Only each
+/// order's struct hash goes in, so the commitment doesn't depend on the user
+/// public key or a domain. Reveal both later and check with
+/// `rs_open_order_batch_commitment`. With `timeout_ms`, raises `TimeoutError`
+/// once the deadline passes.
+#[pyfunction]
+#[pyo3(signature = (orders, blinding_hex, timeout_ms = None))]
+fn rs_commit_order_batch(
+    py: Python,
+    orders: &PyList,
+    blinding_hex: HexArg,
+    timeout_ms: Option<u64>,
+) -> PyResult<String> {
+    let orders: Vec<OrderFields> = extract_batch("orders", orders)?;
+    py.allow_threads(move || -> Result<String, BatchError> {
+        let blinding = blinding_hex.parse_canonical("blinding")?;
+        let hashes = order_struct_hashes(&orders, timeout_ms)?;
+        Ok(order_batch_commitment(&hashes, blinding).to_output_hex())
+    })
+    .map_err(PyErr::from)
+}
+
+/// Checks a revealed batch of orders and blinding factor against a commitment
+/// from `rs_commit_order_batch`, comparing in constant time. Orders that fail
+/// to parse still raise.
+#[pyfunction]
+#[pyo3(signature = (orders, blinding_hex, commitment_hex, timeout_ms = None))]
+fn rs_open_order_batch_commitment(
+    py: Python,
+    orders: &PyList,
+    blinding_hex: HexArg,
+    commitment_hex: HexArg,
+    timeout_ms: Option<u64>,
+) -> PyResult<bool> {
+    let orders: Vec<OrderFields> = extract_batch("orders", orders)?;
+    py.allow_threads(move || -> Result<bool, BatchError> {
+        let blinding = blinding_hex.parse_canonical("blinding")?;
+        let commitment = commitment_hex.parse("commitment")?;
+        let hashes = order_struct_hashes(&orders, timeout_ms)?;
+        Ok(verify_order_batch_commitment(&hashes, blinding, commitment))
+    })
+    .map_err(PyErr::from)
+}
+
+fn order_struct_hashes(
+    orders: &[OrderFields],
+    timeout_ms: Option<u64>,
+) -> Result<Vec<Felt>, BatchError> {
+    par_map_with_deadline(orders, timeout_ms, |index, order| {
+        order
+            .to_order()
+            .map(|order| order.hash())
+            .map_err(|e| format!("Order {}: {}", index, e))
+    })
+}
+
+/// Derives the read-only viewing key for a private key, as hex. It is a
+/// one-way hash, so it can only be checked by recomputing it from the private
+/// key; there is no way to verify it against the public key.
+#[pyfunction]
+fn rs_derive_viewing_key(py: Python, priv_key_hex: String) -> PyResult<String> {
+    py.allow_threads(move || -> Result<String, String> {
+        let private_key = str_to_field_element(&priv_key_hex)?;
+        Ok(derive_viewing_key(&private_key).to_output_hex())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Derives the keypair of subaccount `index` from a master private key, as
+/// `(private_key, public_key)` hex. The same master key and index always give
+/// the same keypair, and a subaccount's private key can't be used to recover
+/// the master key.
+#[pyfunction]
+fn rs_derive_subaccount_key(
+    py: Python,
+    master_priv_hex: String,
+    index: u64,
+) -> PyResult<(String, String)> {
+    py.allow_threads(move || -> Result<(String, String), String> {
+        let master_key = str_to_field_element(&master_priv_hex)?;
+        let private_key = derive_subaccount_key(&master_key, index)?;
+        let public_key = fetch_public_key(&private_key);
+        Ok((private_key.to_output_hex(), public_key.to_output_hex()))
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Returns the first `count` keypairs of a fixed table of test keys, as
+/// `(private_key, public_key)` hex pairs. Entry `i` is always the same key,
+/// whatever `count` is, so suites can share accounts by index. The keys are
+/// public knowledge; never fund them.
+#[pyfunction]
+fn rs_test_keypairs(py: Python, count: usize) -> PyResult<Vec<(String, String)>> {
+    py.allow_threads(move || -> Result<Vec<(String, String)>, String> {
+        check_batch_size("count", count)?;
+        Ok((0..count as u64)
+            .into_par_iter()
+            .map(|index| {
+                let private_key = test_private_key(index);
+                let public_key = fetch_public_key(&private_key);
+                (private_key.to_output_hex(), public_key.to_output_hex())
+            })
+            .collect())
+    })
+    .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Generates a random keypair from the operating system's CSPRNG.
+#[pyfunction]
+fn rs_generate_keypair(py: Python) -> (String, String) {
+    py.allow_threads(|| {
+        let private_key = generate_private_key();
+        let public_key = fetch_public_key(&private_key);
+        (private_key.to_output_hex(), public_key.to_output_hex())
+    })
+}
+
+/// Generates an order salt with `timestamp`, in seconds, in its high 32 bits
+/// and random low bits, so ops tooling can age out stale orders from the salt
+/// alone. Raises if `timestamp` doesn't fit in 32 bits.
+#[pyfunction]
+fn rs_generate_timestamped_salt(timestamp: u64) -> PyResult<u64> {
+    let timestamp = u32::try_from(timestamp).map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "timestamp {} doesn't fit in 32 bits",
+            timestamp
+        ))
+    })?;
+    Ok(generate_timestamped_salt(timestamp))
+}
+
+/// Reads back the timestamp of a salt from `rs_generate_timestamped_salt`.
+#[pyfunction]
+fn rs_salt_timestamp(salt: u64) -> u64 {
+    salt_timestamp(salt).into()
+}
+
+#[pyfunction]
+fn rs_generate_keypair_from_eth_signature(
+    _py: Python,
+    signature: String,
+) -> PyResult<(String, String)> {
+    return get_private_key_from_eth_signature(&signature)
+        .and_then(|private_key| {
+            let public_key = fetch_public_key(&private_key);
+            let private_key_hex = private_key.to_output_hex();
+            let public_key_hex = public_key.to_output_hex();
+            Ok((private_key_hex, public_key_hex))
+        })
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>);
+}
+
+/// Like `rs_generate_keypair_from_eth_signature`, but raises unless the
+/// signature over `message_hash` recovers to `expected_address`.
+#[pyfunction]
+fn rs_generate_keypair_from_eth_signature_checked(
+    py: Python,
+    signature: String,
+    message_hash: String,
+    expected_address: String,
+) -> PyResult<(String, String)> {
+    py.allow_threads(move || {
+        get_private_key_from_eth_signature_checked(&signature, &message_hash, &expected_address)
+            .map(|private_key| {
+                let public_key = fetch_public_key(&private_key);
+                (private_key.to_output_hex(), public_key.to_output_hex())
+            })
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+    })
+}
+
+#[pymodule]
+fn fast_stark_crypto(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(rs_set_strict_hex, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_set_hex_output_mode, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_configure_asset_encoding, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_felt_eq, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_felt_cmp, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_felt_ct_eq, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_is_valid_felt, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_felt_byte_length, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_public_key, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_public_key_x, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_public_key_parity, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_compressed_public_keys, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_public_keys_map, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_ec_generator, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_ec_mul, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_ec_add, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_compute_pedersen_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_pedersen_with_zero, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_hash2, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_pedersen_verify_proof, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_hash_config_fingerprint, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_message, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_message_checked, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_message_with_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_message_padded, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_register_key, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_with_handle, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_drop_key, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_with_key_shares, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_message_with_external_nonce, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_pairs, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_pairs_with_progress, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_file, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sign_for_invoke, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_signature_to_calldata, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_signature_int, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_hash_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_signature_detailed, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_signature_canonical, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_signature_timing_safe, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_signature_r_on_curve, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_signature_range_check, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_signature_strict_encoding, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_invalid_signature_indices, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_any, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_multisig, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_order_msg, m)?)?;
+    #[cfg(feature = "testing")]
+    m.add_function(wrap_pyfunction!(
+        rs_get_order_msg_with_revision_override,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(rs_get_order_msg_sided, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_order_msg_scaled, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_order_msg_int, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_order_msg_preparsed, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_order_field_sensitivity, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_order_msg_fee_rate, m)?)?;
+    #[cfg(feature = "draft-layouts")]
+    m.add_function(wrap_pyfunction!(rs_get_order_msg_with_fees, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_asset_id_from_int, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_asset_id_to_int, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_order_hashes, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_hash_order_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_validate_order_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_sum_order_notionals, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_reexpire_order, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_migrate_orders, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_order_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_order_concat_sig, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_order_fingerprint, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_order_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_signed_order_to_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_signed_order_from_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_build_signed_order_payload, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_compute_domain_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_encode_short_string, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_decode_short_string, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_transfer_msg, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_transfer_msg_int, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_order_field_names, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_transfer_field_names, m)?)?;
+    #[cfg(feature = "draft-layouts")]
+    m.add_function(wrap_pyfunction!(rs_get_withdrawal_to_address_msg, m)?)?;
+    #[cfg(feature = "draft-layouts")]
+    m.add_function(wrap_pyfunction!(rs_get_cancel_all_msg, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_hash_message, m)?)?;
+    #[cfg(feature = "draft-layouts")]
+    m.add_function(wrap_pyfunction!(rs_get_settlement_msg, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_orders_matchable, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_orders_mismatch_reason, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_commit_position, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_position_commitment, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_commit_collateral, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_verify_collateral_commitment, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_commit_order_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_open_order_batch_commitment, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_derive_viewing_key, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_derive_subaccount_key, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_eip712_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_generate_keypair, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_generate_timestamped_salt, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_salt_timestamp, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_test_keypairs, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_generate_keypair_from_eth_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        rs_generate_keypair_from_eth_signature_checked,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(rs_compute_multicall_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_compute_invoke_tx_hash_v3, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_compute_account_address, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_get_selector_from_name, m)?)?;
+    m.add_function(wrap_pyfunction!(rs_set_max_batch_size, m)?)?;
+    m.add_class::<VerifyResult>()?;
+    m.add_class::<FeltValue>()?;
+    m.add("SigningError", py.get_type::<SigningError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use pyo3::types::PyLong;
+    use pyo3::types::PyTuple;
+
+    use super::*;
+
+    #[test]
+    fn test_format_hex() {
+        let mode = |name: &str| {
+            HEX_OUTPUT_MODES
+                .iter()
+                .position(|known| *known == name)
+                .unwrap() as u8
+        };
+        let ten = Felt::from(10u64);
+        let two_fifty_six = Felt::from(256u64);
+        assert_eq!(format_hex(&ten, mode("minimal")), "0xa");
+        assert_eq!(format_hex(&two_fifty_six, mode("minimal")), "0x100");
+        assert_eq!(format_hex(&Felt::ZERO, mode("minimal")), "0x0");
+        assert_eq!(format_hex(&ten, mode("even")), "0x0a");
+        assert_eq!(format_hex(&two_fifty_six, mode("even")), "0x0100");
+        assert_eq!(format_hex(&Felt::from(0x1234u64), mode("even")), "0x1234");
+        assert_eq!(format_hex(&Felt::ZERO, mode("even")), "0x00");
+        assert_eq!(
+            format_hex(&ten, mode("padded64")),
+            format!("0x{:0>64}", "a")
+        );
+    }
+
+    #[test]
+    fn test_rs_set_hex_output_mode() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let set_hex_output_mode = module.getattr("rs_set_hex_output_mode").unwrap();
+            // Other tests run concurrently and expect minimal hex, so only
+            // the default mode is set here
+            set_hex_output_mode.call1(("minimal",)).unwrap();
+            let err = set_hex_output_mode.call1(("upper",)).unwrap_err();
+            assert!(err.to_string().contains("padded64"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_check_canonical_hex() {
+        assert!(check_canonical_hex("0x1a2b").is_ok());
+        assert!(check_canonical_hex("0x0").is_ok());
+        assert!(check_canonical_hex("0x1A2B").is_err());
+        assert!(check_canonical_hex("0X1a2b").is_err());
+        assert!(check_canonical_hex("1a2b").is_err());
+        assert!(check_canonical_hex("0x").is_err());
+    }
+
+    // Strict mode is process-wide and most tests pass non-canonical hex, so
+    // the end-to-end check runs alone in a child process
+    #[test]
+    fn test_rs_set_strict_hex() {
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["tests::strict_hex_in_own_process", "--exact", "--ignored"])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "{}", stdout);
+        assert!(stdout.contains("1 passed"), "{}", stdout);
+    }
+
+    #[test]
+    #[ignore = "enables strict hex for the whole process; run by test_rs_set_strict_hex"]
+    fn strict_hex_in_own_process() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let felt_eq = module.getattr("rs_felt_eq").unwrap();
+            let verify = module.getattr("rs_verify_order_concat_sig").unwrap();
+            let order = [
+                ("position_id", "100"),
+                ("base_asset_id", "0x2"),
+                ("base_amount", "100"),
+                ("quote_asset_id", "0x1"),
+                ("quote_amount", "-156"),
+                ("fee_asset_id", "0x1"),
+                ("fee_amount", "74"),
+                ("expiration", "100"),
+                ("salt", "123"),
+                (
+                    "user_public_key",
+                    "0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43",
+                ),
+            ]
+            .into_py_dict(py);
+            let verify_concat = |sig_concat: String| {
+                verify.call1((
+                    order,
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                    "0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43",
+                    sig_concat,
+                ))
+            };
+            module
+                .getattr("rs_set_strict_hex")
+                .unwrap()
+                .call1((true,))
+                .unwrap();
+
+            let equal: bool = felt_eq.call1(("0xa", "0xa")).unwrap().extract().unwrap();
+            assert!(equal);
+            for value in ["0xA", "0XA", "10"] {
+                let err = felt_eq.call1((value, "0xa")).unwrap_err();
+                assert!(err.to_string().contains(value), "{}", err);
+            }
+
+            let lowercase = format!("0x{}{}", "ab".repeat(32), "12".repeat(32));
+            assert!(verify_concat(lowercase).is_ok());
+            let err =
+                verify_concat(format!("0x{}{}", "AB".repeat(32), "12".repeat(32))).unwrap_err();
+            assert!(err.to_string().contains("Invalid r"), "{}", err);
+            let err =
+                verify_concat(format!("0x{}{}", "ab".repeat(32), "CD".repeat(32))).unwrap_err();
+            assert!(err.to_string().contains("Invalid s"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_configure_asset_encoding_rejects_out_of_field() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let configure = module.getattr("rs_configure_asset_encoding").unwrap();
+            // Only invalid layouts here: a valid one would change the hashes
+            // other tests compute in parallel
+            assert!(configure.call1((8,)).is_err());
+            assert!(configure.call1((200, "0xffffffffffffffff")).is_err());
+            assert!(ASSET_LAYOUT.read().unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn test_rs_felt_eq_and_cmp() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let felt_eq = module.getattr("rs_felt_eq").unwrap();
+            let felt_cmp = module.getattr("rs_felt_cmp").unwrap();
+            let eq =
+                |a: &str, b: &str| -> bool { felt_eq.call1((a, b)).unwrap().extract().unwrap() };
+            let cmp =
+                |a: &str, b: &str| -> i8 { felt_cmp.call1((a, b)).unwrap().extract().unwrap() };
+
+            assert!(eq("0x0A", "0xa"));
+            assert!(eq("0xa", "10"));
+            assert!(!eq("0xa", "0xb"));
+            assert_eq!(cmp("9", "0xa"), -1);
+            assert_eq!(cmp("0x00a", "10"), 0);
+            assert_eq!(cmp("0x100", "255"), 1);
+            assert!(felt_eq.call1(("0xa", "ten")).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_felt_ct_eq() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let felt_ct_eq = module.getattr("rs_felt_ct_eq").unwrap();
+            let eq =
+                |a: &str, b: &str| -> bool { felt_ct_eq.call1((a, b)).unwrap().extract().unwrap() };
+
+            assert!(eq("0x0A", "0xa"));
+            assert!(!eq("0xa", "0xb"));
+            assert!(felt_ct_eq.call1(("0xa", "not hex")).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_is_valid_felt() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let is_valid_felt = module.getattr("rs_is_valid_felt").unwrap();
+            let valid =
+                |value: &str| -> bool { is_valid_felt.call1((value,)).unwrap().extract().unwrap() };
+
+            assert!(valid("0x0"));
+            assert!(valid("0x00ABC"));
+            // The field prime minus one, then the prime itself
+            assert!(valid(
+                "0x800000000000011000000000000000000000000000000000000000000000000"
+            ));
+            assert!(!valid(
+                "0x800000000000011000000000000000000000000000000000000000000000001"
+            ));
+            assert!(!valid(
+                "0x10000000000000000000000000000000000000000000000000000000000000000"
+            ));
+            assert!(!valid("0xxyz"));
+            assert!(!valid("0x"));
+            assert!(!valid(""));
+        });
+    }
+
+    #[test]
+    fn test_rs_felt_byte_length() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let felt_byte_length = module.getattr("rs_felt_byte_length").unwrap();
+            let byte_length =
+                |hex: &str| -> usize { felt_byte_length.call1((hex,)).unwrap().extract().unwrap() };
+
+            assert_eq!(byte_length("0x0"), 1);
+            assert_eq!(byte_length("0x1"), 1);
+            assert_eq!(byte_length("0xff"), 1);
+            assert_eq!(byte_length("0x100"), 2);
+            assert_eq!(
+                byte_length("0x800000000000011000000000000000000000000000000000000000000000000"),
+                32
+            );
+            assert!(felt_byte_length.call1(("zz",)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_ec_mul_and_add() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let ec_mul = module.getattr("rs_ec_mul").unwrap();
+            let ec_add = module.getattr("rs_ec_add").unwrap();
+            let (gx, gy): (String, String) = module
+                .getattr("rs_ec_generator")
+                .unwrap()
+                .call0()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                (gx.as_str(), gy.as_str()),
+                (
+                    "0x1ef15c18599971b7beced415a40f0c7deacfd9b0d1819e03d723d8bc943cfca",
+                    "0x5668060aa49730b7be4801df46ec62de53ecd11abe43a32873000c36e8dc1f"
+                )
+            );
+
+            let (x, y): (String, String) =
+                ec_mul.call1((&gx, &gy, "0x2")).unwrap().extract().unwrap();
+            assert_eq!(
+                (x.as_str(), y.as_str()),
+                (
+                    "0x759ca09377679ecd535a81e83039658bf40959283187c654c5416f439403cf5",
+                    "0x6f524a3400e7708d5c01a28598ad272e7455aa88778b19f93b562d7a9646c41"
+                )
+            );
+            let sum: (String, String) =
+                ec_add.call1((&x, &y, &gx, &gy)).unwrap().extract().unwrap();
+            assert_eq!(
+                sum.0,
+                "0x411494b501a98abd8262b0da1351e17899a0c4ef23dd2f96fec5ba847310b20"
+            );
+
+            let err = ec_mul.call1((&gx, "0x1", "0x2")).unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            assert!(err.to_string().contains("not on the curve"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_get_compressed_public_keys() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let get_compressed_public_keys =
+                module.getattr("rs_get_compressed_public_keys").unwrap();
+
+            let keys: Vec<(String, u8)> = get_compressed_public_keys
+                .call1((vec![
+                    "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+                    "0x1",
+                ],))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                keys,
+                vec![
+                    (
+                        "0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43"
+                            .to_string(),
+                        0
+                    ),
+                    (
+                        "0x1ef15c18599971b7beced415a40f0c7deacfd9b0d1819e03d723d8bc943cfca"
+                            .to_string(),
+                        1
+                    ),
+                ]
+            );
+
+            let err = get_compressed_public_keys
+                .call1((vec!["0x1", "0x0"],))
+                .unwrap_err();
+            assert!(err.to_string().contains("private_keys[1]"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_get_public_keys_map() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let get_public_keys_map = module.getattr("rs_get_public_keys_map").unwrap();
+
+            let keys = [
+                (
+                    "zed",
+                    "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+                ),
+                ("alice", "0x1"),
+            ]
+            .into_py_dict(py);
+            let public_keys: Vec<(String, String)> = get_public_keys_map
+                .call1((keys,))
+                .unwrap()
+                .downcast::<PyDict>()
+                .unwrap()
+                .iter()
+                .map(|(name, public_key)| (name.extract().unwrap(), public_key.extract().unwrap()))
+                .collect();
+            assert_eq!(
+                public_keys,
+                vec![
+                    (
+                        "zed".to_string(),
+                        "0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43"
+                            .to_string()
+                    ),
+                    (
+                        "alice".to_string(),
+                        "0x1ef15c18599971b7beced415a40f0c7deacfd9b0d1819e03d723d8bc943cfca"
+                            .to_string()
+                    ),
+                ]
+            );
+
+            let keys = [("alice", "0x1"), ("bob", "0xzz")].into_py_dict(py);
+            let err = get_public_keys_map.call1((keys,)).unwrap_err();
+            assert!(err.to_string().contains("keys['bob']"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let position_id = "100".to_string();
+            let base_asset_id = "0x2".to_string();
+            let base_amount = "100".to_string();
+            let quote_asset_id = "0x1".to_string();
+            let quote_amount = "-156".to_string();
+            let fee_asset_id = "0x1".to_string();
+            let fee_amount = "74".to_string();
+            let expiration = "100".to_string();
+            let salt = "123".to_string();
+            let user_public_key =
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904".to_string();
+            let domain_name = "Perpetuals".to_string();
+            let domain_version = "v0".to_string();
+            let domain_chain_id = "SN_SEPOLIA".to_string();
+            let domain_revision = "1".to_string();
+            let result: String = module
+                .getattr("rs_get_order_msg")
+                .unwrap()
+                .call1(PyTuple::new(
+                    py,
+                    [
+                        position_id,
+                        base_asset_id,
+                        base_amount,
+                        quote_asset_id,
+                        quote_amount,
+                        fee_asset_id,
+                        fee_amount,
+                        expiration,
+                        salt,
+                        user_public_key,
+                        domain_name,
+                        domain_version,
+                        domain_chain_id,
+                        domain_revision,
+                    ],
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+
+            assert_eq!(
+                result,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+        });
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_rs_get_order_msg_with_revision_override() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let mut args = [
+                "100",
+                "0x2",
+                "100",
+                "0x1",
+                "-156",
+                "0x1",
+                "74",
+                "100",
+                "123",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "2",
+            ];
+            let get_order_msg = module
+                .getattr("rs_get_order_msg_with_revision_override")
+                .unwrap();
+
+            let result: String = get_order_msg
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x8f3a92352441715452214bff3dc30fa0957f8bb3b99226e106f50f32a417d5"
+            );
+            assert!(module
+                .getattr("rs_get_order_msg")
+                .unwrap()
+                .call1(PyTuple::new(py, args))
+                .is_err());
+
+            args[13] = "1";
+            let result: String = get_order_msg
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_reduce_only() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = PyTuple::new(
+                py,
+                [
+                    "100",
+                    "0x2",
+                    "100",
+                    "0x1",
+                    "-156",
+                    "0x1",
+                    "74",
+                    "100",
+                    "123",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ],
+            );
+            let get_order_msg = module.getattr("rs_get_order_msg").unwrap();
+            let call = |reduce_only: bool| {
+                let kwargs = [("reduce_only", reduce_only)].into_py_dict(py);
+                get_order_msg.call(args, Some(kwargs))
+            };
+
+            assert_eq!(
+                call(false).unwrap().extract::<String>().unwrap(),
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+            #[cfg(feature = "draft-layouts")]
+            assert_eq!(
+                call(true).unwrap().extract::<String>().unwrap(),
+                "0xc13326033fe01500b2f8dfad7553e635bb947f10da7c1c333aee9977c7d876"
+            );
+            #[cfg(not(feature = "draft-layouts"))]
+            {
+                let err = call(true).unwrap_err();
+                assert!(err.to_string().contains("draft-layouts"), "{}", err);
+            }
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_linked_to_parent() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = PyTuple::new(
+                py,
+                [
+                    "100",
+                    "0x2",
+                    "100",
+                    "0x1",
+                    "-156",
+                    "0x1",
+                    "74",
+                    "100",
+                    "123",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ],
+            );
+            let get_order_msg = module.getattr("rs_get_order_msg").unwrap();
+            let parent_order_hash =
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48";
+            let call = |reduce_only: bool| {
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("reduce_only", reduce_only).unwrap();
+                kwargs
+                    .set_item("parent_order_hash_hex", parent_order_hash)
+                    .unwrap();
+                get_order_msg.call(args, Some(kwargs))
+            };
+
+            #[cfg(feature = "draft-layouts")]
+            {
+                let hash =
+                    |reduce_only: bool| -> String { call(reduce_only).unwrap().extract().unwrap() };
+                assert_eq!(
+                    hash(false),
+                    "0x281de6b91262ec3c040765519e64a5e701caddebdc9e0878cd707fab22eb7c"
+                );
+                assert_eq!(
+                    hash(true),
+                    "0x45e75dd8f7f76db0f53f86660e86ef68584f8397b374baff84f366867bb5e40"
+                );
+            }
+            #[cfg(not(feature = "draft-layouts"))]
+            {
+                let err = call(false).unwrap_err();
+                assert!(err.to_string().contains("draft-layouts"), "{}", err);
+            }
+
+            // A hash at or above the field prime isn't a felt identity
+            let kwargs = [(
+                "parent_order_hash_hex",
+                "0x800000000000011000000000000000000000000000000000000000000000001",
+            )]
+            .into_py_dict(py);
+            let err = get_order_msg.call(args, Some(kwargs)).unwrap_err();
+            assert!(
+                err.to_string().contains("field `parent_order_hash`"),
+                "{}",
+                err
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_audit_tag() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = PyTuple::new(
+                py,
+                [
+                    "100",
+                    "0x2",
+                    "100",
+                    "0x1",
+                    "-156",
+                    "0x1",
+                    "74",
+                    "100",
+                    "123",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ],
+            );
+            let get_order_msg = module.getattr("rs_get_order_msg").unwrap();
+            #[cfg(feature = "draft-layouts")]
+            {
+                let hash = |parent_order_hash: Option<&str>| -> String {
+                    let kwargs = PyDict::new(py);
+                    kwargs.set_item("audit_tag_hex", "0xde5c").unwrap();
+                    kwargs
+                        .set_item("parent_order_hash_hex", parent_order_hash)
+                        .unwrap();
+                    get_order_msg
+                        .call(args, Some(kwargs))
+                        .unwrap()
+                        .extract()
+                        .unwrap()
+                };
+
+                assert_eq!(
+                    hash(None),
+                    "0x1e428d7a29fdcb7e9843ee806be4e28f4656a55b8ddd00a5fc695924bdb1480"
+                );
+                assert_eq!(
+                    hash(Some(
+                        "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+                    )),
+                    "0x5532f1f66a045c311e5c7aa0d5e34a924abf40486712627d576aa9c957c3bdb"
+                );
+
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("audit_tag_hex", "desk-7").unwrap();
+                let err = get_order_msg.call(args, Some(kwargs)).unwrap_err();
+                assert!(err.to_string().contains("audit_tag"), "{}", err);
+                kwargs
+                    .set_item(
+                        "audit_tag_hex",
+                        "0x800000000000011000000000000000000000000000000000000000000000001",
+                    )
+                    .unwrap();
+                let err = get_order_msg.call(args, Some(kwargs)).unwrap_err();
+                assert!(err.to_string().contains("field `audit_tag`"), "{}", err);
+            }
+            #[cfg(not(feature = "draft-layouts"))]
+            {
+                let kwargs = [("audit_tag_hex", "0xde5c")].into_py_dict(py);
+                let err = get_order_msg.call(args, Some(kwargs)).unwrap_err();
+                assert!(err.to_string().contains("draft-layouts"), "{}", err);
+            }
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_post_only() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = PyTuple::new(
+                py,
+                [
+                    "100",
+                    "0x2",
+                    "100",
+                    "0x1",
+                    "-156",
+                    "0x1",
+                    "74",
+                    "100",
+                    "123",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ],
+            );
+            let get_order_msg = module.getattr("rs_get_order_msg").unwrap();
+            let call = |post_only: bool, reduce_only: bool, parent: Option<&str>| {
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("post_only", post_only).unwrap();
+                kwargs.set_item("reduce_only", reduce_only).unwrap();
+                kwargs.set_item("parent_order_hash_hex", parent).unwrap();
+                get_order_msg.call(args, Some(kwargs))
+            };
+            let hash = |post_only: bool, reduce_only: bool, parent: Option<&str>| -> String {
+                call(post_only, reduce_only, parent)
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            };
+
+            assert_eq!(
+                hash(false, false, None),
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+            #[cfg(feature = "draft-layouts")]
+            {
+                assert_eq!(
+                    hash(true, false, None),
+                    "0x279cb30becc85383ebeebe48f118be6eb794462293849d815c1a053a56db580"
+                );
+                assert_eq!(
+                    hash(true, true, None),
+                    "0x5d5338ed6a913b19304b6aab3f18cf718e8204073274d12e55fbd333a77754b"
+                );
+                let parent = "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48";
+                assert_eq!(
+                    hash(true, false, Some(parent)),
+                    "0x28b521c9cd8ba8852de889953b24ebaa48cb6aa186c36947f63c552efbac4e6"
+                );
+            }
+            #[cfg(not(feature = "draft-layouts"))]
+            {
+                let err = call(true, false, None).unwrap_err();
+                assert!(err.to_string().contains("draft-layouts"), "{}", err);
+            }
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_leverage() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = PyTuple::new(
+                py,
+                [
+                    "100",
+                    "0x2",
+                    "100",
+                    "0x1",
+                    "-156",
+                    "0x1",
+                    "74",
+                    "100",
+                    "123",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ],
+            );
+            let get_order_msg = module.getattr("rs_get_order_msg").unwrap();
+            let call = |leverage: Option<i64>, flags: bool, parent: Option<&str>| {
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("leverage", leverage).unwrap();
+                kwargs.set_item("post_only", flags).unwrap();
+                kwargs.set_item("reduce_only", flags).unwrap();
+                kwargs.set_item("parent_order_hash_hex", parent).unwrap();
+                get_order_msg.call(args, Some(kwargs))
+            };
+            let hash = |leverage: Option<i64>, flags: bool, parent: Option<&str>| -> String {
+                call(leverage, flags, parent).unwrap().extract().unwrap()
+            };
+
+            assert_eq!(
+                hash(None, false, None),
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+            #[cfg(feature = "draft-layouts")]
+            {
+                assert_eq!(
+                    hash(Some(10), false, None),
+                    "0x5253db4a4d3f9daa2d7aeac111da194344de563717d274fa58c63b15197b8d3"
+                );
+                assert_eq!(
+                    hash(Some(1), false, None),
+                    "0x281d59db4956bd979d19e4112a5969f597bededb65589b789b2aff21fceab71"
+                );
+                assert_eq!(
+                    hash(Some(100), true, None),
+                    "0x5ce22fee038b0fdb68432fde6ec3b2dbe103ff08b5db02655dc587ce64db237"
+                );
+                let parent = "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48";
+                assert_eq!(
+                    hash(Some(10), false, Some(parent)),
+                    "0x2f1b9de4e436f167adafe224a3f4862f0d6aea460a6f539f602374501849b85"
+                );
+            }
+            #[cfg(not(feature = "draft-layouts"))]
+            {
+                let err = call(Some(10), false, None).unwrap_err();
+                assert!(err.to_string().contains("draft-layouts"), "{}", err);
+            }
+
+            // Out-of-range leverage is rejected either way
+            for leverage in [0, 101, -1] {
+                let err = call(Some(leverage), false, None).unwrap_err();
+                assert!(err.to_string().contains("field `leverage`"), "{}", err);
+            }
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_rejects_same_assets() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = PyTuple::new(
+                py,
+                [
+                    "100",
+                    "0x1",
+                    "100",
+                    "0x1",
+                    "-156",
+                    "0x1",
+                    "74",
+                    "100",
+                    "123",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ],
+            );
+            let get_order_msg = module.getattr("rs_get_order_msg").unwrap();
+
+            let err = get_order_msg.call1(args).unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            assert!(err.to_string().contains("quote_asset_id"), "{}", err);
+
+            let kwargs = [("allow_same_assets", true)].into_py_dict(py);
+            assert!(get_order_msg.call(args, Some(kwargs)).is_ok());
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_sided() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let mut args = [
+                "100",
+                "0x2",
+                "buy",
+                "100",
+                "0x1",
+                "sell",
+                "156",
+                "0x1",
+                "74",
+                "100",
+                "123",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            ];
+            let get_order_msg_sided = module.getattr("rs_get_order_msg_sided").unwrap();
+
+            let result: String = get_order_msg_sided
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+
+            args[5] = "short";
+            let err = get_order_msg_sided
+                .call1(PyTuple::new(py, args))
+                .unwrap_err();
+            assert!(err.to_string().contains("quote_side"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_scaled() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let mut args = [
+                "100",
+                "0x2",
+                "0.1",
+                "1000",
+                "0x1",
+                "-0.156",
+                "1000",
+                "0x1",
+                "0.074",
+                "1000",
+                "100",
+                "123",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            ];
+            let get_order_msg_scaled = module.getattr("rs_get_order_msg_scaled").unwrap();
+
+            let result: String = get_order_msg_scaled
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+
+            args[2] = "0.1000";
+            let result: String = get_order_msg_scaled
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+
+            args[2] = "0.1005";
+            let err = get_order_msg_scaled
+                .call1(PyTuple::new(py, args))
+                .unwrap_err();
+            assert!(err.to_string().contains("not a whole number"), "{}", err);
+
+            args[2] = "1e3";
+            let err = get_order_msg_scaled
+                .call1(PyTuple::new(py, args))
+                .unwrap_err();
+            assert!(err.to_string().contains("base_human_amount"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_felt_class() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let felt_class = module.getattr("Felt").unwrap();
+
+            let felt = felt_class.call1(("0x0A",)).unwrap();
+            let hex: String = felt.call_method0("hex").unwrap().extract().unwrap();
+            assert_eq!(hex, "0xa");
+            assert_eq!(felt.repr().unwrap().to_str().unwrap(), "Felt(0xa)");
+            let value: BigUint = felt.call_method1("__int__", ()).unwrap().extract().unwrap();
+            assert_eq!(value, BigUint::from(10u32));
+            let from_int = felt_class.call1((10,)).unwrap();
+            let hex: String = from_int.call_method0("hex").unwrap().extract().unwrap();
+            assert_eq!(hex, "0xa");
+
+            assert!(felt_class.call1(("0xzz",)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_preparsed() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let felt_class = module.getattr("Felt").unwrap();
+            let base_asset_id = felt_class.call1(("0x2",)).unwrap();
+            let quote_asset_id = felt_class.call1(("0x1",)).unwrap();
+            let get_order_msg_preparsed = module.getattr("rs_get_order_msg_preparsed").unwrap();
+            let hash = |base_asset_id: &PyAny| {
+                get_order_msg_preparsed.call1(PyTuple::new(
+                    py,
+                    [
+                        "100".to_object(py),
+                        base_asset_id.to_object(py),
+                        "100".to_object(py),
+                        quote_asset_id.to_object(py),
+                        "-156".to_object(py),
+                        quote_asset_id.to_object(py),
+                        "74".to_object(py),
+                        "100".to_object(py),
+                        "123".to_object(py),
+                        "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904"
+                            .to_object(py),
+                        "Perpetuals".to_object(py),
+                        "v0".to_object(py),
+                        "SN_SEPOLIA".to_object(py),
+                        "1".to_object(py),
+                    ],
+                ))
+            };
+
+            let result: String = hash(base_asset_id).unwrap().extract().unwrap();
+            assert_eq!(
+                result,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+            let err = hash(quote_asset_id).unwrap_err();
+            assert!(
+                err.to_string().contains("base_asset_id and quote_asset_id"),
+                "{}",
+                err
+            );
+            // Asset ids must already be Felt objects
+            let err = hash("0x2".into_py(py).into_ref(py)).unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn test_rs_order_field_sensitivity() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let mut args = [
+                "100",
+                "0x2",
+                "100",
+                "0x1",
+                "-156",
+                "0x1",
+                "74",
+                "100",
+                "123",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            ];
+            let (baseline, sensitivity): (String, Vec<(String, String)>) = module
+                .getattr("rs_order_field_sensitivity")
+                .unwrap()
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+
+            let names: Vec<&str> = sensitivity.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, ORDER_FIELD_NAMES[..10]);
+            assert_eq!(
+                baseline,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+            let mut hashes: Vec<&str> = sensitivity.iter().map(|(_, hash)| hash.as_str()).collect();
+            assert!(!hashes.contains(&baseline.as_str()));
+            hashes.sort();
+            hashes.dedup();
+            assert_eq!(hashes.len(), 10);
+
+            // Bumping a field matches hashing the bumped order directly
+            args[0] = "101";
+            let bumped: String = module
+                .getattr("rs_get_order_msg")
+                .unwrap()
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(sensitivity[0].1, bumped);
+        });
+    }
+
+    #[test]
+    fn test_rs_get_msg_int() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let order_args = PyTuple::new(
+                py,
+                [
+                    "100",
+                    "0x2",
+                    "100",
+                    "0x1",
+                    "-156",
+                    "0x1",
+                    "74",
+                    "100",
+                    "123",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ],
+            );
+            let result = module
+                .getattr("rs_get_order_msg_int")
+                .unwrap()
+                .call1(order_args)
+                .unwrap();
+            assert!(result.is_instance_of::<PyLong>());
+            let hash: BigUint = result.extract().unwrap();
+            assert_eq!(
+                hash,
+                BigUint::parse_bytes(
+                    b"4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48",
+                    16
+                )
+                .unwrap()
+            );
+
+            let transfer_args = (
+                "1",
+                "2",
+                "0x3",
+                "4",
+                "5",
+                "6",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            );
+            let hash: BigUint = module
+                .getattr("rs_get_transfer_msg_int")
+                .unwrap()
+                .call1(transfer_args)
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                hash,
+                BigUint::parse_bytes(
+                    b"56c7b21d13b79a33d7700dda20e22246c25e89818249504148174f527fc3f8f",
+                    16
+                )
+                .unwrap()
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_fee_rate() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            // ceil(156 * 4743 / 10000) = 74, the fee in test_rs_get_order_msg
+            let args = [
+                "100",
+                "0x2",
+                "100",
+                "0x1",
+                "-156",
+                "0x1",
+                "4743",
+                "100",
+                "123",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            ];
+            let result: String = module
+                .getattr("rs_get_order_msg_fee_rate")
+                .unwrap()
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_compute_domain_hash() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let domain_hash: String = module
+                .getattr("rs_compute_domain_hash")
+                .unwrap()
+                .call1(("Perpetuals", "v0", "SN_SEPOLIA", "1"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                domain_hash,
+                "0x62a6f0272bbf7ebbc25bf7432754da4522ff6d54a9c1015f3d4248a0b3941e7"
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_short_string_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let encode = module.getattr("rs_encode_short_string").unwrap();
+            let decode = module.getattr("rs_decode_short_string").unwrap();
+
+            let encoded: String = encode.call1(("SN_SEPOLIA",)).unwrap().extract().unwrap();
+            assert_eq!(encoded, "0x534e5f5345504f4c4941");
+            let decoded: String = decode.call1((&encoded,)).unwrap().extract().unwrap();
+            assert_eq!(decoded, "SN_SEPOLIA");
+
+            assert!(encode.call1(("a".repeat(32),)).is_err());
+            assert!(encode.call1(("é",)).is_err());
+            assert!(decode.call1(("0xe9",)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_compute_domain_hash_rejects_unsupported_revision() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let compute_domain_hash = module.getattr("rs_compute_domain_hash").unwrap();
+
+            assert!(compute_domain_hash
+                .call1(("Perpetuals", "v0", "SN_SEPOLIA", "1"))
+                .is_ok());
+            let err = compute_domain_hash
+                .call1(("Perpetuals", "v0", "SN_SEPOLIA", "2"))
+                .unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            assert!(err.to_string().contains("[1]"), "{}", err);
+            // Revision 0 uses a different domain encoding, which isn't
+            // implemented; it must not be hashed as revision 1
+            let err = compute_domain_hash
+                .call1(("Perpetuals", "v0", "SN_SEPOLIA", "0"))
+                .unwrap_err();
+            assert!(
+                err.to_string().contains("revision 0 is not implemented"),
+                "{}",
+                err
+            );
+            let err = compute_domain_hash
+                .call1(("Perpetuals", "v0", "SN_SEPOLIA", "99999999999"))
+                .unwrap_err();
+            assert!(err.to_string().contains("domain_revision"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_asset_id_int_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let config = [("shift", 8.into_py(py)), ("tag", "0x1".into_py(py))].into_py_dict(py);
+
+            let asset_id: String = module
+                .getattr("rs_asset_id_from_int")
+                .unwrap()
+                .call1(("5", config))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(asset_id, "0x501");
+
+            let to_int = module.getattr("rs_asset_id_to_int").unwrap();
+            let int_id: String = to_int.call1((asset_id, config)).unwrap().extract().unwrap();
+            assert_eq!(int_id, "5");
+            assert!(to_int.call1(("0x502", config)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_hashes() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = [
+                "100",
+                "0x2",
+                "100",
+                "0x1",
+                "-156",
+                "0x1",
+                "74",
+                "100",
+                "123",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            ];
+            let (struct_hash, message_hash): (String, String) = module
+                .getattr("rs_get_order_hashes")
+                .unwrap()
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+
+            assert_eq!(
+                struct_hash,
+                "0x3aed3c3627335a8753f0b2edf0d5cdd5414245fc6216c3face560420a460a30"
+            );
+            assert_eq!(
+                message_hash,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_accepts_ints() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args: Vec<PyObject> = vec![
+                100.into_py(py),
+                2.into_py(py),
+                100.into_py(py),
+                "0x1".into_py(py),
+                (-156).into_py(py),
+                "0x1".into_py(py),
+                74.into_py(py),
+                "100".into_py(py),
+                123.into_py(py),
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904".into_py(py),
+                "Perpetuals".into_py(py),
+                "v0".into_py(py),
+                "SN_SEPOLIA".into_py(py),
+                1.into_py(py),
+            ];
+            let result: String = module
+                .getattr("rs_get_order_msg")
+                .unwrap()
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_msg_names_wrong_argument() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let mut args: Vec<PyObject> = [
+                "100",
+                "0x2",
+                "100",
+                "0x1",
+                "-156",
+                "0x1",
+                "74",
+                "100",
+                "123",
+                "0x5",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            ]
+            .into_iter()
+            .map(|arg| arg.into_py(py))
+            .collect();
+            args[2] = 1.5.into_py(py);
+            let err = module
+                .getattr("rs_get_order_msg")
+                .unwrap()
+                .call1(PyTuple::new(py, args))
+                .unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+            assert!(err.to_string().contains("base_amount"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_build_signed_order_payload() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let public_key = "0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43";
+            let mut args = [
+                "100",
+                "0x02",
+                "100",
+                "0x1",
+                "-156",
+                "0x1",
+                "74",
+                "100",
+                "123",
+                public_key,
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+                priv_key,
+            ];
+            let build_payload = module.getattr("rs_build_signed_order_payload").unwrap();
+
+            let payload: String = build_payload
+                .call1(PyTuple::new(py, args))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+            assert_eq!(payload["base_asset_id"], "0x2");
+            assert_eq!(payload["quote_amount"], "-156");
+            assert_eq!(payload["user_public_key"], public_key);
+            assert_eq!(
+                payload["message_hash"],
+                "0x4dd1361c1a546266a9e61c3aa96ec9376b25aaf352ec670f0f2a1b95dcf2a9b"
+            );
+            let msg_hash = Felt::from_hex(payload["message_hash"].as_str().unwrap()).unwrap();
+            let r = Felt::from_hex(payload["signature"]["r"].as_str().unwrap()).unwrap();
+            let s = Felt::from_hex(payload["signature"]["s"].as_str().unwrap()).unwrap();
+            let public_key = Felt::from_hex(public_key).unwrap();
+            assert!(verify_signature(&public_key, &msg_hash, &r, &s).unwrap());
+
+            args[14] = "0x1";
+            let err = build_payload.call1(PyTuple::new(py, args)).unwrap_err();
+            assert!(err.to_string().contains("user_public_key"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_reexpire_order() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let order = [
+                ("position_id", "100"),
+                ("base_asset_id", "0x2"),
+                ("base_amount", "100"),
+                ("quote_asset_id", "0x1"),
+                ("quote_amount", "-156"),
+                ("fee_asset_id", "0x1"),
+                ("fee_amount", "74"),
+                ("expiration", "1"),
+                ("salt", "123"),
+                (
+                    "user_public_key",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                ),
+            ]
+            .into_py_dict(py);
+            let (message_hash, r, s): (String, String, String) = module
+                .getattr("rs_reexpire_order")
+                .unwrap()
+                .call1((
+                    order,
+                    "100",
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                    priv_key,
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+
+            assert_eq!(
+                message_hash,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+            let public_key = fetch_public_key(&Felt::from_hex(priv_key).unwrap());
+            let msg_hash = Felt::from_hex(&message_hash).unwrap();
+            let r = Felt::from_hex(&r).unwrap();
+            let s = Felt::from_hex(&s).unwrap();
+            assert!(verify_signature(&public_key, &msg_hash, &r, &s).unwrap());
+        });
+    }
+
+    #[test]
+    fn test_rs_validate_order_batch() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let order = |base_asset_id: &str, quote_amount: &str| {
+                [
+                    ("position_id", "100"),
+                    ("base_asset_id", base_asset_id),
+                    ("base_amount", "100"),
+                    ("quote_asset_id", "0x1"),
+                    ("quote_amount", quote_amount),
+                    ("fee_asset_id", "0x1"),
+                    ("fee_amount", "74"),
+                    ("expiration", "100"),
+                    ("salt", "123"),
+                    (
+                        "user_public_key",
+                        "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    ),
+                ]
+                .into_py_dict(py)
+            };
+            let validate = module.getattr("rs_validate_order_batch").unwrap();
+
+            let orders = vec![
+                order("0x2", "-156"),
+                order("0x1", "-156"),
+                order("0x2", "-156"),
+                order("0x2", "abc"),
+            ];
+            let errors: Vec<(usize, String)> =
+                validate.call1((orders,)).unwrap().extract().unwrap();
+            assert_eq!(errors.len(), 2);
+            assert_eq!(errors[0].0, 1);
+            assert!(errors[0].1.contains("base_asset_id and quote_asset_id"));
+            assert_eq!(errors[1].0, 3);
+            assert!(errors[1].1.contains("quote_amount"));
+
+            let errors: Vec<(usize, String)> = validate
+                .call1((vec![order("0x2", "-156")],))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(errors.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_rs_eip712_hash() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            // The Mail example from the EIP-712 specification, as signed by
+            // MetaMask's eth_signTypedData_v4
+            let domain = r#"{"name": "Ether Mail", "version": "1", "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"}"#;
+            let types = r#"{
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "Person": [
+                    {"name": "name", "type": "string"},
+                    {"name": "wallet", "type": "address"}
+                ],
+                "Mail": [
+                    {"name": "from", "type": "Person"},
+                    {"name": "to", "type": "Person"},
+                    {"name": "contents", "type": "string"}
+                ]
+            }"#;
+            let message = r#"{
+                "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+                "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+                "contents": "Hello, Bob!"
+            }"#;
+            let eip712_hash = module.getattr("rs_eip712_hash").unwrap();
+
+            let hash: String = eip712_hash
+                .call1((domain, types, message))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                hash,
+                "0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+            );
+            assert!(eip712_hash.call1((domain, types, "{")).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_sum_order_notionals() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let order = |quote_amount: &str| {
+                [
+                    ("position_id", "100"),
+                    ("base_asset_id", "0x2"),
+                    ("base_amount", "100"),
+                    ("quote_asset_id", "0x1"),
+                    ("quote_amount", quote_amount),
+                    ("fee_asset_id", "0x1"),
+                    ("fee_amount", "74"),
+                    ("expiration", "100"),
+                    ("salt", "123"),
+                    (
+                        "user_public_key",
+                        "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    ),
+                ]
+                .into_py_dict(py)
+            };
+            let sum = module.getattr("rs_sum_order_notionals").unwrap();
+
+            let orders = vec![
+                order("-9223372036854775808"),
+                order("9223372036854775807"),
+                order("-156"),
+            ];
+            let total: String = sum.call1((orders,)).unwrap().extract().unwrap();
+            assert_eq!(total, "18446744073709551771");
+
+            let empty: Vec<&PyDict> = vec![];
+            let total: String = sum.call1((empty,)).unwrap().extract().unwrap();
+            assert_eq!(total, "0");
+
+            let err = sum.call1((vec![order("-156"), order("1.5")],)).unwrap_err();
+            assert!(err.to_string().contains("Order 1"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_orders_matchable() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let order = |base_amount: &str, quote_amount: &str| {
+                [
+                    ("position_id", "100"),
+                    ("base_asset_id", "0x2"),
+                    ("base_amount", base_amount),
+                    ("quote_asset_id", "0x1"),
+                    ("quote_amount", quote_amount),
+                    ("fee_asset_id", "0x1"),
+                    ("fee_amount", "74"),
+                    ("expiration", "100"),
+                    ("salt", "123"),
+                    ("user_public_key", "0x1"),
+                ]
+                .into_py_dict(py)
+            };
+            let matchable = module.getattr("rs_orders_matchable").unwrap();
+            let mismatch_reason = module.getattr("rs_orders_mismatch_reason").unwrap();
+            let check = |b_base: &str, b_quote: &str| -> (bool, Option<String>) {
+                let args = (order("100", "-156"), order(b_base, b_quote));
+                (
+                    matchable.call1(args).unwrap().extract().unwrap(),
+                    mismatch_reason.call1(args).unwrap().extract().unwrap(),
+                )
+            };
+
+            assert_eq!(check("-100", "156"), (true, None));
+            assert_eq!(check("100", "-156"), (false, Some("same side".to_string())));
+            assert_eq!(
+                check("-100", "200"),
+                (false, Some("prices don't cross".to_string()))
+            );
+
+            let err = matchable
+                .call1((order("100", "-156"), order("abc", "156")))
+                .unwrap_err();
+            assert!(err.to_string().contains("base_amount"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_migrate_orders() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let order = [
+                ("position_id", "100"),
+                ("base_asset_id", "0x2"),
+                ("base_amount", "100"),
+                ("quote_asset_id", "0x1"),
+                ("quote_amount", "-156"),
+                ("fee_asset_id", "0x1"),
+                ("fee_amount", "74"),
+                ("expiration", "100"),
+                ("salt", "123"),
+                (
+                    "user_public_key",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                ),
+            ]
+            .into_py_dict(py);
+            let old_domain = ("Perpetuals", "v0", "SN_SEPOLIA", "1");
+            let new_domain = ("Perpetuals", "v0", "SN_MAIN", "1");
+            let migrate_orders = module.getattr("rs_migrate_orders").unwrap();
+
+            let migrated: Vec<(String, String, String, String)> = migrate_orders
+                .call1((vec![order], old_domain, new_domain, vec![priv_key]))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let (old_hash, new_hash, r, s) = &migrated[0];
+            assert_eq!(
+                old_hash,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+            assert_eq!(
+                new_hash,
+                "0x5ea56be002fedb0538788ad412c33e639fd1e67241b308630be77d16d441b17"
+            );
+            let public_key = fetch_public_key(&Felt::from_hex(priv_key).unwrap());
+            let new_hash = Felt::from_hex(new_hash).unwrap();
+            let r = Felt::from_hex(r).unwrap();
+            let s = Felt::from_hex(s).unwrap();
+            assert!(verify_signature(&public_key, &new_hash, &r, &s).unwrap());
+
+            let err = migrate_orders
+                .call1((vec![order], old_domain, new_domain, Vec::<&str>::new()))
+                .unwrap_err();
+            assert!(err.to_string().contains("private keys"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_order_signature() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let public_key = "0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43";
+            let order = [
+                ("position_id", "100"),
+                ("base_asset_id", "0x2"),
+                ("base_amount", "100"),
+                ("quote_asset_id", "0x1"),
+                ("quote_amount", "-156"),
+                ("fee_asset_id", "0x1"),
+                ("fee_amount", "74"),
+                ("expiration", "100"),
+                ("salt", "123"),
+                ("user_public_key", public_key),
+            ]
+            .into_py_dict(py);
+            let domain = ("Perpetuals", "v0", "SN_SEPOLIA", "1");
+            let (message_hash, r, s): (String, String, String) = module
+                .getattr("rs_reexpire_order")
+                .unwrap()
+                .call1((
+                    order, "100", domain.0, domain.1, domain.2, domain.3, priv_key,
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+
+            let verify = module.getattr("rs_verify_order_signature").unwrap();
+            let (valid, recomputed): (bool, String) = verify
+                .call1((order, domain.0, domain.1, domain.2, domain.3, &r, &s))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(valid);
+            assert_eq!(recomputed, message_hash);
+
+            order.set_item("fee_amount", "75").unwrap();
+            let (valid, recomputed): (bool, String) = verify
+                .call1((order, domain.0, domain.1, domain.2, domain.3, &r, &s))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(!valid);
+            assert_ne!(recomputed, message_hash);
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_order_concat_sig() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let public_key = "0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43";
+            let order = [
+                ("position_id", "100"),
+                ("base_asset_id", "0x2"),
+                ("base_amount", "100"),
+                ("quote_asset_id", "0x1"),
+                ("quote_amount", "-156"),
+                ("fee_asset_id", "0x1"),
+                ("fee_amount", "74"),
+                ("expiration", "100"),
+                ("salt", "123"),
+                ("user_public_key", public_key),
+            ]
+            .into_py_dict(py);
+            let domain = ("Perpetuals", "v0", "SN_SEPOLIA", "1");
+            let (_, r, s): (String, String, String) = module
+                .getattr("rs_reexpire_order")
+                .unwrap()
+                .call1((
+                    order, "100", domain.0, domain.1, domain.2, domain.3, priv_key,
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let sig_concat = format!(
+                "0x{:0>64}{:0>64}",
+                r.trim_start_matches("0x"),
+                s.trim_start_matches("0x")
+            );
+
+            let verify = module.getattr("rs_verify_order_concat_sig").unwrap();
+            let valid: bool = verify
+                .call1((
+                    order,
+                    domain.0,
+                    domain.1,
+                    domain.2,
+                    domain.3,
+                    public_key,
+                    &sig_concat,
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(valid);
+
+            order.set_item("fee_amount", "75").unwrap();
+            let valid: bool = verify
+                .call1((
+                    order,
+                    domain.0,
+                    domain.1,
+                    domain.2,
+                    domain.3,
+                    public_key,
+                    &sig_concat,
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(!valid);
+
+            let err = verify
+                .call1((
+                    order,
+                    domain.0,
+                    domain.1,
+                    domain.2,
+                    domain.3,
+                    public_key,
+                    &sig_concat[..sig_concat.len() - 2],
+                ))
+                .unwrap_err();
+            assert!(err.to_string().contains("128 hex digits"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_order_fingerprint() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let order = [
+                ("position_id", "100"),
+                ("base_asset_id", "0x2"),
+                ("base_amount", "100"),
+                ("quote_asset_id", "0x1"),
+                ("quote_amount", "-156"),
+                ("fee_asset_id", "0x1"),
+                ("fee_amount", "74"),
+                ("expiration", "100"),
+                ("salt", "123"),
+                (
+                    "user_public_key",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                ),
+            ]
+            .into_py_dict(py);
+            let order_fingerprint = module.getattr("rs_order_fingerprint").unwrap();
+            let fingerprint = |order: &PyDict| -> String {
+                order_fingerprint
+                    .call1((order,))
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            };
+            let expected = "0x4e11b09c4055f3c80e9f8f3d453353dc17d0a58b3d87f7622009f5a4c92e37a";
+            assert_eq!(fingerprint(order), expected);
+
+            order
+                .set_item(
+                    "user_public_key",
+                    "0x05D05989E9302DCEBC74E241001E3E3AC3F4402CCF2F8E6F74B034B07AD6A904",
+                )
+                .unwrap();
+            order.set_item("base_asset_id", 2).unwrap();
+            order.set_item("quote_asset_id", "0x0001").unwrap();
+            order.set_item("position_id", 100).unwrap();
+            assert_eq!(fingerprint(order), expected);
+
+            order.set_item("salt", "124").unwrap();
+            assert_ne!(fingerprint(order), expected);
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_order_batch() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let public_key = "0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43";
+            let order = [
+                ("position_id", "100"),
+                ("base_asset_id", "0x2"),
+                ("base_amount", "100"),
+                ("quote_asset_id", "0x1"),
+                ("quote_amount", "-156"),
+                ("fee_asset_id", "0x1"),
+                ("fee_amount", "74"),
+                ("expiration", "100"),
+                ("salt", "123"),
+                ("user_public_key", public_key),
+            ]
+            .into_py_dict(py);
+            let domain = ("Perpetuals", "v0", "SN_SEPOLIA", "1");
+            let (_, r, s): (String, String, String) = module
+                .getattr("rs_reexpire_order")
+                .unwrap()
+                .call1((
+                    order, "100", domain.0, domain.1, domain.2, domain.3, priv_key,
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let verify_order_batch = module.getattr("rs_verify_order_batch").unwrap();
+
+            let records = vec![
+                (order, public_key, r.as_str(), s.as_str()),
+                (order, "0x1", r.as_str(), s.as_str()),
+                (order, public_key, s.as_str(), r.as_str()),
+                (order, public_key, "0xzz", s.as_str()),
+            ];
+            let valid: Vec<bool> = verify_order_batch
+                .call1((records, domain.0, domain.1, domain.2, domain.3))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(valid, vec![true, false, false, false]);
+
+            order.set_item("base_amount", "x").unwrap();
+            let records = vec![(order, public_key, r.as_str(), s.as_str())];
+            let err = verify_order_batch
+                .call1((records, domain.0, domain.1, domain.2, domain.3))
+                .unwrap_err();
+            assert!(err.to_string().contains("Record 0"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_signed_order_bytes_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let order = [
+                ("position_id", "100"),
+                ("base_asset_id", "0x2"),
+                ("base_amount", "100"),
+                ("quote_asset_id", "0x1"),
+                ("quote_amount", "-156"),
+                ("fee_asset_id", "0x1"),
+                ("fee_amount", "74"),
+                ("expiration", "100"),
+                ("salt", "123"),
+                (
+                    "user_public_key",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                ),
+            ]
+            .into_py_dict(py);
+            let message_hash = "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48";
+            let to_bytes = module.getattr("rs_signed_order_to_bytes").unwrap();
+            let bytes: &PyBytes = to_bytes
+                .call1((order, message_hash, "0x3", "0x4"))
+                .unwrap()
+                .downcast()
+                .unwrap();
+            assert_eq!(bytes.as_bytes().len(), 292);
+
+            let (decoded, decoded_hash, r, s): (&PyDict, String, String, String) = module
+                .getattr("rs_signed_order_from_bytes")
+                .unwrap()
+                .call1((bytes,))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(decoded.eq(order).unwrap());
+            assert_eq!(decoded_hash, message_hash);
+            assert_eq!((r.as_str(), s.as_str()), ("0x3", "0x4"));
+
+            let reencoded: &PyBytes = to_bytes
+                .call1((decoded, decoded_hash, r, s))
+                .unwrap()
+                .downcast()
+                .unwrap();
+            assert_eq!(reencoded.as_bytes(), bytes.as_bytes());
+
+            let truncated = PyBytes::new(py, &bytes.as_bytes()[..291]);
+            assert!(module
+                .getattr("rs_signed_order_from_bytes")
+                .unwrap()
+                .call1((truncated,))
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_pedersen_verify_proof() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let verify_proof = module.getattr("rs_pedersen_verify_proof").unwrap();
+            // root = H(0x3, H(0x1, 0x2))
+            let root = "0x551b4adb6c35d49c686a00b9192da9332b18c9b262507cad0ece37f3b6918d2";
+            let proof = vec![("0x2", false), ("0x3", true)];
+
+            let valid: bool = verify_proof
+                .call1(("0x1", proof.clone(), root))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(valid);
+
+            let flipped = vec![("0x2", true), ("0x3", true)];
+            let valid: bool = verify_proof
+                .call1(("0x1", flipped, root))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(!valid);
+
+            let empty: Vec<(&str, bool)> = vec![];
+            let valid: bool = verify_proof
+                .call1(("0x1", empty, "0x1"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(valid);
+
+            assert!(verify_proof
+                .call1(("0x1", vec![("zz", false)], root))
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_pedersen_with_zero() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let pedersen_with_zero = module.getattr("rs_pedersen_with_zero").unwrap();
+            let compute_pedersen_hash = module.getattr("rs_compute_pedersen_hash").unwrap();
+
+            for x in [
+                "0x0",
+                "0x1",
+                "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+            ] {
+                let hash: String = pedersen_with_zero.call1((x,)).unwrap().extract().unwrap();
+                let expected: String = compute_pedersen_hash
+                    .call1((x, "0x0"))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                assert_eq!(hash, expected);
+            }
+            assert!(pedersen_with_zero.call1(("0xzz",)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_hash2() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let hash2 = module.getattr("rs_hash2").unwrap();
+
+            let pedersen: String = hash2
+                .call1(("pedersen", "0x1", "0x2"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(pedersen, pedersen_hash(&Felt::ONE, &Felt::TWO).to_string());
+
+            let poseidon: String = hash2
+                .call1(("poseidon", "0x1", "0x2"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                poseidon,
+                "2636648219362971850283425434366427370362725365790740855428580782178634926362"
+            );
+
+            let err = hash2.call1(("keccak", "0x1", "0x2")).unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+        });
+    }
+
+    #[test]
+    fn test_rs_sign_message_with_hash() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let msg_hash = "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48";
+
+            let (hash, r, s): (String, String, String) = module
+                .getattr("rs_sign_message_with_hash")
+                .unwrap()
+                .call1((priv_key, msg_hash))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let (plain_r, plain_s): (String, String) = module
+                .getattr("rs_sign_message")
+                .unwrap()
+                .call1((priv_key, msg_hash))
+                .unwrap()
+                .extract()
+                .unwrap();
+
+            assert_eq!(hash, msg_hash);
+            assert_eq!(Felt::from_hex(&r).unwrap().to_string(), plain_r);
+            assert_eq!(Felt::from_hex(&s).unwrap().to_string(), plain_s);
+        });
+    }
+
+    #[test]
+    fn test_rs_sign_message_checked() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let sign_message = module.getattr("rs_sign_message").unwrap();
+            let sign_message_checked = module.getattr("rs_sign_message_checked").unwrap();
+
+            let checked: (String, String) = sign_message_checked
+                .call1((priv_key, "0x1234"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let plain: (String, String) = sign_message
+                .call1((priv_key, "0x1234"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(checked, plain);
+
+            // A hash past 2^251 can't be signed
+            let err = sign_message_checked
+                .call1((priv_key, ELEMENT_UPPER_BOUND.to_hex_string()))
+                .unwrap_err();
+            assert!(err.is_instance_of::<SigningError>(py), "{}", err);
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            let err = sign_message_checked.call1((priv_key, "0xzz")).unwrap_err();
+            assert!(!err.is_instance_of::<SigningError>(py), "{}", err);
+            assert!(module.getattr("SigningError").is_ok());
+        });
+    }
+
+    #[test]
+    fn test_rs_sign_message_padded() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let sign_message = module.getattr("rs_sign_message").unwrap();
+            let sign_message_padded = module.getattr("rs_sign_message_padded").unwrap();
+
+            for msg_hash in [
+                "0x1",
+                "0x1234",
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48",
+            ] {
+                let (r, s): (String, String) = sign_message_padded
+                    .call1((priv_key, msg_hash))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                let (plain_r, plain_s): (String, String) = sign_message
+                    .call1((priv_key, msg_hash))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                for (padded, plain) in [(&r, &plain_r), (&s, &plain_s)] {
+                    assert_eq!(padded.len(), 66, "{}", padded);
+                    assert!(padded.starts_with("0x"), "{}", padded);
+                    assert_eq!(&Felt::from_hex(padded).unwrap().to_string(), plain);
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_rs_sign_with_handle() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let handle: u64 = module
+                .getattr("rs_register_key")
+                .unwrap()
+                .call1((priv_key,))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let sign_with_handle = module.getattr("rs_sign_with_handle").unwrap();
+            let drop_key = module.getattr("rs_drop_key").unwrap();
+
+            let signature: (String, String) = sign_with_handle
+                .call1((handle, "0x1234"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let expected: (String, String) = module
+                .getattr("rs_sign_message")
+                .unwrap()
+                .call1((priv_key, "0x1234"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(signature, expected);
+
+            let dropped: bool = drop_key.call1((handle,)).unwrap().extract().unwrap();
+            assert!(dropped);
+            let dropped: bool = drop_key.call1((handle,)).unwrap().extract().unwrap();
+            assert!(!dropped);
+            let err = sign_with_handle.call1((handle, "0x1234")).unwrap_err();
+            assert!(err.to_string().contains("Unknown key handle"), "{}", err);
+
+            assert!(module
+                .getattr("rs_register_key")
+                .unwrap()
+                .call1(("0x0",))
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_sign_with_key_shares() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let ec_order_minus_one =
+                "0x800000000000010ffffffffffffffffb781126dcae7b2321e66a241adc64d2e";
+            let sign_with_key_shares = module.getattr("rs_sign_with_key_shares").unwrap();
+            let expected: (String, String) = module
+                .getattr("rs_sign_message")
+                .unwrap()
+                .call1((priv_key, "0x1234"))
+                .unwrap()
+                .extract()
+                .unwrap();
+
+            let signature: (String, String) = sign_with_key_shares
+                .call1((
+                    "0x123",
+                    "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e2a9",
+                    "0x1234",
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(signature, expected);
+
+            // The shares sum past EC_ORDER and wrap around to the key
+            let signature: (String, String) = sign_with_key_shares
+                .call1((
+                    ec_order_minus_one,
+                    "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cd",
+                    "0x1234",
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(signature, expected);
+
+            let err = sign_with_key_shares
+                .call1((ec_order_minus_one, "0x1", "0x1234"))
+                .unwrap_err();
+            assert!(err.to_string().contains("sum to zero"), "{}", err);
+            let err = sign_with_key_shares
+                .call1((
+                    "0x1",
+                    "0x800000000000010ffffffffffffffffb781126dcae7b2321e66a241adc64d2f",
+                    "0x1234",
+                ))
+                .unwrap_err();
+            assert!(err.to_string().contains("share_b"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_sign_message_with_external_nonce() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let msg_hash = "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48";
+            let nonce = "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+            let sign = module
+                .getattr("rs_sign_message_with_external_nonce")
+                .unwrap();
+
+            let (r, s, r_x): (String, String, String) = sign
+                .call1((priv_key, msg_hash, nonce))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(r, r_x);
+            let public_key = fetch_public_key(&Felt::from_hex(priv_key).unwrap());
+            let msg_hash_felt = Felt::from_hex(msg_hash).unwrap();
+            let r = Felt::from_hex(&r).unwrap();
+            let s = Felt::from_hex(&s).unwrap();
+            assert!(verify_signature(&public_key, &msg_hash_felt, &r, &s).unwrap());
+
+            assert!(sign.call1((priv_key, msg_hash, "0xff")).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_sign_for_invoke() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let tx_hash = "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48";
+            let sign = module.getattr("rs_sign_for_invoke").unwrap();
+
+            let plain: Vec<String> = sign.call1((tx_hash, priv_key)).unwrap().extract().unwrap();
+            let recoverable: Vec<String> = sign
+                .call1((tx_hash, priv_key, true))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(plain.len(), 2);
+            assert_eq!(recoverable.len(), 3);
+            assert_eq!(plain[..], recoverable[..2]);
+
+            let public_key = fetch_public_key(&Felt::from_hex(priv_key).unwrap());
+            let r = Felt::from_hex(&plain[0]).unwrap();
+            let s = Felt::from_hex(&plain[1]).unwrap();
+            let msg_hash = Felt::from_hex(tx_hash).unwrap();
+            assert!(verify_signature(&public_key, &msg_hash, &r, &s).unwrap());
+        });
+    }
+
+    #[test]
+    fn test_rs_compute_account_address() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let compute_account_address = module.getattr("rs_compute_account_address").unwrap();
+            let class_hash = "0x33434ad846cdd5f23eb73ff09fe6fddd568284a0fb7d1be20ee482f044dabe2";
+            let public_key = "0x77a3b314db07c45076d11f62b6f9e748a39790441823307743cf00d6597ea43";
+
+            let address: String = compute_account_address
+                .call1((class_hash, public_key, vec![public_key]))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                address,
+                "0x57dce334bd39e0fcff97b11a95e79d0799c5e373435a3859673304b0e74a134"
+            );
+
+            let err = compute_account_address
+                .call1((class_hash, public_key, vec!["0x1", "oops"]))
+                .unwrap_err();
+            assert!(
+                err.to_string().contains("constructor_calldata[1]"),
+                "{}",
+                err
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_get_selector_from_name() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let get_selector_from_name = module.getattr("rs_get_selector_from_name").unwrap();
+
+            let selector: String = get_selector_from_name
+                .call1(("__execute__",))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                selector,
+                "0x15d40a3d6ca2ac30f4031e42be28da9b056fef9bb7357ac5e85627ee876e5ad"
+            );
+
+            let err = get_selector_from_name.call1(("tränsfer",)).unwrap_err();
+            assert!(err.to_string().contains("not ASCII"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_compute_multicall_hash() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let compute_multicall_hash = module.getattr("rs_compute_multicall_hash").unwrap();
+            let transfer = "0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e";
+            let execute = "0x15d40a3d6ca2ac30f4031e42be28da9b056fef9bb7357ac5e85627ee876e5ad";
+
+            let hash: String = compute_multicall_hash
+                .call1((vec![
+                    ("0x123", transfer, vec!["0x456", "0x64", "0x0"]),
+                    ("0x789", execute, vec![]),
+                ],))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                hash,
+                "0x3ca9952921038692d919cd0adb2872cf3563a0edbf8cdc69bcaffad8358b23f"
+            );
+
+            let err = compute_multicall_hash
+                .call1((vec![("0x123", transfer, vec!["0x456", "zz"])],))
+                .unwrap_err();
+            assert!(err.to_string().contains("calls[0].calldata[1]"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_signature_to_calldata() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let to_calldata = module.getattr("rs_signature_to_calldata").unwrap();
+
+            let plain: Vec<String> = to_calldata
+                .call1(("0xa", "0xb", "plain"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(plain, vec!["0xa", "0xb"]);
+
+            let prefixed: Vec<String> = to_calldata
+                .call1(("0xa", "0xb", "length_prefixed"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(prefixed, vec!["0x2", "0xa", "0xb"]);
+
+            let err = to_calldata.call1(("0xa", "0xb", "wrapped")).unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_hash_signature() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key =
+                Felt::from_hex("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc")
+                    .unwrap();
+            let public_key = fetch_public_key(&priv_key).to_hex_string();
+            let msg_hash = "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48";
+            let signature = sign_message(&Felt::from_hex(msg_hash).unwrap(), &priv_key).unwrap();
+            let r = signature.r.to_hex_string();
+            let s = signature.s.to_hex_string();
+            let verify = module.getattr("rs_verify_hash_signature").unwrap();
+
+            let valid: bool = verify
+                .call1((msg_hash, &r, &s, &public_key))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(valid);
+
+            // Public key in the message hash's place, as a transposed column would be
+            let valid: bool = verify
+                .call1((&public_key, &r, &s, msg_hash))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(!valid);
+
+            let out_of_range = EC_ORDER.to_hex_string();
+            let valid: bool = verify
+                .call1((msg_hash, &out_of_range, &s, &public_key))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(!valid);
+
+            assert!(verify.call1((msg_hash, "zz", &s, &public_key)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_signature_strict_encoding() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key =
+                Felt::from_hex("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc")
+                    .unwrap();
+            let public_key = fetch_public_key(&priv_key).to_hex_string();
+            let msg_hash = Felt::from_hex("0x1234").unwrap();
+            let signature = sign_message(&msg_hash, &priv_key).unwrap();
+            let r = PyBytes::new(py, &signature.r.to_bytes_be());
+            let s = PyBytes::new(py, &signature.s.to_bytes_be());
+            let verify = module
+                .getattr("rs_verify_signature_strict_encoding")
+                .unwrap();
+
+            let valid: bool = verify
+                .call1((&public_key, "0x1234", r, s))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(valid);
+
+            let short_r = PyBytes::new(py, &signature.r.to_bytes_be()[1..]);
+            let err = verify
+                .call1((&public_key, "0x1234", short_r, s))
+                .unwrap_err();
+            assert!(err.to_string().contains("32 bytes"), "{}", err);
+
+            let order = PyBytes::new(py, &EC_ORDER.to_bytes_be());
+            let err = verify.call1((&public_key, "0x1234", r, order)).unwrap_err();
+            assert!(err.to_string().contains("curve order"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_sign_pairs() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let pairs = vec![
+                ("0x1", "0x1234"),
+                (
+                    "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",
+                    "0x5678",
+                ),
+            ];
+            let sign_pairs = module.getattr("rs_sign_pairs").unwrap();
+
+            let signatures: Vec<(String, String)> = sign_pairs
+                .call1((pairs.clone(),))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(signatures.len(), 2);
+            for ((priv_key, msg_hash), (r, s)) in pairs.iter().zip(&signatures) {
+                let public_key = fetch_public_key(&Felt::from_hex(priv_key).unwrap());
+                let msg_hash = Felt::from_hex(msg_hash).unwrap();
+                let r = Felt::from_dec_str(r).unwrap();
+                let s = Felt::from_dec_str(s).unwrap();
+                assert!(verify_signature(&public_key, &msg_hash, &r, &s).unwrap());
+            }
+
+            let err = sign_pairs
+                .call1((vec![("0x1", "0x1234"), ("0x1", "not hex")],))
+                .unwrap_err();
+            assert!(err.to_string().contains("Pair 1"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_signature_int() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key =
+                Felt::from_hex("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc")
+                    .unwrap();
+            let to_int = |felt: &Felt| BigUint::from_bytes_be(&felt.to_bytes_be());
+            let public_key = to_int(&fetch_public_key(&priv_key));
+            let msg_hash = Felt::from_hex("0x1234").unwrap();
+            let signature = sign_message(&msg_hash, &priv_key).unwrap();
+            let r = to_int(&signature.r);
+            let s = to_int(&signature.s);
+            let verify = module.getattr("rs_verify_signature_int").unwrap();
+
+            let valid: bool = verify
+                .call1((&public_key, to_int(&msg_hash), &r, &s))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(valid);
+            let valid: bool = verify
+                .call1((&public_key, BigUint::from(0x1235u32), &r, &s))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(!valid);
+
+            let prime = to_int(&Felt::MAX) + 1u32;
+            let err = verify
+                .call1((&public_key, to_int(&msg_hash), &prime, &s))
+                .unwrap_err();
+            assert!(err.to_string().contains("field `r`"), "{}", err);
+            let err = verify
+                .call1((&public_key, to_int(&msg_hash), BigUint::ZERO, &s))
+                .unwrap_err();
+            assert!(err.to_string().contains("r out of range"), "{}", err);
+            assert!(verify.call1((&public_key, -1, &r, &s)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_signature_detailed() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key =
+                Felt::from_hex("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc")
+                    .unwrap();
+            let public_key = fetch_public_key(&priv_key).to_hex_string();
+            let msg_hash = Felt::from_hex("0x1234").unwrap();
+            let signature = sign_message(&msg_hash, &priv_key).unwrap();
+            let r = signature.r.to_hex_string();
+            let s = signature.s.to_hex_string();
+            let verify = module.getattr("rs_verify_signature_detailed").unwrap();
+            let outcome = |public_key: &str, msg_hash: &str, r: &str| {
+                let result = verify.call1((public_key, msg_hash, r, &s)).unwrap();
+                let valid: bool = result.getattr("valid").unwrap().extract().unwrap();
+                let reason: Option<String> = result.getattr("reason").unwrap().extract().unwrap();
+                (valid, reason)
+            };
+
+            assert_eq!(outcome(&public_key, "0x1234", &r), (true, None));
+            assert_eq!(
+                outcome(&public_key, "0x1235", &r),
+                (false, Some("hash mismatch".to_string()))
+            );
+            assert_eq!(
+                outcome(&public_key, "0x1234", "0x0"),
+                (false, Some("r out of range".to_string()))
+            );
+            // 0x5 is not the x-coordinate of any point on the curve
+            assert_eq!(
+                outcome("0x5", "0x1234", &r),
+                (false, Some("point not on curve".to_string()))
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_signature_canonical() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key =
+                Felt::from_hex("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc")
+                    .unwrap();
+            let public_key = fetch_public_key(&priv_key).to_hex_string();
+            let msg_hash = Felt::from_hex("0x1234").unwrap();
+            let signature = sign_message(&msg_hash, &priv_key).unwrap();
+            let low_s = if signature.s > *EC_ORDER_HALF {
+                *EC_ORDER - signature.s
+            } else {
+                signature.s
+            };
+            let high_s = *EC_ORDER - low_s;
+            let r = signature.r.to_hex_string();
+            let verify = module.getattr("rs_verify_signature_canonical").unwrap();
+            let outcome = |msg_hash: &str, s: &Felt| {
+                let result = verify
+                    .call1((&public_key, msg_hash, &r, s.to_hex_string()))
+                    .unwrap();
+                let valid: bool = result.getattr("valid").unwrap().extract().unwrap();
+                let reason: Option<String> = result.getattr("reason").unwrap().extract().unwrap();
+                (valid, reason)
+            };
+
+            assert_eq!(outcome("0x1234", &low_s), (true, None));
+            assert_eq!(
+                outcome("0x1234", &high_s),
+                (false, Some("non-canonical s".to_string()))
+            );
+            // An invalid signature is reported as such, whatever half its s is in
+            assert_eq!(
+                outcome("0x1235", &high_s),
+                (false, Some("hash mismatch".to_string()))
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_signature_timing_safe() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key =
+                Felt::from_hex("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc")
+                    .unwrap();
+            let public_key = fetch_public_key(&priv_key).to_hex_string();
+            let signature = sign_message(&Felt::from_hex("0x1234").unwrap(), &priv_key).unwrap();
+            let r = signature.r.to_hex_string();
+            let s = signature.s.to_hex_string();
+            let verify = module.getattr("rs_verify_signature_timing_safe").unwrap();
+            let valid = |public_key: &str, msg_hash: &str, r: &str, s: &str| -> bool {
+                verify
+                    .call1((public_key, msg_hash, r, s))
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            };
+
+            assert!(valid(&public_key, "0x1234", &r, &s));
+            assert!(!valid(&public_key, "0x1235", &r, &s));
+            // Out of range and off-curve inputs are rejected rather than raised
+            assert!(!valid(&public_key, "0x1234", "0x0", &s));
+            assert!(!valid(
+                &public_key,
+                "0x1234",
+                &r,
+                &Felt::MAX.to_hex_string()
+            ));
+            assert!(!valid("0x5", "0x1234", &r, &s));
+            assert!(verify.call1((&public_key, "0x1234", "zz", &s)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_signature_r_on_curve() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key =
+                Felt::from_hex("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc")
+                    .unwrap();
+            let signature_r_on_curve = module.getattr("rs_signature_r_on_curve").unwrap();
+            let on_curve =
+                |r: &str| -> bool { signature_r_on_curve.call1((r,)).unwrap().extract().unwrap() };
+
+            for msg_hash in [0x1234u64, 0x1235, 0x1236] {
+                let signature = sign_message(&Felt::from(msg_hash), &priv_key).unwrap();
+                assert!(on_curve(&signature.r.to_hex_string()));
+            }
+            assert!(!on_curve("0x5"));
+            assert!(signature_r_on_curve.call1(("0xzz",)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_signature_range_check() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let range_check = module.getattr("rs_signature_range_check").unwrap();
+            let check = |r: &str, s: &str| -> String {
+                range_check.call1((r, s)).unwrap().extract().unwrap()
+            };
+            let bound = ELEMENT_UPPER_BOUND.to_hex_string();
+            let below_bound = (*ELEMENT_UPPER_BOUND - Felt::ONE).to_hex_string();
+
+            assert_eq!(check("0x1", &below_bound), "ok");
+            assert_eq!(check("0x0", "0x1"), "r_out_of_range");
+            assert_eq!(check(&bound, "0x1"), "r_out_of_range");
+            assert_eq!(check("0x1", "0x0"), "s_out_of_range");
+            assert_eq!(check("0x1", &EC_ORDER.to_hex_string()), "s_out_of_range");
+            assert_eq!(check("0x0", &bound), "both");
+            assert!(range_check.call1(("0xzz", "0x1")).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_invalid_signature_indices() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key =
+                Felt::from_hex("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc")
+                    .unwrap();
+            let public_key = fetch_public_key(&priv_key).to_hex_string();
+            let msg_hash = Felt::from_hex("0x1234").unwrap();
+            let signature = sign_message(&msg_hash, &priv_key).unwrap();
+            let r = signature.r.to_hex_string();
+            let s = signature.s.to_hex_string();
+
+            let items = vec![
+                (
+                    public_key.clone(),
+                    "0x1234".to_string(),
+                    r.clone(),
+                    s.clone(),
+                ),
+                (
+                    public_key.clone(),
+                    "0x1235".to_string(),
+                    r.clone(),
+                    s.clone(),
+                ),
+                (
+                    public_key.clone(),
+                    "0x1234".to_string(),
+                    r.clone(),
+                    s.clone(),
+                ),
+                (public_key, "not hex".to_string(), r, s),
+            ];
+            let result: Vec<usize> = module
+                .getattr("rs_invalid_signature_indices")
+                .unwrap()
+                .call1((items,))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(result, vec![1, 3]);
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_any() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key =
+                Felt::from_hex("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc")
+                    .unwrap();
+            let public_key = fetch_public_key(&priv_key).to_hex_string();
+            let other_key = fetch_public_key(&Felt::from(12345u64)).to_hex_string();
+            let msg_hash = Felt::from_hex("0x1234").unwrap();
+            let signature = sign_message(&msg_hash, &priv_key).unwrap();
+            let r = signature.r.to_hex_string();
+            let s = signature.s.to_hex_string();
+            let verify_any = module.getattr("rs_verify_any").unwrap();
+
+            let matched: Option<usize> = verify_any
+                .call1((
+                    vec![other_key.clone(), public_key.clone()],
+                    "0x1234",
+                    &r,
+                    &s,
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(matched, Some(1));
+
+            let matched: Option<usize> = verify_any
+                .call1((vec![other_key], "0x1234", &r, &s))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(matched, None);
+
+            // 0x5 is not the x-coordinate of any point on the curve
+            let err = verify_any
+                .call1((vec![public_key, "0x5".to_string()], "0x1234", &r, &s))
+                .unwrap_err();
+            assert!(err.to_string().contains("pub_keys[1]"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_verify_multisig() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let msg_hash = Felt::from_hex("0x1234").unwrap();
+            let signer = |priv_key: u64| {
+                let priv_key = Felt::from(priv_key);
+                let signature = sign_message(&msg_hash, &priv_key).unwrap();
+                (
+                    fetch_public_key(&priv_key).to_hex_string(),
+                    signature.r.to_hex_string(),
+                    signature.s.to_hex_string(),
+                )
+            };
+            let (alice, bob, carol) = (signer(111), signer(222), signer(333));
+            // Carol's key with Alice's signature doesn't verify
+            let forged = (carol.0.clone(), alice.1.clone(), alice.2.clone());
+            let verify_multisig = module.getattr("rs_verify_multisig").unwrap();
+            let approved = |signers: Vec<(String, String, String)>, threshold: usize| -> bool {
+                verify_multisig
+                    .call1(("0x1234", signers, threshold))
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            };
+
+            assert!(approved(vec![alice.clone(), bob.clone(), carol.clone()], 3));
+            assert!(approved(
+                vec![alice.clone(), forged.clone(), bob.clone()],
+                2
+            ));
+            assert!(!approved(vec![alice.clone(), forged, bob.clone()], 3));
+            // A repeated signer only counts once
+            assert!(!approved(vec![alice.clone(), alice.clone()], 2));
+
+            assert!(verify_multisig
+                .call1(("0x1234", vec![alice.clone()], 0))
+                .is_err());
+            // 0x5 is not the x-coordinate of any point on the curve
+            let err = verify_multisig
+                .call1(("0x1234", vec![alice, ("0x5".to_string(), bob.1, bob.2)], 1))
+                .unwrap_err();
+            assert!(err.to_string().contains("signers[1]"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_get_order_and_transfer_msg_by_keyword() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let user_public_key_hex =
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904";
+
+            let order_values = [
+                "100",
+                "0x2",
+                "100",
+                "0x1",
+                "-156",
+                "0x1",
+                "74",
+                "100",
+                "123",
+                user_public_key_hex,
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            ];
+            let order_kwargs = ORDER_FIELD_NAMES
+                .into_iter()
+                .zip(order_values)
+                .into_py_dict(py);
+            let result: String = module
+                .getattr("rs_get_order_msg")
+                .unwrap()
+                .call((), Some(order_kwargs))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+
+            let transfer_values = [
+                "1",
+                "2",
+                "0x3",
+                "4",
+                "5",
+                "6",
+                user_public_key_hex,
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            ];
+            let transfer_kwargs = TRANSFER_FIELD_NAMES
+                .into_iter()
+                .zip(transfer_values)
+                .into_py_dict(py);
+            let result: String = module
+                .getattr("rs_get_transfer_msg")
+                .unwrap()
+                .call((), Some(transfer_kwargs))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x56c7b21d13b79a33d7700dda20e22246c25e89818249504148174f527fc3f8f"
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_get_transfer_msg() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+
+            let recipient_position_id = "1".to_string();
+            let sender_position_id = "2".to_string();
+            let collateral_id_hex = "0x3".to_string();
+            let amount = "4".to_string();
+            let expiration = "5".to_string();
+            let salt = "6".to_string();
+            let user_public_key_hex =
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904".to_string();
+
+            let domain_name = "Perpetuals".to_string();
+            let domain_version = "v0".to_string();
+            let domain_chain_id = "SN_SEPOLIA".to_string();
+            let domain_revision = "1".to_string();
+
+            let result: String = module
+                .getattr("rs_get_transfer_msg")
+                .unwrap()
+                .call1(PyTuple::new(
+                    py,
+                    [
+                        recipient_position_id,
+                        sender_position_id,
+                        collateral_id_hex,
+                        amount,
+                        expiration,
+                        salt,
+                        user_public_key_hex,
+                        domain_name,
+                        domain_version,
+                        domain_chain_id,
+                        domain_revision,
+                    ],
+                ))
+                .unwrap()
+                .extract()
+                .unwrap();
+
+            assert_eq!(
+                result, "0x56c7b21d13b79a33d7700dda20e22246c25e89818249504148174f527fc3f8f",
+                "Hashes do not match for TransferArgs"
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_get_transfer_msg_expiration_window() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = (
+                "1",
+                "2",
+                "0x3",
+                "4",
+                "1700000000000",
+                "6",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            );
+            let get_transfer_msg = module.getattr("rs_get_transfer_msg").unwrap();
+            let window = |min_expiration: &str, max_expiration: &str| {
+                [
+                    ("min_expiration", min_expiration),
+                    ("max_expiration", max_expiration),
+                ]
+                .into_py_dict(py)
+            };
+
+            assert!(get_transfer_msg.call1(args).is_ok());
+            assert!(get_transfer_msg
+                .call(args, Some(window("1700000000000", "1700000000000")))
+                .is_ok());
+            let err = get_transfer_msg
+                .call(args, Some(window("1600000000", "1800000000")))
+                .unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            assert!(err.to_string().contains("max_expiration"), "{}", err);
+            let err = get_transfer_msg
+                .call(
+                    args,
+                    Some([("min_expiration", "1800000000000")].into_py_dict(py)),
+                )
+                .unwrap_err();
+            assert!(err.to_string().contains("min_expiration"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_get_msg_amount_ranges() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let user_public_key =
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904";
+            let get_order_msg = module.getattr("rs_get_order_msg").unwrap();
+            let order_msg = |base_amount: &str, quote_amount: &str, fee_amount: &str| {
+                get_order_msg.call1((
+                    "100",
+                    "0x2",
+                    base_amount,
+                    "0x1",
+                    quote_amount,
+                    "0x1",
+                    fee_amount,
+                    "100",
+                    "123",
+                    user_public_key,
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ))
+            };
+
+            assert!(order_msg("-100", "156", "74").is_ok());
+            let err = order_msg("100", "-156", "-74").unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("field `fee_amount` must be non-negative"),
+                "{}",
+                err
+            );
+            let err = order_msg("9223372036854775808", "-156", "74").unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("field `base_amount` must be within the i64 range"),
+                "{}",
+                err
+            );
+            let err = order_msg("100", "-9223372036854775809", "74").unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("field `quote_amount` must be within the i64 range"),
+                "{}",
+                err
+            );
+            let err = order_msg("100", "-156", "7x").unwrap_err();
+            assert!(err.to_string().contains("Failed to parse"), "{}", err);
+
+            let err = module
+                .getattr("rs_get_transfer_msg")
+                .unwrap()
+                .call1((
+                    "1",
+                    "2",
+                    "0x3",
+                    "-4",
+                    "5",
+                    "6",
+                    user_public_key,
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ))
+                .unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("field `amount` must be non-negative"),
+                "{}",
+                err
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_get_transfer_msg_with_owner() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = (
+                "1",
+                "2",
+                "0x3",
+                "4",
+                "5",
+                "6",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            );
+            let get_transfer_msg = module.getattr("rs_get_transfer_msg").unwrap();
+
+            let kwargs = [("owner_address_hex", "0x7")].into_py_dict(py);
+            #[cfg(feature = "draft-layouts")]
+            {
+                let result: String = get_transfer_msg
+                    .call(args, Some(kwargs))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                assert_eq!(
+                    result,
+                    "0x5e8cd66e464e6e73171c234cc2b557a3fa0e0fb0eddf8da8d52aca51b1f283"
+                );
+
+                // 0x7 plus the field prime would reduce to the same owner
+                let kwargs = [(
+                    "owner_address_hex",
+                    "0x800000000000011000000000000000000000000000000000000000000000008",
+                )]
+                .into_py_dict(py);
+                let err = get_transfer_msg.call(args, Some(kwargs)).unwrap_err();
+                assert!(err.to_string().contains("field `owner_address`"), "{}", err);
+            }
+            #[cfg(not(feature = "draft-layouts"))]
+            {
+                let err = get_transfer_msg.call(args, Some(kwargs)).unwrap_err();
+                assert!(err.to_string().contains("draft-layouts"), "{}", err);
+            }
+
+            let kwargs = [("owner_address_hex", py.None())].into_py_dict(py);
+            let result: String = get_transfer_msg
+                .call(args, Some(kwargs))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x56c7b21d13b79a33d7700dda20e22246c25e89818249504148174f527fc3f8f"
+            );
+        });
+    }
+
+    #[test]
+    fn test_rs_get_transfer_msg_with_nonce() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = (
+                "1",
+                "2",
+                "0x3",
+                "4",
+                "5",
+                "6",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            );
+            let get_transfer_msg = module.getattr("rs_get_transfer_msg").unwrap();
+
+            let kwargs = [("nonce", "7")].into_py_dict(py);
+            #[cfg(feature = "draft-layouts")]
+            {
+                let result: String = get_transfer_msg
+                    .call(args, Some(kwargs))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                assert_eq!(
+                    result,
+                    "0x293283306e93159015b68e9dd2c3cc0a09128ee4d04e08dab7adc5955b72134"
+                );
+            }
+            #[cfg(not(feature = "draft-layouts"))]
+            {
+                let err = get_transfer_msg.call(args, Some(kwargs)).unwrap_err();
+                assert!(err.to_string().contains("draft-layouts"), "{}", err);
+            }
+
+            let kwargs = [("nonce", py.None())].into_py_dict(py);
+            let result: String = get_transfer_msg
+                .call(args, Some(kwargs))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                result,
+                "0x56c7b21d13b79a33d7700dda20e22246c25e89818249504148174f527fc3f8f"
+            );
+
+            let kwargs = [("nonce", "7"), ("owner_address_hex", "0x7")].into_py_dict(py);
+            let err = get_transfer_msg.call(args, Some(kwargs)).unwrap_err();
+            assert!(err.to_string().contains("can't both be given"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_get_transfer_msg_with_memo() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let args = (
+                "1",
+                "2",
+                "0x3",
+                "4",
+                "5",
+                "6",
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                "Perpetuals",
+                "v0",
+                "SN_SEPOLIA",
+                "1",
+            );
+            let get_transfer_msg = module.getattr("rs_get_transfer_msg").unwrap();
+            let hash = |kwargs: &PyDict| -> String {
+                get_transfer_msg
+                    .call(args, Some(kwargs))
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            };
+
+            assert_eq!(
+                hash([("memo_hex", py.None())].into_py_dict(py)),
+                "0x56c7b21d13b79a33d7700dda20e22246c25e89818249504148174f527fc3f8f"
+            );
+
+            #[cfg(feature = "draft-layouts")]
+            {
+                assert_eq!(
+                    hash([("memo_hex", "0x7")].into_py_dict(py)),
+                    "0x33eccd557594e3696964cc8d8d22414c7ef5bfe73c5b46c206612358e476b71"
+                );
+                // A zero memo is still bound, unlike leaving the memo out
+                assert_eq!(
+                    hash([("memo_hex", "0x0")].into_py_dict(py)),
+                    "0x548dca623b24dbf2ac5003df6cdc6202f7c3fa09324541382d0d303026363c1"
+                );
+
+                // The field prime itself is not a felt
+                let kwargs = [(
+                    "memo_hex",
+                    "0x800000000000011000000000000000000000000000000000000000000000001",
+                )]
+                .into_py_dict(py);
+                let err = get_transfer_msg.call(args, Some(kwargs)).unwrap_err();
+                assert!(err.to_string().contains("field `memo`"), "{}", err);
+            }
+            #[cfg(not(feature = "draft-layouts"))]
+            {
+                let kwargs = [("memo_hex", "0x7")].into_py_dict(py);
+                let err = get_transfer_msg.call(args, Some(kwargs)).unwrap_err();
+                assert!(err.to_string().contains("draft-layouts"), "{}", err);
+            }
+
+            let kwargs = [("memo_hex", "0x7"), ("nonce", "7")].into_py_dict(py);
+            let err = get_transfer_msg.call(args, Some(kwargs)).unwrap_err();
+            assert!(err.to_string().contains("memo can't be given"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_set_max_batch_size() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let set_max_batch_size = module.getattr("rs_set_max_batch_size").unwrap();
+            // Only an invalid limit here: a valid one would apply to the
+            // batches other tests run in parallel
+            assert!(set_max_batch_size.call1((0,)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_check_batch_size_against() {
+        assert!(batch::check_batch_size_against("pairs", 100, 100).is_ok());
+        let err = batch::check_batch_size_against("pairs", 101, 100).unwrap_err();
+        assert!(err.contains("pairs has 101 items"), "{}", err);
+        assert!(err.contains("maximum batch size of 100"), "{}", err);
+    }
+
+    #[test]
+    fn test_rs_hash_message() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let hash_message = module.getattr("rs_hash_message").unwrap();
+            let user_public_key =
+                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904";
+            let hash = |message_type: &str, fields: &PyDict| {
+                hash_message.call1((message_type, fields, "Perpetuals", "v0", "SN_SEPOLIA", "1"))
+            };
+
+            let order = [
+                ("position_id", "100"),
+                ("base_asset_id", "0x2"),
+                ("base_amount", "100"),
+                ("quote_asset_id", "0x1"),
+                ("quote_amount", "-156"),
+                ("fee_asset_id", "0x1"),
+                ("fee_amount", "74"),
+                ("expiration", "100"),
+                ("salt", "123"),
+                ("user_public_key", user_public_key),
+            ]
+            .into_py_dict(py);
+            let result: String = hash("order", order).unwrap().extract().unwrap();
+            assert_eq!(
+                result,
+                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+            );
+
+            let transfer = [
+                ("recipient_position_id", "1"),
+                ("sender_position_id", "2"),
+                ("collateral_id", "0x3"),
+                ("amount", "4"),
+                ("expiration", "5"),
+                ("salt", "6"),
+                ("user_public_key", user_public_key),
+            ]
+            .into_py_dict(py);
+            let result: String = hash("transfer", transfer).unwrap().extract().unwrap();
+            assert_eq!(
+                result,
+                "0x56c7b21d13b79a33d7700dda20e22246c25e89818249504148174f527fc3f8f"
+            );
 
-#[cfg(test)]
-mod tests {
+            let withdrawal = [
+                ("position_id", "2"),
+                ("collateral_id", "0x3"),
+                ("amount", "4"),
+                ("eth_address", "0x71C7656EC7ab88b098defB751B7401B5f6d8976F"),
+                ("expiration", "5"),
+                ("salt", "6"),
+                ("user_public_key", user_public_key),
+            ]
+            .into_py_dict(py);
+            #[cfg(feature = "draft-layouts")]
+            {
+                let result: String = hash("withdrawal", withdrawal).unwrap().extract().unwrap();
+                assert_eq!(
+                    result,
+                    "0x451fdcdaee86a5d64d118a7382211d8595ef9579e17d212df00232763a365be"
+                );
 
-    use pyo3::types::PyTuple;
+                withdrawal.del_item("amount").unwrap();
+                withdrawal.del_item("salt").unwrap();
+                let err = hash("withdrawal", withdrawal).unwrap_err();
+                assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+                assert!(err.to_string().contains("amount, salt"), "{}", err);
+            }
+            #[cfg(not(feature = "draft-layouts"))]
+            {
+                let err = hash("withdrawal", withdrawal).unwrap_err();
+                assert!(err.to_string().contains("draft-layouts"), "{}", err);
+            }
 
-    use super::*;
+            let err = hash("cancel", transfer).unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            assert!(err.to_string().contains("cancel"), "{}", err);
+        });
+    }
 
     #[test]
-    fn test_rs_get_order_msg() {
+    fn test_rs_sign_pairs_with_progress() {
         pyo3::prepare_freethreaded_python();
         Python::with_gil(|py| {
             let module = PyModule::new(py, "fast_stark_crypto").unwrap();
             fast_stark_crypto(py, module).unwrap();
-            let position_id = "100".to_string();
-            let base_asset_id = "0x2".to_string();
-            let base_amount = "100".to_string();
-            let quote_asset_id = "0x1".to_string();
-            let quote_amount = "-156".to_string();
-            let fee_asset_id = "0x1".to_string();
-            let fee_amount = "74".to_string();
-            let expiration = "100".to_string();
-            let salt = "123".to_string();
-            let user_public_key =
-                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904".to_string();
-            let domain_name = "Perpetuals".to_string();
-            let domain_version = "v0".to_string();
-            let domain_chain_id = "SN_SEPOLIA".to_string();
-            let domain_revision = "1".to_string();
-            let result: String = module
-                .getattr("rs_get_order_msg")
+            let locals = PyDict::new(py);
+            py.run("reported = []", None, Some(locals)).unwrap();
+            let progress = py.eval("reported.append", None, Some(locals)).unwrap();
+            let pairs = vec![("0x1", "0x1234"); 5];
+
+            let signatures: Vec<(String, String)> = module
+                .getattr("rs_sign_pairs_with_progress")
                 .unwrap()
-                .call1(PyTuple::new(
-                    py,
-                    [
-                        position_id,
-                        base_asset_id,
-                        base_amount,
-                        quote_asset_id,
-                        quote_amount,
-                        fee_asset_id,
-                        fee_amount,
-                        expiration,
-                        salt,
-                        user_public_key,
-                        domain_name,
-                        domain_version,
-                        domain_chain_id,
-                        domain_revision,
-                    ],
-                ))
+                .call1((pairs.clone(), progress, 2))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let expected: Vec<(String, String)> = module
+                .getattr("rs_sign_pairs")
+                .unwrap()
+                .call1((pairs,))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(signatures, expected);
+
+            let reported: Vec<usize> = locals
+                .get_item("reported")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(reported, vec![2, 4, 5]);
+        });
+    }
+
+    #[test]
+    fn test_rs_sign_pairs_timeout() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let sign_pairs = module.getattr("rs_sign_pairs").unwrap();
+            let pairs = vec![("0x1", "0x1234"); 4];
+
+            let err = sign_pairs.call1((pairs.clone(), 0)).unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyTimeoutError>(py));
+            assert!(err.to_string().contains("0 of 4"), "{}", err);
+
+            let signatures: Vec<(String, String)> = sign_pairs
+                .call1((pairs, 60_000))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(signatures.len(), 4);
+        });
+    }
+
+    #[test]
+    fn test_rs_sign_file() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let priv_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+            let dir = std::env::temp_dir();
+            let input_path = dir.join(format!("rs_sign_file_{}_in.csv", std::process::id()));
+            let output_path = dir.join(format!("rs_sign_file_{}_out.csv", std::process::id()));
+            let paths = (
+                input_path.to_str().unwrap().to_string(),
+                output_path.to_str().unwrap().to_string(),
+            );
+            let sign_file = module.getattr("rs_sign_file").unwrap();
+
+            std::fs::write(&input_path, "0x1234\n\n0x5678\r\n").unwrap();
+            let written: usize = sign_file
+                .call1((&paths.0, &paths.1, priv_key))
                 .unwrap()
                 .extract()
                 .unwrap();
+            assert_eq!(written, 2);
+            let output = std::fs::read_to_string(&output_path).unwrap();
+            let rows: Vec<&str> = output.lines().collect();
+            assert_eq!(rows.len(), 2);
+            for (row, msg_hash) in rows.iter().zip(["0x1234", "0x5678"]) {
+                let (r, s): (String, String) = module
+                    .getattr("rs_sign_message")
+                    .unwrap()
+                    .call1((priv_key, msg_hash))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                let r = Felt::from_dec_str(&r).unwrap().to_hex_string();
+                let s = Felt::from_dec_str(&s).unwrap().to_hex_string();
+                assert_eq!(*row, format!("{},{},{}", msg_hash, r, s));
+            }
 
+            let err = sign_file.call1((&paths.0, &paths.0, priv_key)).unwrap_err();
+            assert!(err.to_string().contains("same file"), "{}", err);
             assert_eq!(
-                result,
-                "0x4de4c009e0d0c5a70a7da0e2039fb2b99f376d53496f89d9f437e736add6b48"
+                std::fs::read_to_string(&input_path).unwrap(),
+                "0x1234\n\n0x5678\r\n"
             );
+
+            std::fs::write(&input_path, "0x1234\n0x5678\n0xzz\n").unwrap();
+            let err = sign_file.call1((&paths.0, &paths.1, priv_key)).unwrap_err();
+            assert!(err.to_string().contains("Line 3"), "{}", err);
+
+            std::fs::remove_file(&input_path).unwrap();
+            std::fs::remove_file(&output_path).unwrap();
+            let err = sign_file.call1((&paths.0, &paths.1, priv_key)).unwrap_err();
+            assert!(err.to_string().contains("Failed to open"), "{}", err);
         });
     }
 
     #[test]
-    fn test_rs_get_transfer_msg() {
+    fn test_field_names_match_signatures() {
         pyo3::prepare_freethreaded_python();
         Python::with_gil(|py| {
             let module = PyModule::new(py, "fast_stark_crypto").unwrap();
             fast_stark_crypto(py, module).unwrap();
+            let positional_names = |function: &str| -> Vec<String> {
+                let signature: String = module
+                    .getattr(function)
+                    .unwrap()
+                    .getattr("__text_signature__")
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                signature
+                    .trim_start_matches('(')
+                    .trim_end_matches(')')
+                    .split(", ")
+                    .filter(|name| !name.contains('='))
+                    .map(str::to_string)
+                    .collect()
+            };
 
-            let recipient_position_id = "1".to_string();
-            let sender_position_id = "2".to_string();
-            let collateral_id_hex = "0x3".to_string();
-            let amount = "4".to_string();
-            let expiration = "5".to_string();
-            let salt = "6".to_string();
-            let user_public_key_hex =
-                "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904".to_string();
+            let order_names: Vec<String> = module
+                .getattr("rs_order_field_names")
+                .unwrap()
+                .call0()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(order_names, positional_names("rs_get_order_msg"));
 
-            let domain_name = "Perpetuals".to_string();
-            let domain_version = "v0".to_string();
-            let domain_chain_id = "SN_SEPOLIA".to_string();
-            let domain_revision = "1".to_string();
+            let transfer_names: Vec<String> = module
+                .getattr("rs_transfer_field_names")
+                .unwrap()
+                .call0()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(transfer_names, positional_names("rs_get_transfer_msg"));
+        });
+    }
 
-            let result: String = module
-                .getattr("rs_get_transfer_msg")
+    #[test]
+    fn test_rs_generate_timestamped_salt() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+
+            let salt: u64 = module
+                .getattr("rs_generate_timestamped_salt")
                 .unwrap()
-                .call1(PyTuple::new(
-                    py,
-                    [
-                        recipient_position_id,
-                        sender_position_id,
-                        collateral_id_hex,
-                        amount,
-                        expiration,
-                        salt,
-                        user_public_key_hex,
-                        domain_name,
-                        domain_version,
-                        domain_chain_id,
-                        domain_revision,
-                    ],
-                ))
+                .call1((1_700_000_000u64,))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let timestamp: u64 = module
+                .getattr("rs_salt_timestamp")
+                .unwrap()
+                .call1((salt,))
                 .unwrap()
                 .extract()
                 .unwrap();
+            assert_eq!(timestamp, 1_700_000_000);
+
+            assert!(module
+                .getattr("rs_generate_timestamped_salt")
+                .unwrap()
+                .call1((1u64 << 32,))
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_test_keypairs() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let test_keypairs = module.getattr("rs_test_keypairs").unwrap();
 
+            let keypairs: Vec<(String, String)> =
+                test_keypairs.call1((3,)).unwrap().extract().unwrap();
             assert_eq!(
-                result, "0x56c7b21d13b79a33d7700dda20e22246c25e89818249504148174f527fc3f8f",
-                "Hashes do not match for TransferArgs"
+                keypairs[0],
+                (
+                    "0x129423501bbb30c96bc5cea8f02023a8df041974fe11adbe247080f927b6172".to_string(),
+                    "0x2ae3d6a23139e7949c6929e1296a7887c1c36c0dca951f35826ebc7e69633a7".to_string()
+                )
+            );
+            assert_eq!(
+                keypairs[2],
+                (
+                    "0x5efd43a478f3fcc62ee00dbb4793bb8a76c980fab91fb60a7322dd4f08a5696".to_string(),
+                    "0x2b3d48153c56b355a046307a8062177a614c5d50abec2ea17e768a181ec5ab0".to_string()
+                )
+            );
+            let prefix: Vec<(String, String)> =
+                test_keypairs.call1((2,)).unwrap().extract().unwrap();
+            assert_eq!(prefix, keypairs[..2]);
+        });
+    }
+
+    #[test]
+    fn test_rs_commit_position() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let commit = module.getattr("rs_commit_position").unwrap();
+            let verify = module.getattr("rs_verify_position_commitment").unwrap();
+
+            let commitment: String = commit.call1(("100", "0x1234")).unwrap().extract().unwrap();
+            let valid: bool = verify
+                .call1(("100", "0x1234", &commitment))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(valid);
+            let valid: bool = verify
+                .call1(("101", "0x1234", &commitment))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(!valid);
+
+            let err = commit.call1(("4294967296", "0x1234")).unwrap_err();
+            assert!(err.to_string().contains("position_id"), "{}", err);
+
+            // The prime would reduce to a zero blinding factor
+            let prime = "0x800000000000011000000000000000000000000000000000000000000000001";
+            let err = commit.call1(("100", prime)).unwrap_err();
+            assert!(err.to_string().contains("blinding"), "{}", err);
+            let err = verify.call1(("100", prime, &commitment)).unwrap_err();
+            assert!(err.to_string().contains("blinding"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_commit_collateral() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let commit = module.getattr("rs_commit_collateral").unwrap();
+            let verify = module.getattr("rs_verify_collateral_commitment").unwrap();
+
+            let commitment: String = commit
+                .call1(("100", "0x1", "1000", "0x1234"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                commitment,
+                "0x2a697364ad5791f62b1d9b5e5fdbbaccd9d2656ae888191e3a370df0dd8120d"
+            );
+            let opens = |balance: &str| -> bool {
+                verify
+                    .call1(("100", "0x1", balance, "0x1234", &commitment))
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            };
+            assert!(opens("1000"));
+            assert!(!opens("999"));
+
+            let err = commit
+                .call1(("100", "0x1", "9223372036854775808", "0x1234"))
+                .unwrap_err();
+            assert!(err.to_string().contains("balance"), "{}", err);
+            let err = commit.call1(("100", "0x1", "1000", "0xzz")).unwrap_err();
+            assert!(err.to_string().contains("blinding"), "{}", err);
+            let prime = "0x800000000000011000000000000000000000000000000000000000000000001";
+            let err = commit.call1(("100", "0x1", "1000", prime)).unwrap_err();
+            assert!(err.to_string().contains("blinding"), "{}", err);
+            let err = verify
+                .call1(("100", "0x1", "1000", prime, &commitment))
+                .unwrap_err();
+            assert!(err.to_string().contains("blinding"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_commit_order_batch() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let order = |position_id: &str| {
+                [
+                    ("position_id", position_id),
+                    ("base_asset_id", "0x2"),
+                    ("base_amount", "100"),
+                    ("quote_asset_id", "0x1"),
+                    ("quote_amount", "-156"),
+                    ("fee_asset_id", "0x1"),
+                    ("fee_amount", "74"),
+                    ("expiration", "100"),
+                    ("salt", "123"),
+                    (
+                        "user_public_key",
+                        "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    ),
+                ]
+                .into_py_dict(py)
+            };
+            let commit = module.getattr("rs_commit_order_batch").unwrap();
+            let open = module.getattr("rs_open_order_batch_commitment").unwrap();
+            let orders = vec![order("100"), order("101")];
+
+            let commitment: String = commit
+                .call1((orders.clone(), "0x1234"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                commitment,
+                "0x2491f842dfa72568e25b3641d522d6847302f6687f1e6c4743e28f19507f84b"
+            );
+            let opens = |orders: Vec<&PyDict>, blinding: &str| -> bool {
+                open.call1((orders, blinding, &commitment))
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            };
+            assert!(opens(orders.clone(), "0x1234"));
+            assert!(!opens(orders.clone(), "0x1235"));
+            assert!(!opens(vec![order("101"), order("100")], "0x1234"));
+            assert!(!opens(vec![order("100")], "0x1234"));
+
+            let err = commit
+                .call1((vec![order("100"), order("abc")], "0x1234"))
+                .unwrap_err();
+            assert!(err.to_string().contains("Order 1"), "{}", err);
+            let prime = "0x800000000000011000000000000000000000000000000000000000000000001";
+            let err = commit.call1((orders.clone(), prime)).unwrap_err();
+            assert!(err.to_string().contains("blinding"), "{}", err);
+            let err = open.call1((orders, prime, &commitment)).unwrap_err();
+            assert!(err.to_string().contains("blinding"), "{}", err);
+        });
+    }
+
+    #[test]
+    fn test_rs_derive_viewing_key() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let derive = module.getattr("rs_derive_viewing_key").unwrap();
+
+            let viewing_key: String = derive
+                .call1(("0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc",))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(
+                viewing_key,
+                "0x686ac09779e44c9271370c6e25a38dfef2613dd6756abf78dff4655434bb139"
+            );
+            assert!(derive.call1(("not hex",)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_rs_derive_subaccount_key() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+            fast_stark_crypto(py, module).unwrap();
+            let derive = module.getattr("rs_derive_subaccount_key").unwrap();
+            let master_key = "0x3c1e9550e66958296d11b60f8e8e7a7ad990d07fa65d5f7652c4a6c87d4e3cc";
+
+            let keypair: (String, String) =
+                derive.call1((master_key, 0)).unwrap().extract().unwrap();
+            assert_eq!(
+                keypair,
+                (
+                    "0x7e9e177ade0ba20dd28bba01c5eb628ba37b515e5d4073ca0fe09ba75b2bf9".to_string(),
+                    "0x33a42f7f16343c9e207127a57c800a9242c8634fcbe8fd3d47828b1db1e2e4c".to_string()
+                )
             );
+            let other: (String, String) = derive.call1((master_key, 1)).unwrap().extract().unwrap();
+            assert_ne!(other, keypair);
+
+            let err = derive.call1(("0x0", 0)).unwrap_err();
+            assert!(err.to_string().contains("Master key"), "{}", err);
         });
     }
+
+    // Hashes expected from draft layouts, here and in the `draft-layouts`
+    // blocks above, were computed with our own reference implementation: the
+    // exchange hasn't published these layouts, so they only guard against
+    // regressions
+    #[cfg(feature = "draft-layouts")]
+    mod draft_layouts {
+        use super::*;
+
+        #[test]
+        fn test_rs_get_withdrawal_to_address_msg() {
+            pyo3::prepare_freethreaded_python();
+            Python::with_gil(|py| {
+                let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+                fast_stark_crypto(py, module).unwrap();
+                let mut args = [
+                    "2",
+                    "0x3",
+                    "4",
+                    "0x71C7656EC7ab88b098defB751B7401B5f6d8976F",
+                    "5",
+                    "6",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ];
+                let get_withdrawal_msg =
+                    module.getattr("rs_get_withdrawal_to_address_msg").unwrap();
+
+                let result: String = get_withdrawal_msg
+                    .call1(PyTuple::new(py, args))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                assert_eq!(
+                    result,
+                    "0x451fdcdaee86a5d64d118a7382211d8595ef9579e17d212df00232763a365be"
+                );
+
+                // Too long, too short, and p + 1, which would reduce to 0x1
+                for eth_address in [
+                    "0x0171C7656EC7ab88b098defB751B7401B5f6d8976F",
+                    "0x1",
+                    "0x0800000000000011000000000000000000000000000000000000000000000002",
+                ] {
+                    args[3] = eth_address;
+                    let err = get_withdrawal_msg
+                        .call1(PyTuple::new(py, args))
+                        .unwrap_err();
+                    assert!(err.to_string().contains("40 hex digits"), "{}", err);
+                }
+            });
+        }
+
+        #[test]
+        fn test_rs_get_order_msg_with_fees() {
+            pyo3::prepare_freethreaded_python();
+            Python::with_gil(|py| {
+                let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+                fast_stark_crypto(py, module).unwrap();
+                let args = PyTuple::new(
+                    py,
+                    [
+                        "100",
+                        "0x2",
+                        "100",
+                        "0x1",
+                        "-156",
+                        "0x1",
+                        "100",
+                        "123",
+                        "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                        "Perpetuals",
+                        "v0",
+                        "SN_SEPOLIA",
+                        "1",
+                    ],
+                );
+                let function = module.getattr("rs_get_order_msg_with_fees").unwrap();
+
+                let maker_only: String = function
+                    .call(args, Some([("maker_fee_amount", "74")].into_py_dict(py)))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                assert_eq!(
+                    maker_only,
+                    "0x3d90c39546f309afdb13c4b518bd7829ddc2698f15fbf6afdb8b6dac0da7527"
+                );
+
+                let taker_only: String = function
+                    .call(args, Some([("taker_fee_amount", "74")].into_py_dict(py)))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                assert_eq!(
+                    taker_only,
+                    "0x1633c7aaa54501f4aa460f83dd189ee55c532ec33036dc2bcd4ba03d28694f9"
+                );
+            });
+        }
+
+        #[test]
+        fn test_rs_get_settlement_msg() {
+            pyo3::prepare_freethreaded_python();
+            Python::with_gil(|py| {
+                let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+                fast_stark_crypto(py, module).unwrap();
+                let signer_key =
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904";
+                let order =
+                    |position_id: &str, base_amount: &str, quote_amount: &str, salt: &str| {
+                        [
+                            ("position_id", position_id),
+                            ("base_asset_id", "0x2"),
+                            ("base_amount", base_amount),
+                            ("quote_asset_id", "0x1"),
+                            ("quote_amount", quote_amount),
+                            ("fee_asset_id", "0x1"),
+                            ("fee_amount", "74"),
+                            ("expiration", "100"),
+                            ("salt", salt),
+                            ("user_public_key", "0x1"),
+                        ]
+                        .into_py_dict(py)
+                    };
+                let get_settlement_msg = module.getattr("rs_get_settlement_msg").unwrap();
+                let args = |actual_b_amount: &str| {
+                    PyTuple::new(
+                        py,
+                        [
+                            order("100", "100", "-156", "123").to_object(py),
+                            order("200", "-100", "156", "456").to_object(py),
+                            "100".to_object(py),
+                            actual_b_amount.to_object(py),
+                            "74".to_object(py),
+                            "74".to_object(py),
+                            signer_key.to_object(py),
+                            "Perpetuals".to_object(py),
+                            "v0".to_object(py),
+                            "SN_SEPOLIA".to_object(py),
+                            "1".to_object(py),
+                        ],
+                    )
+                };
+
+                let result: String = get_settlement_msg
+                    .call1(args("-100"))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                assert_eq!(
+                    result,
+                    "0x294449a3ecf530c495e50d7267dfe9c9acc8c8e68751f33b472ec151d80fcaa"
+                );
+
+                let err = get_settlement_msg.call1(args("-1.5")).unwrap_err();
+                assert!(err.to_string().contains("actual_b_amount"), "{}", err);
+            });
+        }
+
+        #[test]
+        fn test_rs_get_cancel_all_msg() {
+            pyo3::prepare_freethreaded_python();
+            Python::with_gil(|py| {
+                let module = PyModule::new(py, "fast_stark_crypto").unwrap();
+                fast_stark_crypto(py, module).unwrap();
+                let mut args = [
+                    "100",
+                    "1800000000",
+                    "123",
+                    "0x5d05989e9302dcebc74e241001e3e3ac3f4402ccf2f8e6f74b034b07ad6a904",
+                    "Perpetuals",
+                    "v0",
+                    "SN_SEPOLIA",
+                    "1",
+                ];
+                let get_cancel_all_msg = module.getattr("rs_get_cancel_all_msg").unwrap();
+
+                let result: String = get_cancel_all_msg
+                    .call1(PyTuple::new(py, args))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                assert_eq!(
+                    result,
+                    "0x8fedd18217530d879755f623823d3282eb13028e0da075c8ca641119c39664"
+                );
+
+                args[0] = "4294967296";
+                let err = get_cancel_all_msg
+                    .call1(PyTuple::new(py, args))
+                    .unwrap_err();
+                assert!(err.to_string().contains("position_id"), "{}", err);
+
+                args[0] = "100";
+                args[1] = "-1";
+                let err = get_cancel_all_msg
+                    .call1(PyTuple::new(py, args))
+                    .unwrap_err();
+                assert!(err.to_string().contains("expiration"), "{}", err);
+            });
+        }
+    }
 }
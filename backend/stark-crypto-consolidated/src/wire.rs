@@ -0,0 +1,112 @@
+use rust_crypto_lib_base::starknet_messages::AssetId;
+use rust_crypto_lib_base::starknet_messages::Order;
+use rust_crypto_lib_base::starknet_messages::PositionId;
+use rust_crypto_lib_base::starknet_messages::Timestamp;
+use starknet_crypto::Felt;
+
+// Fixed layout of a signed order, big-endian throughout:
+//   position_id u32 | base_asset_id felt | base_amount i64 |
+//   quote_asset_id felt | quote_amount i64 | fee_asset_id felt |
+//   fee_amount u64 | expiration u64 | salt felt | user_public_key felt |
+//   message_hash felt | r felt | s felt
+pub(crate) const SIGNED_ORDER_LEN: usize = 4 + 8 * 32 + 4 * 8;
+
+pub(crate) struct SignedOrder {
+    pub(crate) order: Order,
+    pub(crate) user_public_key: Felt,
+    pub(crate) message_hash: Felt,
+    pub(crate) r: Felt,
+    pub(crate) s: Felt,
+}
+
+impl SignedOrder {
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let order = &self.order;
+        let mut bytes = Vec::with_capacity(SIGNED_ORDER_LEN);
+        bytes.extend_from_slice(&order.position_id.value.to_be_bytes());
+        bytes.extend_from_slice(&order.base_asset_id.value.to_bytes_be());
+        bytes.extend_from_slice(&order.base_amount.to_be_bytes());
+        bytes.extend_from_slice(&order.quote_asset_id.value.to_bytes_be());
+        bytes.extend_from_slice(&order.quote_amount.to_be_bytes());
+        bytes.extend_from_slice(&order.fee_asset_id.value.to_bytes_be());
+        bytes.extend_from_slice(&order.fee_amount.to_be_bytes());
+        bytes.extend_from_slice(&order.expiration.seconds.to_be_bytes());
+        bytes.extend_from_slice(&order.salt.to_bytes_be());
+        bytes.extend_from_slice(&self.user_public_key.to_bytes_be());
+        bytes.extend_from_slice(&self.message_hash.to_bytes_be());
+        bytes.extend_from_slice(&self.r.to_bytes_be());
+        bytes.extend_from_slice(&self.s.to_bytes_be());
+        bytes
+    }
+
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != SIGNED_ORDER_LEN {
+            return Err(format!(
+                "Signed order must be {} bytes, got {}",
+                SIGNED_ORDER_LEN,
+                bytes.len()
+            ));
+        }
+        let mut reader = Reader { bytes, offset: 0 };
+        let position_id = u32::from_be_bytes(reader.take());
+        let base_asset_id = reader.felt("base_asset_id")?;
+        let base_amount = i64::from_be_bytes(reader.take());
+        let quote_asset_id = reader.felt("quote_asset_id")?;
+        let quote_amount = i64::from_be_bytes(reader.take());
+        let fee_asset_id = reader.felt("fee_asset_id")?;
+        let fee_amount = u64::from_be_bytes(reader.take());
+        let expiration = u64::from_be_bytes(reader.take());
+        let salt = reader.felt("salt")?;
+        Ok(SignedOrder {
+            order: Order {
+                position_id: PositionId { value: position_id },
+                base_asset_id: AssetId {
+                    value: base_asset_id,
+                },
+                base_amount,
+                quote_asset_id: AssetId {
+                    value: quote_asset_id,
+                },
+                quote_amount,
+                fee_asset_id: AssetId {
+                    value: fee_asset_id,
+                },
+                fee_amount,
+                expiration: Timestamp {
+                    seconds: expiration,
+                },
+                salt,
+            },
+            user_public_key: reader.felt("user_public_key")?,
+            message_hash: reader.felt("message_hash")?,
+            r: reader.felt("r")?,
+            s: reader.felt("s")?,
+        })
+    }
+}
+
+// Walks a buffer whose length has already been checked
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let mut out = [0u8; N];
+        out.copy_from_slice(&self.bytes[self.offset..self.offset + N]);
+        self.offset += N;
+        out
+    }
+
+    // Felt::from_bytes_be reduces modulo the field prime, so reject anything
+    // that doesn't encode back to the same bytes
+    fn felt(&mut self, field: &str) -> Result<Felt, String> {
+        let bytes = self.take::<32>();
+        let felt = Felt::from_bytes_be(&bytes);
+        if felt.to_bytes_be() != bytes {
+            return Err(format!("Invalid {}: not a canonical field element", field));
+        }
+        Ok(felt)
+    }
+}